@@ -0,0 +1,176 @@
+use super::{EvmPrecompileResult, Precompile};
+use crate::prelude::types::EthGas;
+use crate::prelude::{AccountId, Address};
+use crate::PrecompileOutput;
+use evm::{Context, ExitError};
+
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    /// Account IDs are at most 64 bytes long, so this is the identity precompile cost of
+    /// copying two words.
+    pub(super) const ACCOUNT_ID_GAS: EthGas = EthGas::new(21);
+}
+
+fn account_id_output(account_id: &AccountId, target_gas: Option<EthGas>) -> EvmPrecompileResult {
+    let cost = costs::ACCOUNT_ID_GAS;
+    if let Some(target_gas) = target_gas {
+        if cost > target_gas {
+            return Err(ExitError::OutOfGas);
+        }
+    }
+
+    Ok(PrecompileOutput::without_logs(cost, account_id.as_bytes().to_vec()).into())
+}
+
+pub struct PredecessorAccount {
+    predecessor_account_id: AccountId,
+}
+
+impl PredecessorAccount {
+    /// Predecessor account ID precompile address
+    /// Returns the UTF-8 bytes of the NEAR account which called the engine.
+    ///
+    /// Address: `0x723ffbaba940e75e7bf5f6d61dcbf8d9a4de0fd7`
+    /// This address is computed as: `&keccak("predecessorAccountId")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0x723ffbab, 0xa940e75e7bf5f6d61dcbf8d9a4de0fd7);
+
+    pub fn new(predecessor_account_id: AccountId) -> Self {
+        Self {
+            predecessor_account_id,
+        }
+    }
+}
+
+impl Precompile for PredecessorAccount {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::ACCOUNT_ID_GAS)
+    }
+
+    fn run(
+        &self,
+        _input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        account_id_output(&self.predecessor_account_id, target_gas)
+    }
+}
+
+pub struct SignerAccount {
+    signer_account_id: AccountId,
+}
+
+impl SignerAccount {
+    /// Signer account ID precompile address
+    /// Returns the UTF-8 bytes of the NEAR account which signed the transaction.
+    ///
+    /// Address: `0xdca2f82bf7d717dc34102f3748fe3d685af8bf6a`
+    /// This address is computed as: `&keccak("signerAccountId")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0xdca2f82b, 0xf7d717dc34102f3748fe3d685af8bf6a);
+
+    pub fn new(signer_account_id: AccountId) -> Self {
+        Self { signer_account_id }
+    }
+}
+
+impl Precompile for SignerAccount {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::ACCOUNT_ID_GAS)
+    }
+
+    fn run(
+        &self,
+        _input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        account_id_output(&self.signer_account_id, target_gas)
+    }
+}
+
+pub struct CurrentAccount {
+    current_account_id: AccountId,
+}
+
+impl CurrentAccount {
+    /// Current account ID precompile address
+    /// Returns the UTF-8 bytes of the NEAR account the engine is deployed to.
+    ///
+    /// Address: `0xfefae79e4180eb0284f261205e3f8cea737aff56`
+    /// This address is computed as: `&keccak("currentAccountId")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0xfefae79e, 0x4180eb0284f261205e3f8cea737aff56);
+
+    pub fn new(current_account_id: AccountId) -> Self {
+        Self { current_account_id }
+    }
+}
+
+impl Precompile for CurrentAccount {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::ACCOUNT_ID_GAS)
+    }
+
+    fn run(
+        &self,
+        _input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        account_id_output(&self.current_account_id, target_gas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CurrentAccount, PredecessorAccount, SignerAccount};
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::types::EthGas;
+    use crate::utils::new_context;
+    use crate::Precompile;
+    use evm::ExitError;
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(
+            PredecessorAccount::ADDRESS,
+            near_account_to_evm_address("predecessorAccountId".as_bytes())
+        );
+        assert_eq!(
+            SignerAccount::ADDRESS,
+            near_account_to_evm_address("signerAccountId".as_bytes())
+        );
+        assert_eq!(
+            CurrentAccount::ADDRESS,
+            near_account_to_evm_address("currentAccountId".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_account_ids() {
+        let precompile = PredecessorAccount::new("relayer.near".parse().unwrap());
+        let res = precompile
+            .run(&[], Some(EthGas::new(21)), &new_context(), false)
+            .unwrap();
+        assert_eq!(res.output, b"relayer.near".to_vec());
+        assert_eq!(res.cost, 21);
+
+        let precompile = SignerAccount::new("alice.near".parse().unwrap());
+        let res = precompile.run(&[], None, &new_context(), true).unwrap();
+        assert_eq!(res.output, b"alice.near".to_vec());
+
+        let precompile = CurrentAccount::new("aurora".parse().unwrap());
+        let res = precompile.run(&[], None, &new_context(), false).unwrap();
+        assert_eq!(res.output, b"aurora".to_vec());
+
+        // gas fail
+        let res = precompile.run(&[], Some(EthGas::new(20)), &new_context(), false);
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+    }
+}
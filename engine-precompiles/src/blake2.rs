@@ -24,7 +24,7 @@ impl Blake2F {
 }
 
 impl Precompile for Blake2F {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         let (int_bytes, _) = input.split_at(mem::size_of::<u32>());
         let num_rounds = u32::from_be_bytes(
             // Unwrap is fine here as it can not fail
@@ -54,7 +54,7 @@ impl Precompile for Blake2F {
             return Err(ExitError::Other(Borrowed("ERR_BLAKE2F_INVALID_LEN")));
         }
 
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -0,0 +1,947 @@
+use crate::prelude::types::EthGas;
+use crate::prelude::{Address, Borrowed, Vec};
+use crate::{EvmPrecompileResult, Precompile, PrecompileOutput};
+use ::bls12_381::hash_to_curve::MapToCurve;
+use ::bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use evm::{Context, ExitError};
+
+type Fp = <G1Projective as MapToCurve>::Field;
+type Fp2 = <G2Projective as MapToCurve>::Field;
+
+/// BLS12-381 costs.
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    /// Cost of the G1 addition operation.
+    pub(super) const G1_ADD: EthGas = EthGas::new(375);
+
+    /// Cost of the G1 multiplication operation, and of a single multiplication in a G1
+    /// multi-scalar multiplication.
+    pub(super) const G1_MUL: EthGas = EthGas::new(12_000);
+
+    /// Cost of the G2 addition operation.
+    pub(super) const G2_ADD: EthGas = EthGas::new(600);
+
+    /// Cost of the G2 multiplication operation, and of a single multiplication in a G2
+    /// multi-scalar multiplication.
+    pub(super) const G2_MUL: EthGas = EthGas::new(22_500);
+
+    /// Cost of the pairing check per pair.
+    pub(super) const PAIRING_PER_PAIR: EthGas = EthGas::new(32_600);
+
+    /// Base cost of the pairing check operation.
+    pub(super) const PAIRING_BASE: EthGas = EthGas::new(37_700);
+
+    /// Cost of the field element to G1 mapping operation.
+    pub(super) const MAP_FP_TO_G1: EthGas = EthGas::new(5_500);
+
+    /// Cost of the Fp2 element to G2 mapping operation.
+    pub(super) const MAP_FP2_TO_G2: EthGas = EthGas::new(23_800);
+
+    /// Divisor of the multi-scalar multiplication discounts.
+    pub(super) const MSM_DISCOUNT_MULTIPLIER: u64 = 1_000;
+
+    /// G1 multi-scalar multiplication discount per number of pairs `k`. Pairs over the
+    /// length of the table get the last discount.
+    pub(super) const G1_MSM_DISCOUNT: [u64; 128] = [
+        1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669,
+        665, 661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619,
+        617, 615, 613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589,
+        588, 586, 585, 584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567,
+        566, 565, 564, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549,
+        548, 547, 547, 546, 545, 544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534,
+        533, 532, 532, 531, 530, 529, 528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520,
+        520, 519,
+    ];
+
+    /// G2 multi-scalar multiplication discount per number of pairs `k`. Pairs over the
+    /// length of the table get the last discount.
+    pub(super) const G2_MSM_DISCOUNT: [u64; 128] = [
+        1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+        699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634,
+        632, 629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597,
+        595, 593, 592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571,
+        570, 569, 568, 567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552,
+        552, 551, 550, 549, 548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537,
+        537, 536, 535, 535, 534, 533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525,
+        524, 524,
+    ];
+}
+
+/// BLS12-381 constants.
+mod consts {
+    /// Length of a padded field element.
+    pub(super) const FP_LEN: usize = 64;
+
+    /// Number of zero bytes a field element is padded with.
+    pub(super) const FP_PADDING_LEN: usize = 16;
+
+    /// Length of an encoded Fp2 element.
+    pub(super) const FP2_LEN: usize = 2 * FP_LEN;
+
+    /// Length of an encoded G1 point.
+    pub(super) const G1_LEN: usize = 2 * FP_LEN;
+
+    /// Length of an encoded G2 point.
+    pub(super) const G2_LEN: usize = 2 * FP2_LEN;
+
+    /// Length of an encoded scalar.
+    pub(super) const SCALAR_LEN: usize = 32;
+
+    /// Length of a G1 point and scalar pair.
+    pub(super) const G1_MUL_LEN: usize = G1_LEN + SCALAR_LEN;
+
+    /// Length of a G2 point and scalar pair.
+    pub(super) const G2_MUL_LEN: usize = G2_LEN + SCALAR_LEN;
+
+    /// Length of a G1 and G2 point pair.
+    pub(super) const PAIR_LEN: usize = G1_LEN + G2_LEN;
+}
+
+/// Reads a padded big-endian field element. The top 16 bytes must be zero and the value must
+/// be less than the modulus.
+fn read_fp(input: &[u8]) -> Result<[u8; 48], ExitError> {
+    if input[..consts::FP_PADDING_LEN].iter().any(|b| *b != 0) {
+        return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_FP")));
+    }
+    let mut buf = [0u8; 48];
+    buf.copy_from_slice(&input[consts::FP_PADDING_LEN..consts::FP_LEN]);
+    if Option::<Fp>::from(Fp::from_bytes(&buf)).is_none() {
+        return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_FP")));
+    }
+    Ok(buf)
+}
+
+/// Reads an Fp2 element, encoded as `c0 || c1`.
+fn read_fp2(input: &[u8]) -> Result<Fp2, ExitError> {
+    let c0 = read_fp(&input[..consts::FP_LEN])?;
+    let c1 = read_fp(&input[consts::FP_LEN..consts::FP2_LEN])?;
+    // Both elements are known to be canonical at this point.
+    Ok(Fp2 {
+        c0: Fp::from_bytes(&c0).unwrap(),
+        c1: Fp::from_bytes(&c1).unwrap(),
+    })
+}
+
+/// Reads a G1 point. The point at infinity is encoded as all zeros.
+fn read_g1(input: &[u8], subgroup_check: bool) -> Result<G1Affine, ExitError> {
+    let x = read_fp(&input[..consts::FP_LEN])?;
+    let y = read_fp(&input[consts::FP_LEN..consts::G1_LEN])?;
+
+    let point = if x == [0u8; 48] && y == [0u8; 48] {
+        G1Affine::identity()
+    } else {
+        let mut buf = [0u8; 96];
+        buf[..48].copy_from_slice(&x);
+        buf[48..].copy_from_slice(&y);
+        // Canonical field elements leave the flag bits unset.
+        Option::<G1Affine>::from(G1Affine::from_uncompressed_unchecked(&buf))
+            .filter(|p| bool::from(p.is_on_curve()))
+            .ok_or(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_POINT")))?
+    };
+
+    if subgroup_check && !bool::from(point.is_torsion_free()) {
+        return Err(ExitError::Other(Borrowed("ERR_BLS12_381_NOT_IN_SUBGROUP")));
+    }
+    Ok(point)
+}
+
+/// Reads a G2 point. The point at infinity is encoded as all zeros.
+fn read_g2(input: &[u8], subgroup_check: bool) -> Result<G2Affine, ExitError> {
+    let x_c0 = read_fp(&input[..consts::FP_LEN])?;
+    let x_c1 = read_fp(&input[consts::FP_LEN..(2 * consts::FP_LEN)])?;
+    let y_c0 = read_fp(&input[(2 * consts::FP_LEN)..(3 * consts::FP_LEN)])?;
+    let y_c1 = read_fp(&input[(3 * consts::FP_LEN)..consts::G2_LEN])?;
+
+    let point = if [x_c0, x_c1, y_c0, y_c1].iter().all(|c| c == &[0u8; 48]) {
+        G2Affine::identity()
+    } else {
+        // The uncompressed encoding of `bls12_381` puts `c1` before `c0`.
+        let mut buf = [0u8; 192];
+        buf[..48].copy_from_slice(&x_c1);
+        buf[48..96].copy_from_slice(&x_c0);
+        buf[96..144].copy_from_slice(&y_c1);
+        buf[144..].copy_from_slice(&y_c0);
+        Option::<G2Affine>::from(G2Affine::from_uncompressed_unchecked(&buf))
+            .filter(|p| bool::from(p.is_on_curve()))
+            .ok_or(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_POINT")))?
+    };
+
+    if subgroup_check && !bool::from(point.is_torsion_free()) {
+        return Err(ExitError::Other(Borrowed("ERR_BLS12_381_NOT_IN_SUBGROUP")));
+    }
+    Ok(point)
+}
+
+/// Reads a big-endian scalar. Values over the group order are reduced.
+fn read_scalar(input: &[u8]) -> Scalar {
+    let mut buf = [0u8; 64];
+    for (dst, src) in buf.iter_mut().zip(input[..consts::SCALAR_LEN].iter().rev()) {
+        *dst = *src;
+    }
+    Scalar::from_bytes_wide(&buf)
+}
+
+fn encode_g1(point: G1Affine) -> Vec<u8> {
+    let mut output = [0u8; consts::G1_LEN];
+    if !bool::from(point.is_identity()) {
+        let bytes = point.to_uncompressed();
+        output[consts::FP_PADDING_LEN..consts::FP_LEN].copy_from_slice(&bytes[..48]);
+        output[(consts::FP_LEN + consts::FP_PADDING_LEN)..].copy_from_slice(&bytes[48..]);
+    }
+    output.to_vec()
+}
+
+fn encode_g2(point: G2Affine) -> Vec<u8> {
+    let mut output = [0u8; consts::G2_LEN];
+    if !bool::from(point.is_identity()) {
+        let bytes = point.to_uncompressed();
+        // Swap each `c1 || c0` pair back into `c0 || c1`.
+        for (i, chunk) in [&bytes[48..96], &bytes[..48], &bytes[144..], &bytes[96..144]]
+            .iter()
+            .enumerate()
+        {
+            let start = i * consts::FP_LEN + consts::FP_PADDING_LEN;
+            output[start..(start + 48)].copy_from_slice(chunk);
+        }
+    }
+    output.to_vec()
+}
+
+/// Multi-scalar multiplication cost for `k` pairs with the given multiplication cost and
+/// discount table.
+fn msm_gas(k: usize, mul_cost: EthGas, discounts: &[u64]) -> EthGas {
+    if k == 0 {
+        return EthGas::new(0);
+    }
+    let discount = discounts[k.min(discounts.len()) - 1];
+    EthGas::new((mul_cost * k).into_u64() * discount / costs::MSM_DISCOUNT_MULTIPLIER)
+}
+
+fn check_gas(cost: EthGas, target_gas: Option<EthGas>) -> Result<(), ExitError> {
+    if let Some(target_gas) = target_gas {
+        if cost > target_gas {
+            return Err(ExitError::OutOfGas);
+        }
+    }
+    Ok(())
+}
+
+pub struct Bls12381G1Add;
+
+impl Bls12381G1Add {
+    pub const ADDRESS: Address = super::make_address(0, 0xb);
+}
+
+impl Precompile for Bls12381G1Add {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::G1_ADD)
+    }
+
+    /// Takes in two G1 points and calculates their sum. No subgroup checks are performed.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.len() != 2 * consts::G1_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let p1 = read_g1(&input[..consts::G1_LEN], false)?;
+        let p2 = read_g1(&input[consts::G1_LEN..], false)?;
+
+        let output = encode_g1((G1Projective::from(p1) + p2).into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381G1Mul;
+
+impl Bls12381G1Mul {
+    /// The final EIP-2537 has no G1 multiplication at its own address, so it follows the
+    /// map-to-curve operations.
+    pub const ADDRESS: Address = super::make_address(0, 0x12);
+}
+
+impl Precompile for Bls12381G1Mul {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::G1_MUL)
+    }
+
+    /// Takes in a G1 point and a scalar and calculates their product, the same as a G1
+    /// multi-scalar multiplication with a single pair.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.len() != consts::G1_MUL_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let p = read_g1(&input[..consts::G1_LEN], true)?;
+        let s = read_scalar(&input[consts::G1_LEN..]);
+
+        let output = encode_g1((p * s).into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381G1MultiExp;
+
+impl Bls12381G1MultiExp {
+    pub const ADDRESS: Address = super::make_address(0, 0xc);
+}
+
+impl Precompile for Bls12381G1MultiExp {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(msm_gas(
+            input.len() / consts::G1_MUL_LEN,
+            costs::G1_MUL,
+            &costs::G1_MSM_DISCOUNT,
+        ))
+    }
+
+    /// Takes in a list of G1 point and scalar pairs and calculates the sum of their products.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.is_empty() || input.len() % consts::G1_MUL_LEN != 0 {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let mut acc = G1Projective::identity();
+        for chunk in input.chunks(consts::G1_MUL_LEN) {
+            let p = read_g1(&chunk[..consts::G1_LEN], true)?;
+            let s = read_scalar(&chunk[consts::G1_LEN..]);
+            acc += p * s;
+        }
+
+        let output = encode_g1(acc.into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381G2Add;
+
+impl Bls12381G2Add {
+    pub const ADDRESS: Address = super::make_address(0, 0xd);
+}
+
+impl Precompile for Bls12381G2Add {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::G2_ADD)
+    }
+
+    /// Takes in two G2 points and calculates their sum. No subgroup checks are performed.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.len() != 2 * consts::G2_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let p1 = read_g2(&input[..consts::G2_LEN], false)?;
+        let p2 = read_g2(&input[consts::G2_LEN..], false)?;
+
+        let output = encode_g2((G2Projective::from(p1) + p2).into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381G2Mul;
+
+impl Bls12381G2Mul {
+    /// The final EIP-2537 has no G2 multiplication at its own address, so it follows the
+    /// G1 multiplication.
+    pub const ADDRESS: Address = super::make_address(0, 0x13);
+}
+
+impl Precompile for Bls12381G2Mul {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::G2_MUL)
+    }
+
+    /// Takes in a G2 point and a scalar and calculates their product, the same as a G2
+    /// multi-scalar multiplication with a single pair.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.len() != consts::G2_MUL_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let p = read_g2(&input[..consts::G2_LEN], true)?;
+        let s = read_scalar(&input[consts::G2_LEN..]);
+
+        let output = encode_g2((p * s).into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381G2MultiExp;
+
+impl Bls12381G2MultiExp {
+    pub const ADDRESS: Address = super::make_address(0, 0xe);
+}
+
+impl Precompile for Bls12381G2MultiExp {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(msm_gas(
+            input.len() / consts::G2_MUL_LEN,
+            costs::G2_MUL,
+            &costs::G2_MSM_DISCOUNT,
+        ))
+    }
+
+    /// Takes in a list of G2 point and scalar pairs and calculates the sum of their products.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.is_empty() || input.len() % consts::G2_MUL_LEN != 0 {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let mut acc = G2Projective::identity();
+        for chunk in input.chunks(consts::G2_MUL_LEN) {
+            let p = read_g2(&chunk[..consts::G2_LEN], true)?;
+            let s = read_scalar(&chunk[consts::G2_LEN..]);
+            acc += p * s;
+        }
+
+        let output = encode_g2(acc.into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381Pairing;
+
+impl Bls12381Pairing {
+    pub const ADDRESS: Address = super::make_address(0, 0xf);
+}
+
+impl Precompile for Bls12381Pairing {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::PAIRING_PER_PAIR * (input.len() / consts::PAIR_LEN) + costs::PAIRING_BASE)
+    }
+
+    /// Takes in a list of G1 and G2 point pairs and checks that the product of their pairings
+    /// is the identity. Returns `1` as a 32-byte word if so, `0` otherwise.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.is_empty() || input.len() % consts::PAIR_LEN != 0 {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let mut pairs = Vec::with_capacity(input.len() / consts::PAIR_LEN);
+        for chunk in input.chunks(consts::PAIR_LEN) {
+            let a = read_g1(&chunk[..consts::G1_LEN], true)?;
+            let b = read_g2(&chunk[consts::G1_LEN..], true)?;
+            pairs.push((a, G2Prepared::from(b)));
+        }
+        let terms: Vec<(&G1Affine, &G2Prepared)> = pairs.iter().map(|(a, b)| (a, b)).collect();
+
+        let mut output = [0u8; 32];
+        if multi_miller_loop(&terms).final_exponentiation() == Gt::identity() {
+            output[31] = 1;
+        }
+        Ok(PrecompileOutput::without_logs(cost, output.to_vec()).into())
+    }
+}
+
+pub struct Bls12381MapG1;
+
+impl Bls12381MapG1 {
+    pub const ADDRESS: Address = super::make_address(0, 0x10);
+}
+
+impl Precompile for Bls12381MapG1 {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::MAP_FP_TO_G1)
+    }
+
+    /// Maps a field element to a G1 point, using the simplified SWU map followed by cofactor
+    /// clearing.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.len() != consts::FP_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let fp = Fp::from_bytes(&read_fp(input)?).unwrap();
+
+        let output = encode_g1(G1Projective::map_to_curve(&fp).clear_h().into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+pub struct Bls12381MapG2;
+
+impl Bls12381MapG2 {
+    pub const ADDRESS: Address = super::make_address(0, 0x11);
+}
+
+impl Precompile for Bls12381MapG2 {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::MAP_FP2_TO_G2)
+    }
+
+    /// Maps an Fp2 element to a G2 point, using the simplified SWU map followed by cofactor
+    /// clearing.
+    ///
+    /// See: https://eips.ethereum.org/EIPS/eip-2537
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        check_gas(cost, target_gas)?;
+
+        if input.len() != consts::FP2_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")));
+        }
+        let fp2 = read_fp2(input)?;
+
+        let output = encode_g2(G2Projective::map_to_curve(&fp2).clear_h().into());
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::new_context;
+
+    use super::*;
+
+    /// The G1 generator.
+    const G1: &str = "\
+        0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0f\
+        c3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+        0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4\
+        fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1";
+
+    /// The G1 generator times 2.
+    const G1_X2: &str = "\
+        000000000000000000000000000000000572cbea904d67468808c8eb50a9450c\
+        9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e\
+        00000000000000000000000000000000166a9d8cabc673a322fda673779d8e38\
+        22ba3ecb8670e461f73bb9021d5fd76a4c56d9d4cd16bd1bba86881979749d28";
+
+    /// The G1 generator times 3.
+    const G1_X3: &str = "\
+        0000000000000000000000000000000009ece308f9d1f0131765212deca99697\
+        b112d61f9be9a5f1f3780a51335b3ff981747a0b2ca2179b96d2c0c9024e5224\
+        00000000000000000000000000000000032b80d3a6f5b09f8a84623389c5f80c\
+        a69a0cddabc3097f9d9c27310fd43be6e745256c634af45ca3473b0590ae30d1";
+
+    /// The G2 generator.
+    const G2: &str = "\
+        00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051\
+        c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8\
+        0000000000000000000000000000000013e02b6052719f607dacd3a088274f65\
+        596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e\
+        000000000000000000000000000000000ce5d527727d6e118cc9cdc6da2e351a\
+        adfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801\
+        000000000000000000000000000000000606c4a02ea734cc32acd2b02bc28b99\
+        cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be";
+
+    /// The G2 generator times 2.
+    const G2_X2: &str = "\
+        000000000000000000000000000000001638533957d540a9d2370f17cc7ed586\
+        3bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053\
+        000000000000000000000000000000000a4edef9c1ed7f729f520e47730a124f\
+        d70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c33577\
+        000000000000000000000000000000000468fb440d82b0630aeb8dca2b525678\
+        9a66da69bf91009cbfe6bd221e47aa8ae88dece9764bf3bd999d95d71e4c9899\
+        000000000000000000000000000000000f6d4552fa65dd2638b361543f887136\
+        a43253d9c66c411697003f7a13c308f5422e1aa0a59c8967acdefd8b6e36ccf3";
+
+    /// The G2 generator times 3.
+    const G2_X3: &str = "\
+        00000000000000000000000000000000122915c824a0857e2ee414a3dccb23ae\
+        691ae54329781315a0c75df1c04d6d7a50a030fc866f09d516020ef82324afae\
+        0000000000000000000000000000000009380275bbc8e5dcea7dc4dd7e0550ff\
+        2ac480905396eda55062650f8d251c96eb480673937cc6d9d6a44aaa56ca66dc\
+        000000000000000000000000000000000b21da7955969e61010c7a1abc1a6f01\
+        36961d1e3b20b1a7326ac738fef5c721479dfd948b52fdf2455e44813ecfd892\
+        0000000000000000000000000000000008f239ba329b3967fe48d718a36cfe5f\
+        62a7e42e0bf1c1ed714150a166bfbd6bcf6b3b58b975b9edea56d53f23a0e849";
+
+    /// The G1 generator times -6.
+    const G1_NEG_X6: &str = "\
+        0000000000000000000000000000000006e82f6da4520f85c5d27d8f329eccfa\
+        05944fd1096b20734c894966d12a9e2a9a9744529d7212d33883113a0cadb909\
+        00000000000000000000000000000000022901b141a9daabba0acdf56c7a9ca7\
+        819db2bb9b92848d7b0885e0b57c1695d6c307cebda4d19f13259775ba9c632f";
+
+    const MAP_G1_INPUT: &str = "\
+        0000000000000000000000000000000011000000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000007";
+
+    const MAP_G1_OUTPUT: &str = "\
+        00000000000000000000000000000000166a7a59662deb6695eeb2c0df2f1a43\
+        a431ed42b5c331805a7cf8d919ab104ae7381630b1c45e703a12180fdecd15cb\
+        000000000000000000000000000000001272b70e341d060e5b87b0d84fefcce5\
+        e82ecbda7410e72b735b5ccf9d6ae6c5192c2f609b1f8ce81d10758a64f6fdc5";
+
+    const MAP_G2_INPUT: &str = "\
+        0000000000000000000000000000000011000000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000007\
+        0000000000000000000000000000000000220000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000009";
+
+    const MAP_G2_OUTPUT: &str = "\
+        0000000000000000000000000000000017e7aaf7bdd420016e5408dd8d268a85\
+        18c380d4a718a904701eb54ea2432627fac3e2a3414f22bcfc41a7fbbffbc347\
+        000000000000000000000000000000000e78c6e17403c7d2d22461e8be33b5e0\
+        fe878f5db70338cbf7d71eee948eb1a685570f60e86d204d9f1eed50db384f9c\
+        00000000000000000000000000000000000a04685a1140657fe5f4c42c2e4471\
+        d8d7ed41df2d3915daabba0b89809135ef41736f150359e43a0c0db35d8b4756\
+        0000000000000000000000000000000002c5d05551f23a762b49f19b7ac42e0b\
+        5a3de945664cdd02af7b59eeda2f3987c8acbddafc28a1d646ce759eccdedc4d";
+
+    /// A point on the G1 curve which is not in the prime order subgroup.
+    const G1_NOT_IN_SUBGROUP: &str = "\
+        0000000000000000000000000000000016c5f47d99ffff8a7abc0af6db6347c0\
+        bb972bdd98bf7a05d2b5f25b9a2c50ced825e5a3c6ee82700a7b82d641dbafb6\
+        00000000000000000000000000000000062911058ec8e40a47e5ec9bae09fe94\
+        e5c2efa42437deec64c49a15b8956489255834e48816f5ea13731bf5653c16c2";
+
+    fn scalar(n: u8) -> Vec<u8> {
+        let mut word = [0u8; 32];
+        word[31] = n;
+        word.to_vec()
+    }
+
+    fn input(parts: &[&str]) -> Vec<u8> {
+        parts.iter().flat_map(|p| hex::decode(p).unwrap()).collect()
+    }
+
+    fn run<P: Precompile>(precompile: P, input: &[u8]) -> Result<Vec<u8>, ExitError> {
+        precompile
+            .run(input, None, &new_context(), false)
+            .map(|res| res.output)
+    }
+
+    #[test]
+    fn test_g1_add() {
+        let res = run(Bls12381G1Add, &input(&[G1, G1_X2])).unwrap();
+        assert_eq!(res, hex::decode(G1_X3).unwrap());
+
+        // identity test
+        let res = run(Bls12381G1Add, &[input(&[G1]), [0u8; 128].to_vec()].concat()).unwrap();
+        assert_eq!(res, hex::decode(G1).unwrap());
+
+        // no subgroup check
+        assert!(run(Bls12381G1Add, &input(&[G1_NOT_IN_SUBGROUP, G1])).is_ok());
+
+        // out of gas test
+        let res = Bls12381G1Add.run(
+            &input(&[G1, G1_X2]),
+            Some(EthGas::new(374)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+
+        // invalid input length
+        let res = run(Bls12381G1Add, &input(&[G1]));
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")))
+        ));
+    }
+
+    #[test]
+    fn test_g1_mul() {
+        let mul_input = [input(&[G1]), scalar(3)].concat();
+        let res = run(Bls12381G1Mul, &mul_input).unwrap();
+        assert_eq!(res, hex::decode(G1_X3).unwrap());
+        assert_eq!(
+            Bls12381G1Mul.required_gas(&mul_input).unwrap(),
+            EthGas::new(12_000)
+        );
+
+        // the same as a multi-scalar multiplication with one pair
+        let res = run(Bls12381G1MultiExp, &mul_input).unwrap();
+        assert_eq!(res, hex::decode(G1_X3).unwrap());
+        assert_eq!(
+            Bls12381G1MultiExp.required_gas(&mul_input).unwrap(),
+            EthGas::new(12_000)
+        );
+
+        // multiplication by zero
+        let res = run(Bls12381G1Mul, &[input(&[G1]), scalar(0)].concat()).unwrap();
+        assert_eq!(res, [0u8; 128].to_vec());
+
+        // out of gas test
+        let res = Bls12381G1Mul.run(&mul_input, Some(EthGas::new(11_999)), &new_context(), false);
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+
+        // invalid input length
+        let res = run(Bls12381G1Mul, &[mul_input.clone(), scalar(1)].concat());
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")))
+        ));
+
+        // subgroup check
+        let res = run(
+            Bls12381G1Mul,
+            &[input(&[G1_NOT_IN_SUBGROUP]), scalar(3)].concat(),
+        );
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_NOT_IN_SUBGROUP")))
+        ));
+
+        // point not on curve fail
+        let mut point = [0u8; 160];
+        point[63] = 1;
+        point[127] = 1;
+        let res = run(Bls12381G1Mul, &point);
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_POINT")))
+        ));
+
+        // invalid field element padding
+        let mut point = [input(&[G1]), scalar(3)].concat();
+        point[0] = 1;
+        let res = run(Bls12381G1Mul, &point);
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_FP")))
+        ));
+    }
+
+    #[test]
+    fn test_g1_multiexp() {
+        let multiexp_input = [input(&[G1]), scalar(1), input(&[G1]), scalar(2)].concat();
+        let res = run(Bls12381G1MultiExp, &multiexp_input).unwrap();
+        assert_eq!(res, hex::decode(G1_X3).unwrap());
+
+        assert_eq!(
+            Bls12381G1MultiExp.required_gas(&multiexp_input).unwrap(),
+            EthGas::new(22_776)
+        );
+        assert_eq!(
+            Bls12381G1MultiExp.required_gas(&[0u8; 160 * 200]).unwrap(),
+            EthGas::new(1_245_600)
+        );
+
+        // no input test
+        let res = run(Bls12381G1MultiExp, &[]);
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")))
+        ));
+    }
+
+    #[test]
+    fn test_g2_add() {
+        let res = run(Bls12381G2Add, &input(&[G2, G2_X2])).unwrap();
+        assert_eq!(res, hex::decode(G2_X3).unwrap());
+
+        // identity test
+        let res = run(Bls12381G2Add, &[[0u8; 256].to_vec(), input(&[G2])].concat()).unwrap();
+        assert_eq!(res, hex::decode(G2).unwrap());
+
+        // out of gas test
+        let res = Bls12381G2Add.run(
+            &input(&[G2, G2_X2]),
+            Some(EthGas::new(599)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+    }
+
+    #[test]
+    fn test_g2_mul() {
+        let mul_input = [input(&[G2]), scalar(3)].concat();
+        let res = run(Bls12381G2Mul, &mul_input).unwrap();
+        assert_eq!(res, hex::decode(G2_X3).unwrap());
+        assert_eq!(
+            Bls12381G2Mul.required_gas(&mul_input).unwrap(),
+            EthGas::new(22_500)
+        );
+
+        // the same as a multi-scalar multiplication with one pair
+        let res = run(Bls12381G2MultiExp, &mul_input).unwrap();
+        assert_eq!(res, hex::decode(G2_X3).unwrap());
+        assert_eq!(
+            Bls12381G2MultiExp.required_gas(&mul_input).unwrap(),
+            EthGas::new(22_500)
+        );
+
+        // multiplication by zero
+        let res = run(Bls12381G2Mul, &[input(&[G2]), scalar(0)].concat()).unwrap();
+        assert_eq!(res, [0u8; 256].to_vec());
+
+        // out of gas test
+        let res = Bls12381G2Mul.run(&mul_input, Some(EthGas::new(22_499)), &new_context(), false);
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+
+        // invalid input length
+        let res = run(Bls12381G2Mul, &input(&[G2]));
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")))
+        ));
+        let res = run(Bls12381G2MultiExp, &input(&[G2]));
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")))
+        ));
+    }
+
+    #[test]
+    fn test_g2_multiexp() {
+        let multiexp_input = [input(&[G2]), scalar(1), input(&[G2]), scalar(2)].concat();
+        let res = run(Bls12381G2MultiExp, &multiexp_input).unwrap();
+        assert_eq!(res, hex::decode(G2_X3).unwrap());
+
+        assert_eq!(
+            Bls12381G2MultiExp.required_gas(&multiexp_input).unwrap(),
+            EthGas::new(45_000)
+        );
+    }
+
+    #[test]
+    fn test_pairing() {
+        // e(2 * G1, 3 * G2) * e(-6 * G1, G2) == 1
+        let pairing_input = input(&[G1_X2, G2_X3, G1_NEG_X6, G2]);
+        let res = run(Bls12381Pairing, &pairing_input).unwrap();
+        assert_eq!(res, scalar(1));
+        assert_eq!(
+            Bls12381Pairing.required_gas(&pairing_input).unwrap(),
+            EthGas::new(102_900)
+        );
+
+        // e(2 * G1, 3 * G2) * e(3 * G1, G2) != 1
+        let res = run(Bls12381Pairing, &input(&[G1_X2, G2_X3, G1_X3, G2])).unwrap();
+        assert_eq!(res, scalar(0));
+
+        // out of gas test
+        let res = Bls12381Pairing.run(
+            &pairing_input,
+            Some(EthGas::new(102_899)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+
+        // subgroup check
+        let res = run(Bls12381Pairing, &input(&[G1_NOT_IN_SUBGROUP, G2]));
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_NOT_IN_SUBGROUP")))
+        ));
+
+        // no input test
+        let res = run(Bls12381Pairing, &[]);
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_LEN")))
+        ));
+    }
+
+    #[test]
+    fn test_map_g1() {
+        let res = run(Bls12381MapG1, &input(&[MAP_G1_INPUT])).unwrap();
+        assert_eq!(res, hex::decode(MAP_G1_OUTPUT).unwrap());
+
+        // field element not less than the modulus
+        let mut fp = [0xffu8; 64];
+        fp[..16].copy_from_slice(&[0u8; 16]);
+        let res = run(Bls12381MapG1, &fp);
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_BLS12_381_INVALID_FP")))
+        ));
+    }
+
+    #[test]
+    fn test_map_g2() {
+        let res = run(Bls12381MapG2, &input(&[MAP_G2_INPUT])).unwrap();
+        assert_eq!(res, hex::decode(MAP_G2_OUTPUT).unwrap());
+
+        // out of gas test
+        let res = Bls12381MapG2.run(
+            &input(&[MAP_G2_INPUT]),
+            Some(EthGas::new(23_799)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+    }
+}
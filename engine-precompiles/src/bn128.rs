@@ -100,7 +100,7 @@ impl<HF: HardFork> Bn128Add<HF> {
 }
 
 impl Precompile for Bn128Add<Byzantium> {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(costs::BYZANTIUM_ADD)
     }
 
@@ -116,7 +116,7 @@ impl Precompile for Bn128Add<Byzantium> {
         context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -129,7 +129,7 @@ impl Precompile for Bn128Add<Byzantium> {
 }
 
 impl Precompile for Bn128Add<Istanbul> {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(costs::ISTANBUL_ADD)
     }
 
@@ -145,7 +145,7 @@ impl Precompile for Bn128Add<Istanbul> {
         context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -192,7 +192,7 @@ impl<HF: HardFork> Bn128Mul<HF> {
 }
 
 impl Precompile for Bn128Mul<Byzantium> {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(costs::BYZANTIUM_MUL)
     }
 
@@ -207,7 +207,7 @@ impl Precompile for Bn128Mul<Byzantium> {
         context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -220,7 +220,7 @@ impl Precompile for Bn128Mul<Byzantium> {
 }
 
 impl Precompile for Bn128Mul<Istanbul> {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(costs::ISTANBUL_MUL)
     }
 
@@ -235,7 +235,7 @@ impl Precompile for Bn128Mul<Istanbul> {
         context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -352,7 +352,7 @@ impl<HF: HardFork> Bn128Pair<HF> {
 }
 
 impl Precompile for Bn128Pair<Byzantium> {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(
             costs::BYZANTIUM_PAIR_PER_POINT * input.len() / consts::PAIR_ELEMENT_LEN
                 + costs::BYZANTIUM_PAIR_BASE,
@@ -370,7 +370,7 @@ impl Precompile for Bn128Pair<Byzantium> {
         context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -383,7 +383,7 @@ impl Precompile for Bn128Pair<Byzantium> {
 }
 
 impl Precompile for Bn128Pair<Istanbul> {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(
             costs::ISTANBUL_PAIR_PER_POINT * input.len() / consts::PAIR_ELEMENT_LEN
                 + costs::ISTANBUL_PAIR_BASE,
@@ -401,7 +401,7 @@ impl Precompile for Bn128Pair<Istanbul> {
         context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -0,0 +1,418 @@
+use super::{EvmPrecompileResult, Precompile};
+use crate::near_gas::{self, PROMISE_CREATE_GAS};
+use crate::prelude::{
+    format,
+    parameters::{
+        CrossContractCallArgs, CrossContractCallPolicy, EvmCallbackCallArgs, PromiseArgs,
+        PromiseCreateArgs, PromiseWithCallbackArgs,
+    },
+    sdk::io::{StorageIntermediate, IO},
+    storage::{bytes_to_key, KeyPrefix},
+    types::{EthGas, NearGas},
+    vec, BorshDeserialize, BorshSerialize, Cow, ToString, TryFrom, Vec, U256,
+};
+use crate::prelude::{AccountId, Address};
+use crate::PrecompileOutput;
+use evm::backend::Log;
+use evm::{Context, ExitError};
+
+/// NEAR cross-contract call precompile address
+///
+/// Address: `0x516cded1d16af10cad47d6d49128e2eb7d27b372`
+/// This address is computed as: `&keccak("nearCrossContractCall")[12..]`
+pub const ADDRESS: Address = super::make_address(0x516cded1, 0xd16af10cad47d6d49128e2eb7d27b372);
+
+/// Name of the engine method which delivers promise results back to the EVM.
+pub const EVM_CALLBACK_METHOD: &str = "evm_callback";
+
+/// Name of the router method which executes a promise on behalf of its EVM address.
+pub const ROUTER_EXECUTE_METHOD: &str = "execute";
+
+/// Key under which the cross-contract call policy is kept in the engine config storage.
+const POLICY_KEY: &[u8] = b"XCC_POLICY";
+
+/// Key under which the router contract code is kept in the engine config storage.
+const ROUTER_CODE_KEY: &[u8] = b"XCC_ROUTER_CODE";
+
+/// Prefix of the engine config keys marking the EVM addresses with a deployed router.
+const ROUTER_DEPLOYED_PREFIX: &[u8] = b"XCC_ROUTER";
+
+mod costs {
+    use crate::prelude::types::NearGas;
+
+    /// NEAR gas used by the router to execute a promise, on top of the gas attached to it.
+    pub(super) const ROUTER_EXECUTE_GAS: NearGas = NearGas::new(10_000_000_000_000);
+
+    /// NEAR gas used by the router to unwrap the wNEAR covering the NEAR attached to a promise.
+    pub(super) const ROUTER_UNWRAP_GAS: NearGas = NearGas::new(20_000_000_000_000);
+}
+
+/// NEAR gas attached to the router receipt which executes the given promise.
+fn router_gas(promise: &PromiseCreateArgs) -> u64 {
+    let unwrap_gas = if promise.attached_balance > 0 {
+        costs::ROUTER_UNWRAP_GAS.into_u64()
+    } else {
+        0
+    };
+    promise
+        .attached_gas
+        .saturating_add(costs::ROUTER_EXECUTE_GAS.into_u64())
+        .saturating_add(unwrap_gas)
+}
+
+/// NEAR gas attached to and burnt by the promises created for a cross-contract call.
+fn cross_contract_call_gas(args: &CrossContractCallArgs) -> NearGas {
+    let callback_gas = args
+        .callback
+        .as_ref()
+        .map(|callback| {
+            PROMISE_CREATE_GAS
+                .into_u64()
+                .saturating_add(callback.attached_gas)
+        })
+        .unwrap_or(0);
+    NearGas::new(
+        PROMISE_CREATE_GAS
+            .into_u64()
+            .saturating_add(router_gas(&args.promise))
+            .saturating_add(callback_gas),
+    )
+}
+
+fn parse_args(input: &[u8]) -> Result<CrossContractCallArgs, ExitError> {
+    CrossContractCallArgs::try_from_slice(input)
+        .map_err(|_| ExitError::Other(Cow::from("ERR_INVALID_XCC_INPUT")))
+}
+
+pub fn get_policy<I: IO>(io: &I) -> CrossContractCallPolicy {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, POLICY_KEY))
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+pub fn set_policy<I: IO>(io: &mut I, policy: &CrossContractCallPolicy) {
+    io.write_storage(
+        &bytes_to_key(KeyPrefix::Config, POLICY_KEY),
+        &policy.try_to_vec().expect("ERR_SER"),
+    );
+}
+
+/// The account which executes the promises of the given EVM address: a sub-account of
+/// the engine named after the address in hex.
+pub fn router_account_id(
+    address: &Address,
+    current_account_id: &AccountId,
+) -> Result<AccountId, &'static str> {
+    AccountId::try_from(format!("{}.{}", hex::encode(address), current_account_id))
+        .map_err(|_| "ERR_XCC_ROUTER_ACCOUNT_ID")
+}
+
+pub fn get_router_code<I: IO>(io: &I) -> Option<Vec<u8>> {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, ROUTER_CODE_KEY))
+        .map(|value| value.to_vec())
+}
+
+pub fn set_router_code<I: IO>(io: &mut I, code: &[u8]) {
+    io.write_storage(&bytes_to_key(KeyPrefix::Config, ROUTER_CODE_KEY), code);
+}
+
+fn router_deployed_key(address: &Address) -> Vec<u8> {
+    bytes_to_key(
+        KeyPrefix::Config,
+        &[ROUTER_DEPLOYED_PREFIX, address.as_bytes()].concat(),
+    )
+}
+
+pub fn is_router_deployed<I: IO>(io: &I, address: &Address) -> bool {
+    io.storage_has_key(&router_deployed_key(address))
+}
+
+pub fn set_router_deployed<I: IO>(io: &mut I, address: &Address) {
+    io.write_storage(&router_deployed_key(address), &[1]);
+}
+
+/// Checks a promise (and the NEAR gas attached to its callback) against the owner policy.
+/// Only the accounts listed by the owner may be called. Calls back into the engine account
+/// are never allowed; promise results reach the EVM through the callback instead.
+pub fn check_policy(
+    policy: &CrossContractCallPolicy,
+    promise: &PromiseCreateArgs,
+    callback_gas: u64,
+    current_account_id: &AccountId,
+) -> Result<(), &'static str> {
+    if &promise.target_account_id == current_account_id {
+        return Err("ERR_XCC_SELF_CALL");
+    }
+    if !policy.allowed_targets.contains(&promise.target_account_id) {
+        return Err("ERR_XCC_TARGET_NOT_ALLOWED");
+    }
+    if promise.attached_balance > policy.max_attached_balance {
+        return Err("ERR_XCC_ATTACHED_BALANCE");
+    }
+    match promise.attached_gas.checked_add(callback_gas) {
+        Some(gas) if gas <= policy.max_attached_gas => Ok(()),
+        _ => Err("ERR_XCC_ATTACHED_GAS"),
+    }
+}
+
+pub struct CrossContractCall<I> {
+    io: I,
+    current_account_id: AccountId,
+    eth_gas_per_tgas: u64,
+}
+
+impl<I> CrossContractCall<I> {
+    pub const ADDRESS: Address = ADDRESS;
+
+    pub fn new(io: I, current_account_id: AccountId, eth_gas_per_tgas: u64) -> Self {
+        Self {
+            io,
+            current_account_id,
+            eth_gas_per_tgas,
+        }
+    }
+}
+
+impl<I: IO> Precompile for CrossContractCall<I> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        let near_gas = parse_args(input)
+            .map(|args| cross_contract_call_gas(&args))
+            .unwrap_or(PROMISE_CREATE_GAS);
+        near_gas::to_eth_gas(near_gas, self.eth_gas_per_tgas).ok_or(ExitError::OutOfGas)
+    }
+
+    /// Input format: Borsh-encoded `CrossContractCallArgs`.
+    ///
+    /// The promise is scheduled by the engine after the transaction completes. It is executed
+    /// by the router account of the calling contract (see `router_account_id`), which must be
+    /// deployed with `deploy_xcc_router`, so the engine is never the predecessor of the call.
+    /// NEAR attached to the call is paid by the router from the wNEAR the calling contract
+    /// transferred to it, e.g. with `withdrawToNear`. If a callback is given, the promise
+    /// result is passed to the `evm_callback` method of the engine which calls the selected
+    /// method of the calling contract, with this precompile as the caller.
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        context: &Context,
+        is_static: bool,
+    ) -> EvmPrecompileResult {
+        let args = parse_args(input)?;
+        let cost = near_gas::charge(
+            cross_contract_call_gas(&args),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
+
+        // It's not allowed to create promises in static mode
+        if is_static {
+            return Err(ExitError::Other(Cow::from("ERR_INVALID_IN_STATIC")));
+        }
+
+        if context.apparent_value != U256::from(0) {
+            return Err(ExitError::Other(Cow::from("ERR_ETH_ATTACHED_FOR_XCC")));
+        }
+
+        let callback_gas = args
+            .callback
+            .as_ref()
+            .map(|callback| callback.attached_gas)
+            .unwrap_or(0);
+
+        let policy = get_policy(&self.io);
+        check_policy(
+            &policy,
+            &args.promise,
+            callback_gas,
+            &self.current_account_id,
+        )
+        .map_err(|e| ExitError::Other(Cow::from(e)))?;
+
+        let router_promise = PromiseCreateArgs {
+            target_account_id: router_account_id(&context.caller, &self.current_account_id)
+                .map_err(|e| ExitError::Other(Cow::from(e)))?,
+            method: ROUTER_EXECUTE_METHOD.to_string(),
+            args: args.promise.try_to_vec().unwrap(),
+            attached_balance: 0,
+            attached_gas: router_gas(&args.promise),
+        };
+        let promise = match args.callback {
+            None => PromiseArgs::Create(router_promise),
+            Some(callback) => {
+                let callback_args = EvmCallbackCallArgs {
+                    contract: context.caller.0,
+                    selector: callback.selector,
+                    gas_limit: callback.gas_limit,
+                };
+                PromiseArgs::Callback(PromiseWithCallbackArgs {
+                    base: router_promise,
+                    callback: PromiseCreateArgs {
+                        target_account_id: self.current_account_id.clone(),
+                        method: EVM_CALLBACK_METHOD.to_string(),
+                        args: callback_args.try_to_vec().unwrap(),
+                        attached_balance: 0,
+                        attached_gas: callback.attached_gas,
+                    },
+                })
+            }
+        };
+
+        let promise_log = Log {
+            address: Self::ADDRESS,
+            topics: Vec::new(),
+            data: promise.try_to_vec().unwrap(),
+        };
+
+        Ok(PrecompileOutput {
+            cost,
+            logs: vec![promise_log],
+            ..Default::default()
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_policy, cross_contract_call_gas, router_account_id, CrossContractCall};
+    use crate::prelude::parameters::{
+        CrossContractCallArgs, CrossContractCallPolicy, EvmCallbackArgs, PromiseCreateArgs,
+    };
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::types::NearGas;
+    use crate::prelude::{vec, AccountId, Address, Vec};
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(
+            CrossContractCall::<()>::ADDRESS,
+            near_account_to_evm_address("nearCrossContractCall".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_check_policy() {
+        let aurora: AccountId = "aurora".parse().unwrap();
+        let target: AccountId = "target.near".parse().unwrap();
+        let promise =
+            |target_account_id: &AccountId, attached_balance, attached_gas| PromiseCreateArgs {
+                target_account_id: target_account_id.clone(),
+                method: "method".into(),
+                args: Vec::new(),
+                attached_balance,
+                attached_gas,
+            };
+        let policy = CrossContractCallPolicy {
+            max_attached_balance: 10,
+            max_attached_gas: 100,
+            allowed_targets: vec![target.clone()],
+        };
+
+        assert_eq!(
+            check_policy(&policy, &promise(&target, 10, 60), 40, &aurora),
+            Ok(())
+        );
+        assert_eq!(
+            check_policy(&policy, &promise(&aurora, 0, 0), 0, &aurora),
+            Err("ERR_XCC_SELF_CALL")
+        );
+        assert_eq!(
+            check_policy(&policy, &promise(&target, 11, 0), 0, &aurora),
+            Err("ERR_XCC_ATTACHED_BALANCE")
+        );
+        assert_eq!(
+            check_policy(&policy, &promise(&target, 0, 60), 41, &aurora),
+            Err("ERR_XCC_ATTACHED_GAS")
+        );
+        assert_eq!(
+            check_policy(&policy, &promise(&target, 0, u64::MAX), 1, &aurora),
+            Err("ERR_XCC_ATTACHED_GAS")
+        );
+
+        let policy = CrossContractCallPolicy {
+            allowed_targets: vec!["other.near".parse().unwrap()],
+            ..policy
+        };
+        assert_eq!(
+            check_policy(&policy, &promise(&target, 0, 0), 0, &aurora),
+            Err("ERR_XCC_TARGET_NOT_ALLOWED")
+        );
+
+        // An empty list denies every target
+        let policy = CrossContractCallPolicy {
+            allowed_targets: Vec::new(),
+            ..policy
+        };
+        assert_eq!(
+            check_policy(&policy, &promise(&target, 0, 0), 0, &aurora),
+            Err("ERR_XCC_TARGET_NOT_ALLOWED")
+        );
+
+        // The default policy disables the precompile
+        assert_eq!(
+            check_policy(
+                &CrossContractCallPolicy::default(),
+                &promise(&target, 0, 1),
+                0,
+                &aurora
+            ),
+            Err("ERR_XCC_TARGET_NOT_ALLOWED")
+        );
+    }
+
+    #[test]
+    fn test_cross_contract_call_gas() {
+        let mut args = CrossContractCallArgs {
+            promise: PromiseCreateArgs {
+                target_account_id: "target.near".parse().unwrap(),
+                method: "method".into(),
+                args: Vec::new(),
+                attached_balance: 0,
+                attached_gas: 10_000_000_000_000,
+            },
+            callback: None,
+        };
+        assert_eq!(
+            cross_contract_call_gas(&args),
+            NearGas::new(25_000_000_000_000)
+        );
+
+        // The router unwraps wNEAR for attached NEAR
+        args.promise.attached_balance = 1;
+        assert_eq!(
+            cross_contract_call_gas(&args),
+            NearGas::new(45_000_000_000_000)
+        );
+        args.promise.attached_balance = 0;
+
+        args.callback = Some(EvmCallbackArgs {
+            selector: [0; 4],
+            gas_limit: 100_000,
+            attached_gas: 20_000_000_000_000,
+        });
+        assert_eq!(
+            cross_contract_call_gas(&args),
+            NearGas::new(50_000_000_000_000)
+        );
+
+        args.promise.attached_gas = u64::MAX;
+        assert_eq!(cross_contract_call_gas(&args), NearGas::new(u64::MAX));
+    }
+
+    #[test]
+    fn test_router_account_id() {
+        let aurora: AccountId = "aurora".parse().unwrap();
+        let address = Address::from_slice(&[0xab; 20]);
+        assert_eq!(
+            router_account_id(&address, &aurora).unwrap().as_ref(),
+            "abababababababababababababababababababab.aurora"
+        );
+
+        // Account ids are limited to 64 characters
+        let long: AccountId = "a".repeat(24).parse().unwrap();
+        assert_eq!(
+            router_account_id(&address, &long),
+            Err("ERR_XCC_ROUTER_ACCOUNT_ID")
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use crate::near_gas;
+#[cfg(all(feature = "contract", feature = "ed25519_host"))]
+use crate::prelude::sdk;
+use crate::prelude::types::{EthGas, NearGas};
+use crate::prelude::{vec, Address, Borrowed};
+use crate::{EvmPrecompileResult, Precompile, PrecompileOutput};
+use evm::{Context, ExitError};
+
+/// The costs only depend on the `ed25519_host` feature, so that the contract and the
+/// standalone engine built with the same features charge the same gas.
+mod costs {
+    use crate::prelude::types::NearGas;
+
+    /// Upper bound of the NEAR gas burnt to verify a signature with the `ed25519_verify` host
+    /// function, whose base fee is 210 Ggas, including the wasm executed around it.
+    #[cfg(feature = "ed25519_host")]
+    pub(super) const ED25519_VERIFY_BASE: NearGas = NearGas::new(250_000_000_000);
+
+    /// Upper bound of the `ed25519_verify_byte` fee of the host function, 9.06 Mgas.
+    #[cfg(feature = "ed25519_host")]
+    pub(super) const ED25519_VERIFY_PER_BYTE: NearGas = NearGas::new(10_000_000);
+
+    /// Upper bound of the NEAR gas burnt to verify a signature with ed25519-dalek in wasm,
+    /// mostly the double scalar multiplication, at 3.86 Mgas per wasm instruction.
+    #[cfg(not(feature = "ed25519_host"))]
+    pub(super) const ED25519_VERIFY_BASE: NearGas = NearGas::new(20_000_000_000_000);
+
+    /// Upper bound of the NEAR gas burnt to hash a byte of the message with SHA-512 in wasm.
+    #[cfg(not(feature = "ed25519_host"))]
+    pub(super) const ED25519_VERIFY_PER_BYTE: NearGas = NearGas::new(100_000_000);
+}
+
+mod consts {
+    pub(super) const PUBLIC_KEY_LEN: usize = 32;
+
+    pub(super) const SIGNATURE_LEN: usize = 64;
+}
+
+/// Ed25519 signature verification precompile.
+pub struct Ed25519Verify {
+    eth_gas_per_tgas: u64,
+}
+
+impl Ed25519Verify {
+    /// Ed25519 verify precompile address
+    ///
+    /// Input: the message, then the 32-byte public key and the 64-byte signature.
+    /// Output: `1` as a 32-byte word if the signature is valid, `0` otherwise.
+    ///
+    /// Address: `0xd9c4d955847ab144f000772accf7deaf0a55c036`
+    /// This address is computed as: `&keccak("ed25519Verify")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0xd9c4d955, 0x847ab144f000772accf7deaf0a55c036);
+
+    pub fn new(eth_gas_per_tgas: u64) -> Self {
+        Self { eth_gas_per_tgas }
+    }
+
+    fn near_gas(input: &[u8]) -> NearGas {
+        let message_len = input
+            .len()
+            .saturating_sub(consts::PUBLIC_KEY_LEN + consts::SIGNATURE_LEN)
+            as u64;
+        NearGas::new(
+            costs::ED25519_VERIFY_PER_BYTE.into_u64() * message_len
+                + costs::ED25519_VERIFY_BASE.into_u64(),
+        )
+    }
+
+    /// Same checks as the `ed25519_verify` host function: non-canonical `S` values are
+    /// rejected. Used in the contract too unless the `ed25519_host` feature is on, as the
+    /// pinned NEAR runtime has no such host function.
+    #[cfg(not(all(feature = "contract", feature = "ed25519_host")))]
+    fn internal_impl(public_key: &[u8; 32], signature: &[u8; 64], message: &[u8]) -> bool {
+        use ed25519_dalek::Verifier;
+
+        let public_key = match ed25519_dalek::PublicKey::from_bytes(public_key) {
+            Ok(public_key) => public_key,
+            Err(_) => return false,
+        };
+        let signature = match ed25519_dalek::Signature::from_bytes(signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+        public_key.verify(message, &signature).is_ok()
+    }
+}
+
+impl Precompile for Ed25519Verify {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        near_gas::to_eth_gas(Self::near_gas(input), self.eth_gas_per_tgas)
+            .ok_or(ExitError::OutOfGas)
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = near_gas::charge(Self::near_gas(input), self.eth_gas_per_tgas, target_gas)?;
+
+        let message_len = input
+            .len()
+            .checked_sub(consts::PUBLIC_KEY_LEN + consts::SIGNATURE_LEN)
+            .ok_or(ExitError::Other(Borrowed("ERR_ED25519_INVALID_LEN")))?;
+        let (message, input) = input.split_at(message_len);
+        let mut public_key = [0u8; consts::PUBLIC_KEY_LEN];
+        public_key.copy_from_slice(&input[..consts::PUBLIC_KEY_LEN]);
+        let mut signature = [0u8; consts::SIGNATURE_LEN];
+        signature.copy_from_slice(&input[consts::PUBLIC_KEY_LEN..]);
+
+        #[cfg(not(all(feature = "contract", feature = "ed25519_host")))]
+        let is_valid = Self::internal_impl(&public_key, &signature, message);
+        #[cfg(all(feature = "contract", feature = "ed25519_host"))]
+        let is_valid = sdk::ed25519_verify(&public_key, &signature, message);
+
+        let mut output = vec![0u8; 32];
+        output[31] = is_valid as u8;
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::near_gas::DEFAULT_ETH_GAS_PER_TGAS;
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::Vec;
+    use crate::utils::new_context;
+
+    use super::*;
+
+    fn run(public_key: &str, signature: &str, message: &str) -> Vec<u8> {
+        let input = hex::decode([message, public_key, signature].concat()).unwrap();
+        Ed25519Verify::new(DEFAULT_ETH_GAS_PER_TGAS)
+            .run(&input, None, &new_context(), false)
+            .unwrap()
+            .output
+    }
+
+    fn word(value: u8) -> Vec<u8> {
+        let mut output = vec![0u8; 32];
+        output[31] = value;
+        output
+    }
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(
+            Ed25519Verify::ADDRESS,
+            near_account_to_evm_address("ed25519Verify".as_bytes())
+        );
+    }
+
+    // Test vectors from RFC 8032, section 7.1.
+    #[test]
+    fn test_ed25519_verify() {
+        // TEST 1
+        let public_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+        let signature = "\
+            e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+            5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+        assert_eq!(run(public_key, signature, ""), word(1));
+        assert_eq!(run(public_key, signature, "72"), word(0));
+
+        // TEST 2
+        let public_key = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+        let signature = "\
+            92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+            085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00";
+        assert_eq!(run(public_key, signature, "72"), word(1));
+        assert_eq!(run(public_key, signature, "73"), word(0));
+
+        // TEST 3
+        let public_key = "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025";
+        let signature = "\
+            6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac\
+            18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a";
+        assert_eq!(run(public_key, signature, "af82"), word(1));
+
+        // wrong public key
+        let other_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+        assert_eq!(run(other_key, signature, "af82"), word(0));
+    }
+
+    #[test]
+    fn test_ed25519_verify_edge_cases() {
+        // RFC 8032 TEST 1
+        let public_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+        let r = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155";
+        let s = "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+        assert_eq!(run(public_key, &[r, s].concat(), ""), word(1));
+
+        // non-canonical `S`: the same signature with `S + L`
+        let s_plus_l = "4c8c7872aa064e049dbb3013fbf29380d25bf5f0595bbe24655141438e7a101b";
+        assert_eq!(run(public_key, &[r, s_plus_l].concat(), ""), word(0));
+
+        // `S` with the high bits set
+        let s_high_bits = "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a10eb";
+        assert_eq!(run(public_key, &[r, s_high_bits].concat(), ""), word(0));
+
+        // public key which is not a point on the curve (y = 2)
+        let invalid_key = "0200000000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(run(invalid_key, &[r, s].concat(), ""), word(0));
+    }
+
+    #[test]
+    fn test_ed25519_verify_input() {
+        let precompile = Ed25519Verify::new(DEFAULT_ETH_GAS_PER_TGAS);
+        let base_cost =
+            near_gas::to_eth_gas(costs::ED25519_VERIFY_BASE, DEFAULT_ETH_GAS_PER_TGAS).unwrap();
+        assert_eq!(precompile.required_gas(&[0u8; 96]).unwrap(), base_cost);
+        // 33 bytes of message
+        let message_cost = NearGas::new(
+            costs::ED25519_VERIFY_BASE.into_u64() + 33 * costs::ED25519_VERIFY_PER_BYTE.into_u64(),
+        );
+        assert_eq!(
+            precompile.required_gas(&[0u8; 129]).unwrap(),
+            near_gas::to_eth_gas(message_cost, DEFAULT_ETH_GAS_PER_TGAS).unwrap()
+        );
+
+        let res = precompile.run(
+            &[0u8; 96],
+            Some(EthGas::new(base_cost.into_u64() - 1)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+
+        // charged at the configured ratio
+        let precompile = Ed25519Verify::new(2 * DEFAULT_ETH_GAS_PER_TGAS);
+        let res = precompile
+            .run(&[0u8; 96], None, &new_context(), false)
+            .unwrap();
+        assert_eq!(res.cost, 2 * base_cost.into_u64());
+        assert_eq!(
+            precompile.required_gas(&[0u8; 96]).unwrap(),
+            EthGas::new(res.cost)
+        );
+
+        let res = Ed25519Verify::new(DEFAULT_ETH_GAS_PER_TGAS).run(
+            &[0u8; 95],
+            None,
+            &new_context(),
+            false,
+        );
+        assert!(matches!(
+            res,
+            Err(ExitError::Other(Borrowed("ERR_ED25519_INVALID_LEN")))
+        ));
+    }
+}
@@ -31,7 +31,7 @@ impl SHA256 {
 }
 
 impl Precompile for SHA256 {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(
             (input.len() as u64 + consts::SHA256_WORD_LEN - 1) / consts::SHA256_WORD_LEN
                 * costs::SHA256_PER_WORD
@@ -52,7 +52,7 @@ impl Precompile for SHA256 {
     ) -> EvmPrecompileResult {
         use sha2::Digest;
 
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -74,7 +74,7 @@ impl Precompile for SHA256 {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -103,7 +103,7 @@ impl RIPEMD160 {
 }
 
 impl Precompile for RIPEMD160 {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(
             (input.len() as u64 + consts::RIPEMD_WORD_LEN - 1) / consts::RIPEMD_WORD_LEN
                 * costs::RIPEMD160_PER_WORD
@@ -121,7 +121,7 @@ impl Precompile for RIPEMD160 {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
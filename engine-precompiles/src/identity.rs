@@ -26,7 +26,7 @@ impl Identity {
 }
 
 impl Precompile for Identity {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(
             (input.len() as u64 + consts::IDENTITY_WORD_LEN - 1) / consts::IDENTITY_WORD_LEN
                 * costs::IDENTITY_PER_WORD
@@ -45,7 +45,7 @@ impl Precompile for Identity {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -4,32 +4,51 @@
 #![cfg_attr(not(feature = "std"), feature(alloc_error_handler))]
 #![cfg_attr(feature = "log", feature(panic_info_message))]
 
+pub mod account_ids;
 pub mod blake2;
+pub mod bls12_381;
 pub mod bn128;
+pub mod cross_contract;
+pub mod ed25519;
 pub mod hash;
 pub mod identity;
 pub mod modexp;
 pub mod native;
+pub mod near_gas;
 mod prelude;
 pub mod random;
+pub mod registry;
 pub mod secp256k1;
+pub mod secp256r1;
+pub mod token_map;
 #[cfg(test)]
 mod utils;
 
+use crate::account_ids::{CurrentAccount, PredecessorAccount, SignerAccount};
 use crate::blake2::Blake2F;
+use crate::bls12_381::{
+    Bls12381G1Add, Bls12381G1Mul, Bls12381G1MultiExp, Bls12381G2Add, Bls12381G2Mul,
+    Bls12381G2MultiExp, Bls12381MapG1, Bls12381MapG2, Bls12381Pairing,
+};
 use crate::bn128::{Bn128Add, Bn128Mul, Bn128Pair};
+use crate::cross_contract::CrossContractCall;
+use crate::ed25519::Ed25519Verify;
 use crate::hash::{RIPEMD160, SHA256};
 use crate::identity::Identity;
 use crate::modexp::ModExp;
-use crate::native::{ExitToEthereum, ExitToNear};
+use crate::native::{ExitNftToNear, ExitToEthereum, ExitToNear};
+use crate::prelude::parameters::DisabledPrecompileMode;
+use crate::prelude::sdk::io::IO;
 use crate::prelude::types::EthGas;
 use crate::prelude::{Vec, H256};
-use crate::random::RandomSeed;
+use crate::random::{ExecutionId, RandomSeed, UniqueRandomSeed};
 use crate::secp256k1::ECRecover;
-use aurora_engine_types::{account_id::AccountId, vec, Address, BTreeMap, Box};
+use crate::secp256r1::P256Verify;
+use crate::token_map::{Erc20FromNep141, Nep141FromErc20};
+use aurora_engine_types::{account_id::AccountId, vec, Address, BTreeMap, BTreeSet, Box};
 use evm::backend::Log;
 use evm::executor;
-use evm::{Context, ExitError, ExitSucceed};
+use evm::{Context, ExitError, ExitRevert, ExitSucceed};
 
 #[derive(Debug, Default)]
 pub struct PrecompileOutput {
@@ -64,9 +83,7 @@ type EvmPrecompileResult = Result<evm::executor::PrecompileOutput, ExitError>;
 /// A precompiled function for use in the EVM.
 pub trait Precompile {
     /// The required gas in order to run the precompile function.
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError>
-    where
-        Self: Sized;
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError>;
 
     /// Runs the precompile function.
     fn run(
@@ -101,9 +118,13 @@ impl HardFork for Istanbul {}
 
 impl HardFork for Berlin {}
 
-pub struct Precompiles(pub prelude::BTreeMap<Address, Box<dyn Precompile>>);
+pub struct Precompiles<'a> {
+    pub all_precompiles: prelude::BTreeMap<Address, Box<dyn Precompile + 'a>>,
+    /// Precompiles disabled by the owner which revert when called.
+    pub reverting_precompiles: BTreeSet<Address>,
+}
 
-impl executor::PrecompileSet for Precompiles {
+impl<'a> executor::PrecompileSet for Precompiles<'a> {
     fn execute(
         &self,
         address: prelude::Address,
@@ -112,48 +133,116 @@ impl executor::PrecompileSet for Precompiles {
         context: &Context,
         is_static: bool,
     ) -> Option<Result<executor::PrecompileOutput, executor::PrecompileFailure>> {
-        self.0.get(&address).map(|p| {
+        if self.reverting_precompiles.contains(&address) {
+            return Some(Err(executor::PrecompileFailure::Revert {
+                exit_status: ExitRevert::Reverted,
+                output: Vec::new(),
+                cost: 0,
+            }));
+        }
+        self.all_precompiles.get(&address).map(|p| {
             p.run(input, gas_limit.map(EthGas::new), context, is_static)
                 .map_err(|exit_status| executor::PrecompileFailure::Error { exit_status })
         })
     }
 
     fn is_precompile(&self, address: prelude::Address) -> bool {
-        self.0.contains_key(&address)
+        self.all_precompiles.contains_key(&address) || self.reverting_precompiles.contains(&address)
     }
 }
 
-pub struct PrecompileConstructorContext {
+pub struct PrecompileConstructorContext<'a, I> {
     pub current_account_id: AccountId,
+    pub predecessor_account_id: AccountId,
+    pub signer_account_id: AccountId,
     pub random_seed: H256,
+    /// Identifies the EVM execution, see `random::ExecutionId`.
+    pub execution_id: &'a ExecutionId<I>,
+    /// EVM gas charged per Tgas of NEAR gas used by the promise-creating precompiles.
+    pub eth_gas_per_tgas: u64,
+    /// Engine storage, for the precompiles which depend on the engine configuration.
+    pub io: I,
 }
 
-impl Precompiles {
+impl<'a> Precompiles<'a> {
+    fn new(all_precompiles: BTreeMap<Address, Box<dyn Precompile + 'a>>) -> Self {
+        Self {
+            all_precompiles,
+            reverting_precompiles: BTreeSet::new(),
+        }
+    }
+
+    /// Applies the owner's registry of disabled precompiles. Addresses which are not
+    /// precompiles in this set are ignored.
+    pub fn disable(&mut self, disabled: &BTreeMap<Address, DisabledPrecompileMode>) {
+        for (address, mode) in disabled {
+            if self.all_precompiles.remove(address).is_some()
+                && *mode == DisabledPrecompileMode::Revert
+            {
+                self.reverting_precompiles.insert(*address);
+            }
+        }
+    }
+
     #[allow(dead_code)]
-    pub fn new_homestead(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_homestead<I: IO + Copy + 'a>(ctx: PrecompileConstructorContext<'a, I>) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
             RIPEMD160::ADDRESS,
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
+            ExitNftToNear::ADDRESS,
+            CrossContractCall::<I>::ADDRESS,
             RandomSeed::ADDRESS,
+            UniqueRandomSeed::<I>::ADDRESS,
+            PredecessorAccount::ADDRESS,
+            SignerAccount::ADDRESS,
+            CurrentAccount::ADDRESS,
+            Nep141FromErc20::<I>::ADDRESS,
+            Erc20FromNep141::<I>::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
             Box::new(ECRecover),
             Box::new(SHA256),
             Box::new(RIPEMD160),
-            Box::new(ExitToNear::new(ctx.current_account_id.clone())),
-            Box::new(ExitToEthereum::new(ctx.current_account_id)),
-            Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(ExitToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitToEthereum::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitNftToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(CrossContractCall::new(
+                ctx.io,
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(RandomSeed::new(ctx.random_seed, ctx.eth_gas_per_tgas)),
+            Box::new(UniqueRandomSeed::new(
+                ctx.random_seed,
+                ctx.execution_id,
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(PredecessorAccount::new(ctx.predecessor_account_id)),
+            Box::new(SignerAccount::new(ctx.signer_account_id)),
+            Box::new(CurrentAccount::new(ctx.current_account_id)),
+            Box::new(Nep141FromErc20::new(ctx.io)),
+            Box::new(Erc20FromNep141::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
-        Precompiles(map)
+        Self::new(map)
     }
 
     #[allow(dead_code)]
-    pub fn new_byzantium(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_byzantium<I: IO + Copy + 'a>(ctx: PrecompileConstructorContext<'a, I>) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -165,9 +254,17 @@ impl Precompiles {
             Bn128Pair::<Byzantium>::ADDRESS,
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
+            ExitNftToNear::ADDRESS,
+            CrossContractCall::<I>::ADDRESS,
             RandomSeed::ADDRESS,
+            UniqueRandomSeed::<I>::ADDRESS,
+            PredecessorAccount::ADDRESS,
+            SignerAccount::ADDRESS,
+            CurrentAccount::ADDRESS,
+            Nep141FromErc20::<I>::ADDRESS,
+            Erc20FromNep141::<I>::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
             Box::new(ECRecover),
             Box::new(SHA256),
             Box::new(RIPEMD160),
@@ -176,16 +273,42 @@ impl Precompiles {
             Box::new(Bn128Add::<Byzantium>::new()),
             Box::new(Bn128Mul::<Byzantium>::new()),
             Box::new(Bn128Pair::<Byzantium>::new()),
-            Box::new(ExitToNear::new(ctx.current_account_id.clone())),
-            Box::new(ExitToEthereum::new(ctx.current_account_id)),
-            Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(ExitToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitToEthereum::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitNftToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(CrossContractCall::new(
+                ctx.io,
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(RandomSeed::new(ctx.random_seed, ctx.eth_gas_per_tgas)),
+            Box::new(UniqueRandomSeed::new(
+                ctx.random_seed,
+                ctx.execution_id,
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(PredecessorAccount::new(ctx.predecessor_account_id)),
+            Box::new(SignerAccount::new(ctx.signer_account_id)),
+            Box::new(CurrentAccount::new(ctx.current_account_id)),
+            Box::new(Nep141FromErc20::new(ctx.io)),
+            Box::new(Erc20FromNep141::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
-        Precompiles(map)
+        Self::new(map)
     }
 
-    pub fn new_istanbul(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_istanbul<I: IO + Copy + 'a>(ctx: PrecompileConstructorContext<'a, I>) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -198,9 +321,17 @@ impl Precompiles {
             Blake2F::ADDRESS,
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
+            ExitNftToNear::ADDRESS,
+            CrossContractCall::<I>::ADDRESS,
             RandomSeed::ADDRESS,
+            UniqueRandomSeed::<I>::ADDRESS,
+            PredecessorAccount::ADDRESS,
+            SignerAccount::ADDRESS,
+            CurrentAccount::ADDRESS,
+            Nep141FromErc20::<I>::ADDRESS,
+            Erc20FromNep141::<I>::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
             Box::new(ECRecover),
             Box::new(SHA256),
             Box::new(RIPEMD160),
@@ -210,16 +341,42 @@ impl Precompiles {
             Box::new(Bn128Mul::<Istanbul>::new()),
             Box::new(Bn128Pair::<Istanbul>::new()),
             Box::new(Blake2F),
-            Box::new(ExitToNear::new(ctx.current_account_id.clone())),
-            Box::new(ExitToEthereum::new(ctx.current_account_id)),
-            Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(ExitToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitToEthereum::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitNftToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(CrossContractCall::new(
+                ctx.io,
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(RandomSeed::new(ctx.random_seed, ctx.eth_gas_per_tgas)),
+            Box::new(UniqueRandomSeed::new(
+                ctx.random_seed,
+                ctx.execution_id,
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(PredecessorAccount::new(ctx.predecessor_account_id)),
+            Box::new(SignerAccount::new(ctx.signer_account_id)),
+            Box::new(CurrentAccount::new(ctx.current_account_id)),
+            Box::new(Nep141FromErc20::new(ctx.io)),
+            Box::new(Erc20FromNep141::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
-        Precompiles(map)
+        Self::new(map)
     }
 
-    pub fn new_berlin(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_berlin<I: IO + Copy + 'a>(ctx: PrecompileConstructorContext<'a, I>) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -232,9 +389,17 @@ impl Precompiles {
             Blake2F::ADDRESS,
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
+            ExitNftToNear::ADDRESS,
+            CrossContractCall::<I>::ADDRESS,
             RandomSeed::ADDRESS,
+            UniqueRandomSeed::<I>::ADDRESS,
+            PredecessorAccount::ADDRESS,
+            SignerAccount::ADDRESS,
+            CurrentAccount::ADDRESS,
+            Nep141FromErc20::<I>::ADDRESS,
+            Erc20FromNep141::<I>::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
             Box::new(ECRecover),
             Box::new(SHA256),
             Box::new(RIPEMD160),
@@ -244,18 +409,94 @@ impl Precompiles {
             Box::new(Bn128Mul::<Istanbul>::new()),
             Box::new(Bn128Pair::<Istanbul>::new()),
             Box::new(Blake2F),
-            Box::new(ExitToNear::new(ctx.current_account_id.clone())),
-            Box::new(ExitToEthereum::new(ctx.current_account_id)),
-            Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(ExitToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitToEthereum::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(ExitNftToNear::new(
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(CrossContractCall::new(
+                ctx.io,
+                ctx.current_account_id.clone(),
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(RandomSeed::new(ctx.random_seed, ctx.eth_gas_per_tgas)),
+            Box::new(UniqueRandomSeed::new(
+                ctx.random_seed,
+                ctx.execution_id,
+                ctx.eth_gas_per_tgas,
+            )),
+            Box::new(PredecessorAccount::new(ctx.predecessor_account_id)),
+            Box::new(SignerAccount::new(ctx.signer_account_id)),
+            Box::new(CurrentAccount::new(ctx.current_account_id)),
+            Box::new(Nep141FromErc20::new(ctx.io)),
+            Box::new(Erc20FromNep141::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
-        Precompiles(map)
+        Self::new(map)
     }
 
-    pub fn new_london(ctx: PrecompileConstructorContext) -> Self {
-        // no precompile changes in London HF
-        Self::new_berlin(ctx)
+    /// Berlin precompiles with the secp256r1 signature verification of RIP-7212 and the ed25519
+    /// signature verification of NEAR keys. London made no changes to the Ethereum precompiles.
+    pub fn new_london<I: IO + Copy + 'a>(ctx: PrecompileConstructorContext<'a, I>) -> Self {
+        let eth_gas_per_tgas = ctx.eth_gas_per_tgas;
+        let mut precompiles = Self::new_berlin(ctx);
+        precompiles
+            .all_precompiles
+            .insert(P256Verify::ADDRESS, Box::new(P256Verify));
+        precompiles.all_precompiles.insert(
+            Ed25519Verify::ADDRESS,
+            Box::new(Ed25519Verify::new(eth_gas_per_tgas)),
+        );
+
+        precompiles
+    }
+
+    /// London precompiles with the nine BLS12-381 operations of EIP-2537. The seven of the
+    /// final EIP are at its addresses, `0x0b` to `0x11`, and the G1 and G2 multiplications,
+    /// which it leaves to the multi-scalar multiplications, follow at `0x12` and `0x13`.
+    ///
+    /// Not active yet: `StackExecutorParams::new` in `engine/src/engine.rs` builds the
+    /// executor with `new_london`, and switches to this set in the engine upgrade which
+    /// enables EIP-2537, as calls to these addresses would then stop succeeding as calls to
+    /// accounts without code.
+    pub fn new_prague<I: IO + Copy + 'a>(ctx: PrecompileConstructorContext<'a, I>) -> Self {
+        let addresses = vec![
+            Bls12381G1Add::ADDRESS,
+            Bls12381G1MultiExp::ADDRESS,
+            Bls12381G2Add::ADDRESS,
+            Bls12381G2MultiExp::ADDRESS,
+            Bls12381Pairing::ADDRESS,
+            Bls12381MapG1::ADDRESS,
+            Bls12381MapG2::ADDRESS,
+            Bls12381G1Mul::ADDRESS,
+            Bls12381G2Mul::ADDRESS,
+        ];
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
+            Box::new(Bls12381G1Add),
+            Box::new(Bls12381G1MultiExp),
+            Box::new(Bls12381G2Add),
+            Box::new(Bls12381G2MultiExp),
+            Box::new(Bls12381Pairing),
+            Box::new(Bls12381MapG1),
+            Box::new(Bls12381MapG2),
+            Box::new(Bls12381G1Mul),
+            Box::new(Bls12381G2Mul),
+        ];
+        let mut precompiles = Self::new_london(ctx);
+        precompiles
+            .all_precompiles
+            .extend(addresses.into_iter().zip(fun));
+
+        precompiles
     }
 }
 
@@ -344,6 +585,16 @@ mod tests {
         assert_eq!(super::Bn128Mul::<Istanbul>::ADDRESS, u8_to_address(7));
         assert_eq!(super::Bn128Pair::<Istanbul>::ADDRESS, u8_to_address(8));
         assert_eq!(super::blake2::Blake2F::ADDRESS, u8_to_address(9));
+        assert_eq!(super::Bls12381G1Add::ADDRESS, u8_to_address(11));
+        assert_eq!(super::Bls12381G1MultiExp::ADDRESS, u8_to_address(12));
+        assert_eq!(super::Bls12381G2Add::ADDRESS, u8_to_address(13));
+        assert_eq!(super::Bls12381G2MultiExp::ADDRESS, u8_to_address(14));
+        assert_eq!(super::Bls12381Pairing::ADDRESS, u8_to_address(15));
+        assert_eq!(super::Bls12381MapG1::ADDRESS, u8_to_address(16));
+        assert_eq!(super::Bls12381MapG2::ADDRESS, u8_to_address(17));
+        assert_eq!(super::Bls12381G1Mul::ADDRESS, u8_to_address(18));
+        assert_eq!(super::Bls12381G2Mul::ADDRESS, u8_to_address(19));
+        assert_eq!(super::P256Verify::ADDRESS, super::make_address(0, 0x100));
     }
 
     #[test]
@@ -360,6 +611,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_disable_precompiles() {
+        use super::{Box, Precompile, Precompiles};
+        use crate::prelude::parameters::DisabledPrecompileMode;
+        use evm::executor::{PrecompileFailure, PrecompileSet};
+
+        let sha256 = super::SHA256::ADDRESS;
+        let identity = super::Identity::ADDRESS;
+        let ripemd160 = super::RIPEMD160::ADDRESS;
+        let all: prelude::Vec<(prelude::Address, Box<dyn Precompile>)> = prelude::vec![
+            (sha256, Box::new(super::SHA256)),
+            (identity, Box::new(super::Identity)),
+            (ripemd160, Box::new(super::RIPEMD160)),
+        ];
+        let mut precompiles = Precompiles::new(all.into_iter().collect());
+        let disabled = prelude::vec![
+            (sha256, DisabledPrecompileMode::EmptyAccount),
+            (identity, DisabledPrecompileMode::Revert),
+            (u8_to_address(1), DisabledPrecompileMode::Revert),
+        ];
+        precompiles.disable(&disabled.into_iter().collect());
+
+        let context = crate::utils::new_context();
+        let execute = |address| precompiles.execute(address, &[1, 2, 3], None, &context, false);
+
+        assert!(!precompiles.is_precompile(sha256));
+        assert!(execute(sha256).is_none());
+
+        assert!(precompiles.is_precompile(identity));
+        assert!(matches!(
+            execute(identity),
+            Some(Err(PrecompileFailure::Revert { cost: 0, .. }))
+        ));
+
+        assert!(precompiles.is_precompile(ripemd160));
+        assert!(matches!(execute(ripemd160), Some(Ok(_))));
+
+        // Addresses which are not precompiles stay regular accounts.
+        assert!(!precompiles.is_precompile(u8_to_address(1)));
+        assert!(execute(u8_to_address(1)).is_none());
+    }
+
     fn u8_to_address(x: u8) -> prelude::Address {
         let mut bytes = [0u8; 20];
         bytes[19] = x;
@@ -95,7 +95,7 @@ impl ModExp<Byzantium> {
 }
 
 impl Precompile for ModExp<Byzantium> {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         let (base_len, exp_len, mod_len) = parse_lengths(input);
 
         let mul = Self::mul_complexity(core::cmp::max(mod_len, base_len));
@@ -115,7 +115,7 @@ impl Precompile for ModExp<Byzantium> {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -137,7 +137,7 @@ impl ModExp<Berlin> {
 }
 
 impl Precompile for ModExp<Berlin> {
-    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
         let (base_len, exp_len, mod_len) = parse_lengths(input);
 
         let mul = Self::mul_complexity(base_len, mod_len);
@@ -155,7 +155,7 @@ impl Precompile for ModExp<Berlin> {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
@@ -431,7 +431,7 @@ mod tests {
         for (test, test_gas) in TESTS.iter().zip(BYZANTIUM_GAS.iter()) {
             let input = hex::decode(&test.input).unwrap();
 
-            let gas = ModExp::<Byzantium>::required_gas(&input).unwrap();
+            let gas = ModExp::<Byzantium>::new().required_gas(&input).unwrap();
             assert_eq!(gas, *test_gas, "{} gas", test.name);
         }
     }
@@ -441,7 +441,7 @@ mod tests {
         for (test, test_gas) in TESTS.iter().zip(BERLIN_GAS.iter()) {
             let input = hex::decode(&test.input).unwrap();
 
-            let gas = ModExp::<Berlin>::required_gas(&input).unwrap();
+            let gas = ModExp::<Berlin>::new().required_gas(&input).unwrap();
             assert_eq!(gas, *test_gas, "{} gas", test.name);
         }
     }
@@ -462,7 +462,7 @@ mod tests {
         input.extend_from_slice(&u256_to_arr(&exp));
 
         // completes without any overflow
-        ModExp::<Berlin>::required_gas(&input).unwrap();
+        ModExp::<Berlin>::new().required_gas(&input).unwrap();
     }
 
     #[test]
@@ -481,7 +481,7 @@ mod tests {
         input.extend_from_slice(&u256_to_arr(&exp));
 
         // completes without any overflow
-        ModExp::<Berlin>::required_gas(&input).unwrap();
+        ModExp::<Berlin>::new().required_gas(&input).unwrap();
     }
 
     #[test]
@@ -2,19 +2,19 @@ use super::{EvmPrecompileResult, Precompile};
 #[cfg(feature = "contract")]
 use crate::prelude::{
     format,
-    parameters::{PromiseArgs, PromiseCreateArgs, WithdrawCallArgs},
+    json::escape_json,
+    parameters::{
+        PromiseArgs, PromiseCreateArgs, PromiseWithCallbackArgs, RefundCallArgs, RefundNftCallArgs,
+        WithdrawCallArgs,
+    },
     sdk,
     storage::{bytes_to_key, KeyPrefix},
-    vec, BorshSerialize, Cow, String, ToString, TryFrom, TryInto, Vec, H160, U256,
-};
-#[cfg(all(feature = "error_refund", feature = "contract"))]
-use crate::prelude::{
-    parameters::{PromiseWithCallbackArgs, RefundCallArgs},
-    types,
+    types, vec, BorshSerialize, String, ToString, TryInto, Vec, H160,
 };
 
+use crate::near_gas;
 use crate::prelude::types::EthGas;
-use crate::prelude::Address;
+use crate::prelude::{Address, Cow, TryFrom, U256};
 use crate::PrecompileOutput;
 use aurora_engine_types::account_id::AccountId;
 #[cfg(feature = "contract")]
@@ -24,23 +24,48 @@ use evm::{Context, ExitError};
 const ERR_TARGET_TOKEN_NOT_FOUND: &str = "Target token not found";
 
 mod costs {
-    use crate::prelude::types::EthGas;
+    use crate::near_gas::PROMISE_CREATE_GAS;
+    use crate::prelude::types::NearGas;
 
-    // TODO(#51): Determine the correct amount of gas
-    pub(super) const EXIT_TO_NEAR_GAS: EthGas = EthGas::new(0);
+    // TODO(#332): Determine the correct amount of gas
+    pub(super) const FT_TRANSFER_GAS: NearGas = NearGas::new(100_000_000_000_000);
 
-    // TODO(#51): Determine the correct amount of gas
-    pub(super) const EXIT_TO_ETHEREUM_GAS: EthGas = EthGas::new(0);
+    /// Covers `ft_on_transfer` of the receiver and `ft_resolve_transfer` on top of the transfer.
+    pub(super) const FT_TRANSFER_CALL_GAS: NearGas = NearGas::new(150_000_000_000_000);
 
     // TODO(#332): Determine the correct amount of gas
-    pub(super) const FT_TRANSFER_GAS: EthGas = EthGas::new(100_000_000_000_000);
+    pub(super) const REFUND_ON_ERROR_GAS: NearGas = NearGas::new(60_000_000_000_000);
 
     // TODO(#332): Determine the correct amount of gas
-    #[cfg(feature = "error_refund")]
-    pub(super) const REFUND_ON_ERROR_GAS: EthGas = EthGas::new(60_000_000_000_000);
+    pub(super) const WITHDRAWAL_GAS: NearGas = NearGas::new(100_000_000_000_000);
+
+    /// NEAR gas attached to and burnt by the promises created by `ExitToNear`.
+    pub(super) fn exit_to_near_gas(with_msg: bool) -> NearGas {
+        let refund_gas = if super::exit_to_near_has_refund(with_msg) {
+            PROMISE_CREATE_GAS + REFUND_ON_ERROR_GAS
+        } else {
+            NearGas::new(0)
+        };
+        let transfer_gas = if with_msg {
+            FT_TRANSFER_CALL_GAS
+        } else {
+            FT_TRANSFER_GAS
+        };
+        PROMISE_CREATE_GAS + transfer_gas + refund_gas
+    }
 
     // TODO(#332): Determine the correct amount of gas
-    pub(super) const WITHDRAWAL_GAS: EthGas = EthGas::new(100_000_000_000_000);
+    pub(super) const NFT_TRANSFER_GAS: NearGas = NearGas::new(100_000_000_000_000);
+
+    /// NEAR gas attached to and burnt by the promises created by `ExitNftToNear`.
+    pub(super) fn exit_nft_to_near_gas() -> NearGas {
+        PROMISE_CREATE_GAS + NFT_TRANSFER_GAS + PROMISE_CREATE_GAS + REFUND_ON_ERROR_GAS
+    }
+
+    /// NEAR gas attached to and burnt by the promise created by `ExitToEthereum`.
+    pub(super) fn exit_to_ethereum_gas() -> NearGas {
+        PROMISE_CREATE_GAS + WITHDRAWAL_GAS
+    }
 }
 
 pub mod events {
@@ -57,6 +82,12 @@ pub mod events {
         0x491373762eeac0831c48400e2d5a5f07,
     );
 
+    /// Derived from event signature (see tests::test_exit_signatures)
+    pub const EXIT_NFT_TO_NEAR_SIGNATURE: H256 = crate::make_h256(
+        0xa1afdff381e8853009e622a419eb42b4,
+        0x661d98d3091b391417d99b90c0f1c10e,
+    );
+
     /// The exit precompile events have an `erc20_address` field to indicate
     /// which ERC-20 token is being withdrawn. However, ETH is not an ERC-20 token
     /// So we need to have some other address to fill this field. This constant is
@@ -125,6 +156,35 @@ pub mod events {
         }
     }
 
+    /// ExitNftToNear(
+    ///    Address indexed sender,
+    ///    Address indexed erc721_address,
+    ///    string indexed dest,
+    ///    string token_id
+    /// )
+    /// Note: `sender` == `erc721_address` because it is the ERC-721 contract
+    /// which calls the exit precompile.
+    pub struct ExitNftToNear {
+        pub sender: Address,
+        pub erc721_address: Address,
+        pub dest: String,
+        pub token_id: String,
+    }
+
+    impl ExitNftToNear {
+        pub fn encode(self) -> ethabi::RawLog {
+            let data = ethabi::encode(&[ethabi::Token::String(self.token_id)]);
+            let topics = vec![
+                EXIT_NFT_TO_NEAR_SIGNATURE,
+                encode_address(self.sender),
+                encode_address(self.erc721_address),
+                aurora_engine_sdk::keccak(&ethabi::encode(&[ethabi::Token::String(self.dest)])),
+            ];
+
+            ethabi::RawLog { topics, data }
+        }
+    }
+
     fn encode_address(a: Address) -> H256 {
         let mut result = [0u8; 32];
         result[12..].copy_from_slice(a.as_ref());
@@ -160,6 +220,35 @@ pub mod events {
         }
     }
 
+    pub fn exit_nft_to_near_schema() -> ethabi::Event {
+        ethabi::Event {
+            name: "ExitNftToNear".to_string(),
+            inputs: vec![
+                ethabi::EventParam {
+                    name: "sender".to_string(),
+                    kind: ethabi::ParamType::Address,
+                    indexed: true,
+                },
+                ethabi::EventParam {
+                    name: "erc721_address".to_string(),
+                    kind: ethabi::ParamType::Address,
+                    indexed: true,
+                },
+                ethabi::EventParam {
+                    name: "dest".to_string(),
+                    kind: ethabi::ParamType::String,
+                    indexed: true,
+                },
+                ethabi::EventParam {
+                    name: "token_id".to_string(),
+                    kind: ethabi::ParamType::String,
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        }
+    }
+
     pub fn exit_to_eth_schema() -> ethabi::Event {
         ethabi::Event {
             name: "ExitToEth".to_string(),
@@ -193,6 +282,7 @@ pub mod events {
 //TransferEthToNear
 pub struct ExitToNear {
     current_account_id: AccountId,
+    eth_gas_per_tgas: u64,
 }
 
 impl ExitToNear {
@@ -203,8 +293,141 @@ impl ExitToNear {
     pub const ADDRESS: Address =
         super::make_address(0xe9217bc7, 0x0b7ed1f598ddd3199e80b093fa71124f);
 
-    pub fn new(current_account_id: AccountId) -> Self {
-        Self { current_account_id }
+    pub fn new(current_account_id: AccountId, eth_gas_per_tgas: u64) -> Self {
+        Self {
+            current_account_id,
+            eth_gas_per_tgas,
+        }
+    }
+}
+
+/// Length of the refund address which follows the flag byte of the `ExitToNear` input. The
+/// ERC-20 exits of flag `0x2` always carry the address of the burner. The other exits only
+/// carry one with the `error_refund` feature, as they did before flag `0x2` was added.
+fn refund_address_len(flag: u8) -> usize {
+    if cfg!(feature = "error_refund") || flag == 0x2 {
+        20
+    } else {
+        0
+    }
+}
+
+/// Length of the amount which follows the refund address of the `ExitToNear` input.
+fn amount_len(flag: u8) -> usize {
+    if flag == 0x0 {
+        0
+    } else {
+        32
+    }
+}
+
+/// Splits the receiver account id from the message for `ft_transfer_call`, which may follow
+/// it after a `:`. Account ids cannot contain `:`, so inputs without a message are unchanged.
+fn parse_recipient(input: &[u8]) -> (&[u8], Option<&[u8]>) {
+    match input.iter().position(|b| *b == b':') {
+        Some(i) => (&input[..i], Some(&input[(i + 1)..])),
+        None => (input, None),
+    }
+}
+
+/// Returns true if the `ExitToNear` input carries a message, i.e. the exit uses
+/// `ft_transfer_call` instead of `ft_transfer`. The legacy ERC-20 exits of flag `0x1` may
+/// not carry the burner, who gets the unused amount back, so they never have a message.
+fn exit_to_near_has_msg(input: &[u8]) -> bool {
+    let flag = match input.first() {
+        Some(flag) if *flag != 0x1 => *flag,
+        _ => return false,
+    };
+    input
+        .get((1 + refund_address_len(flag) + amount_len(flag))..)
+        .map(|recipient| parse_recipient(recipient).1.is_some())
+        .unwrap_or(false)
+}
+
+/// Parsed input of the `ExitToNear` precompile.
+#[cfg_attr(not(feature = "contract"), allow(dead_code))]
+struct ExitToNearInput<'a> {
+    /// Without the refund address in the input, refunds go to the ETH sender.
+    refund_address: Option<Address>,
+    /// The burned amount of ERC-20 exits.
+    amount: Option<U256>,
+    recipient: AccountId,
+    msg: Option<&'a str>,
+}
+
+/// The first byte of the input is a flag, selecting the behavior to be triggered:
+///      0x0 -> ETH transfer: `0x0|[refund_address]|recipient[:msg]`
+///      0x1 -> ERC-20 transfer: `0x1|[refund_address]|amount|recipient`
+///      0x2 -> ERC-20 transfer: `0x2|sender|amount|recipient[:msg]`
+/// where the refund address is only present with the `error_refund` feature.
+fn parse_exit_to_near_input(input: &[u8]) -> Result<ExitToNearInput, ExitError> {
+    let flag = match input.first() {
+        Some(flag) if *flag <= 0x2 => *flag,
+        Some(_) => return Err(ExitError::Other(Cow::from("ERR_INVALID_FLAG"))),
+        None => return Err(ExitError::Other(Cow::from("ERR_INVALID_INPUT"))),
+    };
+    let refund_address_end = 1 + refund_address_len(flag);
+    let amount_end = refund_address_end + amount_len(flag);
+    if input.len() < amount_end {
+        return Err(ExitError::Other(Cow::from("ERR_INVALID_INPUT")));
+    }
+
+    let refund_address = if refund_address_end > 1 {
+        Some(Address::from_slice(&input[1..refund_address_end]))
+    } else {
+        None
+    };
+    let amount = if amount_end > refund_address_end {
+        Some(U256::from_big_endian(
+            &input[refund_address_end..amount_end],
+        ))
+    } else {
+        None
+    };
+    let (recipient, msg) = if flag == 0x1 {
+        (&input[amount_end..], None)
+    } else {
+        parse_recipient(&input[amount_end..])
+    };
+    let recipient = AccountId::try_from(recipient)
+        .map_err(|_| ExitError::Other(Cow::from("ERR_INVALID_RECEIVER_ACCOUNT_ID")))?;
+    let msg = msg
+        .map(|msg| {
+            core::str::from_utf8(msg).map_err(|_| ExitError::Other(Cow::from("ERR_INVALID_MSG")))
+        })
+        .transpose()?;
+
+    Ok(ExitToNearInput {
+        refund_address,
+        amount,
+        recipient,
+        msg,
+    })
+}
+
+/// Returns true if the exit schedules the `refund_on_error` callback. It always does with
+/// the `error_refund` feature, otherwise only for `ft_transfer_call`, which may return part
+/// of the amount unused.
+fn exit_to_near_has_refund(has_msg: bool) -> bool {
+    cfg!(feature = "error_refund") || has_msg
+}
+
+/// JSON arguments of `ft_transfer`, or of `ft_transfer_call` if there is a message.
+#[cfg(feature = "contract")]
+fn transfer_args(receiver_id: &AccountId, amount: u128, msg: Option<&str>) -> String {
+    // There is no way to inject json into the receiver and amount, given their encoding
+    // as a valid account id and decimal respectively.
+    match msg {
+        None => format!(
+            r#"{{"receiver_id": "{}", "amount": "{}", "memo": null}}"#,
+            receiver_id, amount
+        ),
+        Some(msg) => format!(
+            r#"{{"receiver_id": "{}", "amount": "{}", "memo": null, "msg": "{}"}}"#,
+            receiver_id,
+            amount,
+            escape_json(msg)
+        ),
     }
 }
 
@@ -221,8 +444,12 @@ fn get_nep141_from_erc20(erc20_token: &[u8]) -> AccountId {
 }
 
 impl Precompile for ExitToNear {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
-        Ok(costs::EXIT_TO_NEAR_GAS)
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        near_gas::to_eth_gas(
+            costs::exit_to_near_gas(exit_to_near_has_msg(input)),
+            self.eth_gas_per_tgas,
+        )
+        .ok_or(ExitError::OutOfGas)
     }
 
     #[cfg(not(feature = "contract"))]
@@ -233,13 +460,18 @@ impl Precompile for ExitToNear {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        if let Some(target_gas) = target_gas {
-            if Self::required_gas(input)? > target_gas {
-                return Err(ExitError::OutOfGas);
-            }
-        }
+        let cost = near_gas::charge(
+            costs::exit_to_near_gas(exit_to_near_has_msg(input)),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
+        parse_exit_to_near_input(input)?;
 
-        Ok(PrecompileOutput::default().into())
+        Ok(PrecompileOutput {
+            cost,
+            ..Default::default()
+        }
+        .into())
     }
 
     #[cfg(feature = "contract")]
@@ -250,77 +482,45 @@ impl Precompile for ExitToNear {
         context: &Context,
         is_static: bool,
     ) -> EvmPrecompileResult {
-        #[cfg(feature = "error_refund")]
-        fn parse_input(input: &[u8]) -> (Address, &[u8]) {
-            let refund_address = Address::from_slice(&input[1..21]);
-            (refund_address, &input[21..])
-        }
-        #[cfg(not(feature = "error_refund"))]
-        fn parse_input(input: &[u8]) -> &[u8] {
-            &input[1..]
-        }
-
-        if let Some(target_gas) = target_gas {
-            if Self::required_gas(input)? > target_gas {
-                return Err(ExitError::OutOfGas);
-            }
-        }
+        let has_msg = exit_to_near_has_msg(input);
+        let cost = near_gas::charge(
+            costs::exit_to_near_gas(has_msg),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
 
         // It's not allowed to call exit precompiles in static mode
         if is_static {
             return Err(ExitError::Other(Cow::from("ERR_INVALID_IN_STATIC")));
         }
 
-        // First byte of the input is a flag, selecting the behavior to be triggered:
-        //      0x0 -> Eth transfer
-        //      0x1 -> Erc20 transfer
-        let flag = input[0];
-        #[cfg(feature = "error_refund")]
-        let (refund_address, mut input) = parse_input(input);
-        #[cfg(not(feature = "error_refund"))]
-        let mut input = parse_input(input);
+        let ExitToNearInput {
+            refund_address,
+            amount,
+            recipient,
+            msg,
+        } = parse_exit_to_near_input(input)?;
         let current_account_id = self.current_account_id.clone();
-        #[cfg(feature = "error_refund")]
         let refund_on_error_target = current_account_id.clone();
 
-        let (nep141_address, args, exit_event) = match flag {
-            0x0 => {
+        let (nep141_address, args, exit_event) = match amount {
+            None => {
                 // ETH transfer
-                //
-                // Input slice format:
-                //      recipient_account_id (bytes) - the NEAR recipient account which will receive NEP-141 ETH tokens
-
-                if let Ok(dest_account) = AccountId::try_from(input) {
-                    (
-                        current_account_id,
-                        // There is no way to inject json, given the encoding of both arguments
-                        // as decimal and valid account id respectively.
-                        format!(
-                            r#"{{"receiver_id": "{}", "amount": "{}", "memo": null}}"#,
-                            dest_account,
-                            context.apparent_value.as_u128()
-                        ),
-                        events::ExitToNear {
-                            sender: context.caller,
-                            erc20_address: events::ETH_ADDRESS,
-                            dest: dest_account.to_string(),
-                            amount: context.apparent_value,
-                        },
-                    )
-                } else {
-                    return Err(ExitError::Other(Cow::from(
-                        "ERR_INVALID_RECEIVER_ACCOUNT_ID",
-                    )));
-                }
+                (
+                    current_account_id,
+                    transfer_args(&recipient, context.apparent_value.as_u128(), msg),
+                    events::ExitToNear {
+                        sender: context.caller,
+                        erc20_address: events::ETH_ADDRESS,
+                        dest: recipient.to_string(),
+                        amount: context.apparent_value,
+                    },
+                )
             }
-            0x1 => {
+            Some(amount) => {
                 // ERC20 transfer
                 //
-                // This precompile branch is expected to be called from the ERC20 burn function\
-                //
-                // Input slice format:
-                //      amount (U256 big-endian bytes) - the amount that was burned
-                //      recipient_account_id (bytes) - the NEAR recipient account which will receive NEP-141 tokens
+                // This precompile branch is expected to be called from the ERC20 burn function
 
                 if context.apparent_value != U256::from(0) {
                     return Err(ExitError::Other(Cow::from(
@@ -331,77 +531,238 @@ impl Precompile for ExitToNear {
                 let erc20_address = context.caller;
                 let nep141_address = get_nep141_from_erc20(erc20_address.as_bytes());
 
-                let amount = U256::from_big_endian(&input[..32]);
-                input = &input[32..];
+                (
+                    nep141_address,
+                    transfer_args(&recipient, amount.as_u128(), msg),
+                    events::ExitToNear {
+                        sender: erc20_address,
+                        erc20_address,
+                        dest: recipient.to_string(),
+                        amount,
+                    },
+                )
+            }
+        };
+
+        let (method, attached_gas) = if has_msg {
+            ("ft_transfer_call", costs::FT_TRANSFER_CALL_GAS)
+        } else {
+            ("ft_transfer", costs::FT_TRANSFER_GAS)
+        };
+        let transfer_promise = PromiseCreateArgs {
+            target_account_id: nep141_address,
+            method: method.to_string(),
+            args: args.as_bytes().to_vec(),
+            attached_balance: 1,
+            attached_gas: attached_gas.into_u64(),
+        };
+
+        let promise = if exit_to_near_has_refund(has_msg) {
+            let erc20_address = amount.map(|_| exit_event.erc20_address.0);
+            let refund_args = RefundCallArgs {
+                recipient_address: refund_address.unwrap_or(context.caller).0,
+                erc20_address,
+                amount: types::u256_to_arr(&exit_event.amount),
+            };
+            let refund_promise = PromiseCreateArgs {
+                target_account_id: refund_on_error_target,
+                method: "refund_on_error".to_string(),
+                args: refund_args.try_to_vec().unwrap(),
+                attached_balance: 0,
+                attached_gas: costs::REFUND_ON_ERROR_GAS.into_u64(),
+            };
+            PromiseArgs::Callback(PromiseWithCallbackArgs {
+                base: transfer_promise,
+                callback: refund_promise,
+            })
+        } else {
+            PromiseArgs::Create(transfer_promise)
+        };
+
+        let promise_log = Log {
+            address: Self::ADDRESS,
+            topics: Vec::new(),
+            data: promise.try_to_vec().unwrap(),
+        };
+        let exit_event_log = exit_event.encode();
+        let exit_event_log = Log {
+            address: Self::ADDRESS,
+            topics: exit_event_log.topics,
+            data: exit_event_log.data,
+        };
+
+        Ok(PrecompileOutput {
+            cost,
+            logs: vec![promise_log, exit_event_log],
+            ..Default::default()
+        }
+        .into())
+    }
+}
+
+pub struct ExitNftToNear {
+    current_account_id: AccountId,
+    eth_gas_per_tgas: u64,
+}
+
+impl ExitNftToNear {
+    /// Exit NFT to NEAR precompile address
+    ///
+    /// Address: `0x4ef4bcbe1bc5c00c19ec753e362ac0756c01f43b`
+    /// This address is computed as: `&keccak("exitNftToNear")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0x4ef4bcbe, 0x1bc5c00c19ec753e362ac0756c01f43b);
+
+    pub fn new(current_account_id: AccountId, eth_gas_per_tgas: u64) -> Self {
+        Self {
+            current_account_id,
+            eth_gas_per_tgas,
+        }
+    }
+}
 
-                if let Ok(receiver_account_id) = AccountId::try_from(input) {
+#[cfg(feature = "contract")]
+fn get_nep171_from_erc721(erc721_token: &[u8]) -> Result<AccountId, ExitError> {
+    use sdk::io::{StorageIntermediate, IO};
+    sdk::near_runtime::Runtime
+        .read_storage(bytes_to_key(KeyPrefix::Erc721Nep171Map, erc721_token).as_slice())
+        .and_then(|s| AccountId::try_from(s.to_vec()).ok())
+        .ok_or_else(|| ExitError::Other(Cow::from("ERR_TARGET_NFT_NOT_FOUND")))
+}
+
+impl Precompile for ExitNftToNear {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        near_gas::to_eth_gas(costs::exit_nft_to_near_gas(), self.eth_gas_per_tgas)
+            .ok_or(ExitError::OutOfGas)
+    }
+
+    #[cfg(not(feature = "contract"))]
+    fn run(
+        &self,
+        _input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = near_gas::charge(
+            costs::exit_nft_to_near_gas(),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
+
+        Ok(PrecompileOutput {
+            cost,
+            ..Default::default()
+        }
+        .into())
+    }
+
+    /// Called by `withdrawToNear` of an engine-deployed ERC-721 after it burned the token.
+    /// The `refund_nft_on_error` callback mints the token again to its owner if the transfer
+    /// fails.
+    ///
+    /// Input: `abi.encode(string near_token_id, bytes recipient_account_id, address owner,
+    /// string token_uri)`.
+    #[cfg(feature = "contract")]
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        context: &Context,
+        is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = near_gas::charge(
+            costs::exit_nft_to_near_gas(),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
+
+        // It's not allowed to call exit precompiles in static mode
+        if is_static {
+            return Err(ExitError::Other(Cow::from("ERR_INVALID_IN_STATIC")));
+        }
+        if context.apparent_value != U256::from(0) {
+            return Err(ExitError::Other(Cow::from("ERR_ETH_ATTACHED_FOR_NFT_EXIT")));
+        }
+
+        // Only ERC-721 contracts deployed by the engine are mapped to a NEP-171 token.
+        let erc721_address = context.caller;
+        let nep171_address = get_nep171_from_erc721(erc721_address.as_bytes())?;
+
+        let (token_id, recipient, owner, token_uri) = match ethabi::decode(
+            &[
+                ethabi::ParamType::String,
+                ethabi::ParamType::Bytes,
+                ethabi::ParamType::Address,
+                ethabi::ParamType::String,
+            ],
+            input,
+        )
+        .as_deref()
+        {
+            Ok([token_id, recipient, owner, token_uri]) => {
+                match (token_id, recipient, owner, token_uri) {
                     (
-                        nep141_address,
-                        // There is no way to inject json, given the encoding of both arguments
-                        // as decimal and valid account id respectively.
-                        format!(
-                            r#"{{"receiver_id": "{}", "amount": "{}", "memo": null}}"#,
-                            receiver_account_id,
-                            amount.as_u128()
-                        ),
-                        events::ExitToNear {
-                            sender: erc20_address,
-                            erc20_address,
-                            dest: receiver_account_id.to_string(),
-                            amount,
-                        },
-                    )
-                } else {
-                    return Err(ExitError::Other(Cow::from(
-                        "ERR_INVALID_RECEIVER_ACCOUNT_ID",
-                    )));
+                        ethabi::Token::String(token_id),
+                        ethabi::Token::Bytes(recipient),
+                        ethabi::Token::Address(owner),
+                        ethabi::Token::String(token_uri),
+                    ) => (
+                        token_id.clone(),
+                        recipient.clone(),
+                        *owner,
+                        token_uri.clone(),
+                    ),
+                    _ => return Err(ExitError::Other(Cow::from("ERR_INVALID_NFT_EXIT_INPUT"))),
                 }
             }
-            _ => return Err(ExitError::Other(Cow::from("ERR_INVALID_FLAG"))),
+            _ => return Err(ExitError::Other(Cow::from("ERR_INVALID_NFT_EXIT_INPUT"))),
         };
+        let receiver_account_id = AccountId::try_from(recipient)
+            .map_err(|_| ExitError::Other(Cow::from("ERR_INVALID_RECEIVER_ACCOUNT_ID")))?;
 
-        #[cfg(feature = "error_refund")]
-        let erc20_address = if flag == 0 {
-            None
-        } else {
-            Some(exit_event.erc20_address.0)
+        let transfer_promise = PromiseCreateArgs {
+            target_account_id: nep171_address.clone(),
+            method: "nft_transfer".to_string(),
+            args: format!(
+                r#"{{"receiver_id": "{}", "token_id": "{}", "approval_id": null, "memo": null}}"#,
+                receiver_account_id,
+                escape_json(&token_id)
+            )
+            .into_bytes(),
+            attached_balance: 1,
+            attached_gas: costs::NFT_TRANSFER_GAS.into_u64(),
         };
-        #[cfg(feature = "error_refund")]
-        let refund_args = RefundCallArgs {
-            recipient_address: refund_address.0,
-            erc20_address,
-            amount: types::u256_to_arr(&exit_event.amount),
+        let refund_args = RefundNftCallArgs {
+            nep171: nep171_address,
+            token_id: token_id.clone(),
+            token_uri,
+            recipient_address: owner.0,
         };
-        #[cfg(feature = "error_refund")]
         let refund_promise = PromiseCreateArgs {
-            target_account_id: refund_on_error_target,
-            method: "refund_on_error".to_string(),
+            target_account_id: self.current_account_id.clone(),
+            method: "refund_nft_on_error".to_string(),
             args: refund_args.try_to_vec().unwrap(),
             attached_balance: 0,
             attached_gas: costs::REFUND_ON_ERROR_GAS.into_u64(),
         };
-        let transfer_promise = PromiseCreateArgs {
-            target_account_id: nep141_address,
-            method: "ft_transfer".to_string(),
-            args: args.as_bytes().to_vec(),
-            attached_balance: 1,
-            attached_gas: costs::FT_TRANSFER_GAS.into_u64(),
-        };
-
-        #[cfg(feature = "error_refund")]
         let promise = PromiseArgs::Callback(PromiseWithCallbackArgs {
             base: transfer_promise,
             callback: refund_promise,
         });
-        #[cfg(not(feature = "error_refund"))]
-        let promise = PromiseArgs::Create(transfer_promise);
 
         let promise_log = Log {
             address: Self::ADDRESS,
             topics: Vec::new(),
             data: promise.try_to_vec().unwrap(),
         };
-        let exit_event_log = exit_event.encode();
+        let exit_event_log = events::ExitNftToNear {
+            sender: erc721_address,
+            erc721_address,
+            dest: receiver_account_id.to_string(),
+            token_id,
+        }
+        .encode();
         let exit_event_log = Log {
             address: Self::ADDRESS,
             topics: exit_event_log.topics,
@@ -409,6 +770,7 @@ impl Precompile for ExitToNear {
         };
 
         Ok(PrecompileOutput {
+            cost,
             logs: vec![promise_log, exit_event_log],
             ..Default::default()
         }
@@ -418,6 +780,7 @@ impl Precompile for ExitToNear {
 
 pub struct ExitToEthereum {
     current_account_id: AccountId,
+    eth_gas_per_tgas: u64,
 }
 
 impl ExitToEthereum {
@@ -428,14 +791,18 @@ impl ExitToEthereum {
     pub const ADDRESS: Address =
         super::make_address(0xb0bd02f6, 0xa392af548bdf1cfaee5dfa0eefcc8eab);
 
-    pub fn new(current_account_id: AccountId) -> Self {
-        Self { current_account_id }
+    pub fn new(current_account_id: AccountId, eth_gas_per_tgas: u64) -> Self {
+        Self {
+            current_account_id,
+            eth_gas_per_tgas,
+        }
     }
 }
 
 impl Precompile for ExitToEthereum {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
-        Ok(costs::EXIT_TO_ETHEREUM_GAS)
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        near_gas::to_eth_gas(costs::exit_to_ethereum_gas(), self.eth_gas_per_tgas)
+            .ok_or(ExitError::OutOfGas)
     }
 
     #[cfg(not(feature = "contract"))]
@@ -446,13 +813,17 @@ impl Precompile for ExitToEthereum {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        if let Some(target_gas) = target_gas {
-            if Self::required_gas(input)? > target_gas {
-                return Err(ExitError::OutOfGas);
-            }
-        }
+        let cost = near_gas::charge(
+            costs::exit_to_ethereum_gas(),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
 
-        Ok(PrecompileOutput::default().into())
+        Ok(PrecompileOutput {
+            cost,
+            ..Default::default()
+        }
+        .into())
     }
 
     #[cfg(feature = "contract")]
@@ -463,11 +834,11 @@ impl Precompile for ExitToEthereum {
         context: &Context,
         is_static: bool,
     ) -> EvmPrecompileResult {
-        if let Some(target_gas) = target_gas {
-            if Self::required_gas(input)? > target_gas {
-                return Err(ExitError::OutOfGas);
-            }
-        }
+        let cost = near_gas::charge(
+            costs::exit_to_ethereum_gas(),
+            self.eth_gas_per_tgas,
+            target_gas,
+        )?;
 
         // It's not allowed to call exit precompiles in static mode
         if is_static {
@@ -587,6 +958,7 @@ impl Precompile for ExitToEthereum {
         };
 
         Ok(PrecompileOutput {
+            cost,
             logs: vec![promise_log, exit_event_log],
             ..Default::default()
         }
@@ -596,8 +968,16 @@ impl Precompile for ExitToEthereum {
 
 #[cfg(test)]
 mod tests {
-    use super::{ExitToEthereum, ExitToNear};
+    use super::{
+        costs, exit_to_near_has_msg, exit_to_near_has_refund, parse_exit_to_near_input,
+        parse_recipient, refund_address_len, ExitNftToNear, ExitToEthereum, ExitToNear,
+    };
+    use crate::near_gas::{self, DEFAULT_ETH_GAS_PER_TGAS, PROMISE_CREATE_GAS};
     use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::types::{self, EthGas};
+    use crate::prelude::{vec, Address, U256};
+    use crate::utils::new_context;
+    use crate::Precompile;
 
     #[test]
     fn test_precompile_id() {
@@ -609,12 +989,17 @@ mod tests {
             ExitToNear::ADDRESS,
             near_account_to_evm_address("exitToNear".as_bytes())
         );
+        assert_eq!(
+            ExitNftToNear::ADDRESS,
+            near_account_to_evm_address("exitNftToNear".as_bytes())
+        );
     }
 
     #[test]
     fn test_exit_signatures() {
         let exit_to_near = super::events::exit_to_near_schema();
         let exit_to_eth = super::events::exit_to_eth_schema();
+        let exit_nft_to_near = super::events::exit_nft_to_near_schema();
 
         assert_eq!(
             exit_to_near.signature(),
@@ -624,5 +1009,129 @@ mod tests {
             exit_to_eth.signature(),
             super::events::EXIT_TO_ETH_SIGNATURE
         );
+        assert_eq!(
+            exit_nft_to_near.signature(),
+            super::events::EXIT_NFT_TO_NEAR_SIGNATURE
+        );
+    }
+
+    #[test]
+    fn test_parse_recipient() {
+        assert_eq!(parse_recipient(b"alice.near"), (&b"alice.near"[..], None));
+        assert_eq!(
+            parse_recipient(b"pool.near:deposit"),
+            (&b"pool.near"[..], Some(&b"deposit"[..]))
+        );
+        assert_eq!(
+            parse_recipient(b"pool.near:a:b"),
+            (&b"pool.near"[..], Some(&b"a:b"[..]))
+        );
+        assert_eq!(
+            parse_recipient(b"pool.near:"),
+            (&b"pool.near"[..], Some(&b""[..]))
+        );
+    }
+
+    #[test]
+    fn test_exit_to_near_has_msg() {
+        let refund_address = |flag| vec![0x3au8; refund_address_len(flag)];
+        let eth_exit = |recipient: &[u8]| [&[0x0][..], &refund_address(0x0), recipient].concat();
+        assert!(!exit_to_near_has_msg(&eth_exit(b"alice.near")));
+        assert!(exit_to_near_has_msg(&eth_exit(b"pool.near:deposit")));
+
+        // A `:` byte in the sender or the amount is not a separator.
+        let erc20_exit = |recipient: &[u8]| {
+            [&[0x2][..], &refund_address(0x2), &[0x3a; 32][..], recipient].concat()
+        };
+        assert!(!exit_to_near_has_msg(&erc20_exit(b"alice.near")));
+        assert!(exit_to_near_has_msg(&erc20_exit(b"pool.near:deposit")));
+
+        // Legacy ERC-20 exits never carry a message.
+        let legacy_erc20_exit = |recipient: &[u8]| {
+            [&[0x1][..], &refund_address(0x1), &[0x3a; 32][..], recipient].concat()
+        };
+        assert!(!exit_to_near_has_msg(&legacy_erc20_exit(
+            b"pool.near:deposit"
+        )));
+
+        assert!(!exit_to_near_has_msg(&[]));
+    }
+
+    #[test]
+    fn test_exit_to_near_legacy_erc20_input() {
+        // The input of bridged ERC-20 contracts deployed before the sender was added to it.
+        let amount = U256::from(1_000_000_u64);
+        let mut input = vec![0x1];
+        input.resize(1 + refund_address_len(0x1), 0x3a);
+        input.extend_from_slice(&types::u256_to_arr(&amount));
+        input.extend_from_slice(b"alice.near");
+
+        let parsed = parse_exit_to_near_input(&input).unwrap();
+        assert_eq!(parsed.amount, Some(amount));
+        assert_eq!(parsed.recipient.as_bytes(), b"alice.near");
+        assert!(parsed.msg.is_none());
+        assert_eq!(
+            parsed.refund_address.is_some(),
+            cfg!(feature = "error_refund")
+        );
+
+        let exit_to_near = ExitToNear::new("aurora".parse().unwrap(), DEFAULT_ETH_GAS_PER_TGAS);
+        let output = exit_to_near
+            .run(&input, None, &new_context(), false)
+            .unwrap();
+        assert_eq!(
+            EthGas::new(output.cost),
+            exit_to_near.required_gas(&input).unwrap()
+        );
+        assert_eq!(
+            exit_to_near.required_gas(&input).unwrap(),
+            near_gas::to_eth_gas(costs::exit_to_near_gas(false), DEFAULT_ETH_GAS_PER_TGAS).unwrap()
+        );
+
+        // The legacy format has no message, so `:` is an invalid receiver.
+        input.extend_from_slice(b":deposit");
+        assert!(parse_exit_to_near_input(&input).is_err());
+    }
+
+    #[test]
+    fn test_exit_to_near_erc20_input() {
+        let sender = Address::from_slice(&[0x11; 20]);
+        let amount = U256::from(1_000_000_u64);
+        let input = [
+            &[0x2][..],
+            sender.as_bytes(),
+            &types::u256_to_arr(&amount),
+            b"pool.near:deposit",
+        ]
+        .concat();
+
+        let parsed = parse_exit_to_near_input(&input).unwrap();
+        assert_eq!(parsed.refund_address, Some(sender));
+        assert_eq!(parsed.amount, Some(amount));
+        assert_eq!(parsed.recipient.as_bytes(), b"pool.near");
+        assert_eq!(parsed.msg, Some("deposit"));
+
+        // Truncated inputs and unknown flags are rejected instead of misread.
+        assert!(parse_exit_to_near_input(&input[..40]).is_err());
+        assert!(parse_exit_to_near_input(&[0x3]).is_err());
+        assert!(parse_exit_to_near_input(&[]).is_err());
+    }
+
+    #[test]
+    fn test_exit_to_near_refund_gas() {
+        // `ft_transfer_call` may return part of the amount unused, so it is always refunded.
+        assert!(exit_to_near_has_refund(true));
+        assert_eq!(
+            costs::exit_to_near_gas(true),
+            PROMISE_CREATE_GAS
+                + costs::FT_TRANSFER_CALL_GAS
+                + PROMISE_CREATE_GAS
+                + costs::REFUND_ON_ERROR_GAS
+        );
+
+        assert_eq!(
+            exit_to_near_has_refund(false),
+            cfg!(feature = "error_refund")
+        );
     }
 }
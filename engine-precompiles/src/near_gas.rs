@@ -0,0 +1,122 @@
+use crate::prelude::{
+    sdk::io::{StorageIntermediate, IO},
+    storage::{bytes_to_key, KeyPrefix},
+    types::{EthGas, NearGas},
+    BorshSerialize,
+};
+use evm::ExitError;
+
+/// Key under which the NEAR to EVM gas ratio is kept in the engine config storage.
+const RATIO_KEY: &[u8] = b"NEAR_GAS_RATIO";
+
+/// EVM gas charged per Tgas when the owner has not set a ratio. This is roughly the amount
+/// of EVM gas the engine can execute with one Tgas.
+pub const DEFAULT_ETH_GAS_PER_TGAS: u64 = 4_000;
+
+const TGAS: u128 = 1_000_000_000_000;
+
+/// NEAR gas burnt to create a promise with a single function call action, on top of the gas
+/// attached to it.
+pub const PROMISE_CREATE_GAS: NearGas = NearGas::new(5_000_000_000_000);
+
+pub fn get_eth_gas_per_tgas<I: IO>(io: &I) -> u64 {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, RATIO_KEY))
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(DEFAULT_ETH_GAS_PER_TGAS)
+}
+
+/// A ratio of zero would make the NEAR gas used by the precompiles free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroRatioError;
+
+impl AsRef<[u8]> for ZeroRatioError {
+    fn as_ref(&self) -> &[u8] {
+        b"ERR_ZERO_NEAR_GAS_RATIO"
+    }
+}
+
+pub fn set_eth_gas_per_tgas<I: IO>(
+    io: &mut I,
+    eth_gas_per_tgas: u64,
+) -> Result<(), ZeroRatioError> {
+    if eth_gas_per_tgas == 0 {
+        return Err(ZeroRatioError);
+    }
+    io.write_storage(
+        &bytes_to_key(KeyPrefix::Config, RATIO_KEY),
+        &eth_gas_per_tgas.try_to_vec().expect("ERR_SER"),
+    );
+    Ok(())
+}
+
+/// Converts NEAR gas into EVM gas, rounding up. Returns `None` on overflow.
+pub fn to_eth_gas(near_gas: NearGas, eth_gas_per_tgas: u64) -> Option<EthGas> {
+    let eth_gas =
+        (u128::from(near_gas.into_u64()) * u128::from(eth_gas_per_tgas) + TGAS - 1) / TGAS;
+    if eth_gas > u128::from(u64::MAX) {
+        None
+    } else {
+        Some(EthGas::new(eth_gas as u64))
+    }
+}
+
+/// Returns the EVM gas to charge for the given NEAR gas, or `OutOfGas` if it is more than
+/// the target gas.
+pub fn charge(
+    near_gas: NearGas,
+    eth_gas_per_tgas: u64,
+    target_gas: Option<EthGas>,
+) -> Result<EthGas, ExitError> {
+    let cost = to_eth_gas(near_gas, eth_gas_per_tgas).ok_or(ExitError::OutOfGas)?;
+    if let Some(target_gas) = target_gas {
+        if cost > target_gas {
+            return Err(ExitError::OutOfGas);
+        }
+    }
+    Ok(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::EmptyStorage;
+
+    #[test]
+    fn test_set_eth_gas_per_tgas() {
+        assert_eq!(
+            set_eth_gas_per_tgas(&mut EmptyStorage, 0),
+            Err(ZeroRatioError)
+        );
+        assert_eq!(set_eth_gas_per_tgas(&mut EmptyStorage, 1), Ok(()));
+    }
+
+    #[test]
+    fn test_to_eth_gas() {
+        assert_eq!(
+            to_eth_gas(NearGas::new(100_000_000_000_000), 4_000),
+            Some(EthGas::new(400_000))
+        );
+        // rounds up
+        assert_eq!(to_eth_gas(NearGas::new(1), 4_000), Some(EthGas::new(1)));
+        assert_eq!(to_eth_gas(NearGas::new(0), 4_000), Some(EthGas::new(0)));
+        assert_eq!(to_eth_gas(NearGas::new(u64::MAX), u64::MAX), None);
+    }
+
+    #[test]
+    fn test_charge() {
+        let near_gas = NearGas::new(2_000_000_000_000);
+        assert_eq!(charge(near_gas, 4_000, None).unwrap(), EthGas::new(8_000));
+        assert_eq!(
+            charge(near_gas, 4_000, Some(EthGas::new(8_000))).unwrap(),
+            EthGas::new(8_000)
+        );
+        assert!(matches!(
+            charge(near_gas, 4_000, Some(EthGas::new(7_999))),
+            Err(ExitError::OutOfGas)
+        ));
+        assert!(matches!(
+            charge(NearGas::new(u64::MAX), u64::MAX, None),
+            Err(ExitError::OutOfGas)
+        ));
+    }
+}
@@ -1,18 +1,100 @@
 use super::{EvmPrecompileResult, Precompile};
-use crate::prelude::types::EthGas;
-use crate::prelude::{Address, H256};
+use crate::near_gas;
+use crate::prelude::{
+    sdk::{
+        self,
+        io::{StorageIntermediate, IO},
+    },
+    storage::{bytes_to_key, KeyPrefix},
+    types::EthGas,
+    AccountId, Address, BorshSerialize, Vec, H256,
+};
 use crate::PrecompileOutput;
+use core::cell::Cell;
 use evm::{Context, ExitError};
 
 mod costs {
-    use crate::prelude::types::EthGas;
+    use crate::prelude::types::{EthGas, NearGas};
+
+    /// NEAR gas of the `random_seed` host function and of reading the seed from its register.
+    pub(super) const RANDOM_BYTES_GAS: NearGas = NearGas::new(3_000_000_000);
+
+    /// Same as the `KECCAK256` opcode on the 92 bytes mixed into the unique seed.
+    pub(super) const MIX_GAS: EthGas = EthGas::new(48);
+}
+
+/// Key under which the number of EVM executions without a transaction hash is kept in the
+/// engine config storage.
+const EXECUTION_NONCE_KEY: &[u8] = b"EXECUTION_NONCE";
+
+/// Identifies the EVM execution for `UniqueRandomSeed`: the hash of the signed transaction
+/// for `submit`. The other executions (`call`, `deploy_code`, `ft_on_transfer`, callbacks,
+/// ...) have no transaction hash, so they are told apart by the predecessor account and a
+/// stored nonce. The id is only computed when first used, so that the nonce is only
+/// incremented by the executions which need it.
+pub struct ExecutionId<I> {
+    io: I,
+    transaction_hash: H256,
+    predecessor_account_id: AccountId,
+    increment_nonce: bool,
+    value: Cell<Option<H256>>,
+}
+
+impl<I: IO + Copy> ExecutionId<I> {
+    pub fn new(io: I, transaction_hash: H256, predecessor_account_id: AccountId) -> Self {
+        Self {
+            io,
+            transaction_hash,
+            predecessor_account_id,
+            increment_nonce: true,
+            value: Cell::new(None),
+        }
+    }
+
+    /// Same as `new`, without incrementing the nonce. Used for views, which do not change
+    /// the state.
+    pub fn new_view(io: I, transaction_hash: H256, predecessor_account_id: AccountId) -> Self {
+        Self {
+            increment_nonce: false,
+            ..Self::new(io, transaction_hash, predecessor_account_id)
+        }
+    }
+
+    pub fn get(&self) -> H256 {
+        if let Some(value) = self.value.get() {
+            return value;
+        }
+        let value = if self.transaction_hash.is_zero() {
+            let nonce = get_execution_nonce(&self.io);
+            if self.increment_nonce {
+                let mut io = self.io;
+                io.write_storage(
+                    &bytes_to_key(KeyPrefix::Config, EXECUTION_NONCE_KEY),
+                    &(nonce + 1).try_to_vec().expect("ERR_SER"),
+                );
+            }
+            execution_id(&self.predecessor_account_id, nonce)
+        } else {
+            self.transaction_hash
+        };
+        self.value.set(Some(value));
+        value
+    }
+}
+
+fn get_execution_nonce<I: IO>(io: &I) -> u64 {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, EXECUTION_NONCE_KEY))
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(0)
+}
 
-    // TODO(#51): Determine the correct amount of gas
-    pub(super) const RANDOM_BYTES_GAS: EthGas = EthGas::new(0);
+fn execution_id(predecessor_account_id: &AccountId, nonce: u64) -> H256 {
+    sdk::keccak(&[predecessor_account_id.as_bytes(), &nonce.to_be_bytes()].concat())
 }
 
 pub struct RandomSeed {
     random_seed: H256,
+    eth_gas_per_tgas: u64,
 }
 
 impl RandomSeed {
@@ -25,38 +107,117 @@ impl RandomSeed {
     pub const ADDRESS: Address =
         super::make_address(0xc104f484, 0x0573bed437190daf5d2898c2bdf928ac);
 
-    pub fn new(random_seed: H256) -> Self {
-        Self { random_seed }
+    pub fn new(random_seed: H256, eth_gas_per_tgas: u64) -> Self {
+        Self {
+            random_seed,
+            eth_gas_per_tgas,
+        }
     }
 }
 
 impl Precompile for RandomSeed {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
-        Ok(costs::RANDOM_BYTES_GAS)
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        near_gas::to_eth_gas(costs::RANDOM_BYTES_GAS, self.eth_gas_per_tgas)
+            .ok_or(ExitError::OutOfGas)
     }
 
     fn run(
         &self,
-        input: &[u8],
+        _input: &[u8],
         target_gas: Option<EthGas>,
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = near_gas::charge(costs::RANDOM_BYTES_GAS, self.eth_gas_per_tgas, target_gas)?;
+
+        Ok(PrecompileOutput::without_logs(cost, self.random_seed.as_bytes().to_vec()).into())
+    }
+}
+
+pub struct UniqueRandomSeed<'a, I> {
+    random_seed: H256,
+    execution_id: &'a ExecutionId<I>,
+    eth_gas_per_tgas: u64,
+    /// Number of times the precompile was called in the current execution.
+    counter: Cell<u64>,
+}
+
+impl<'a, I> UniqueRandomSeed<'a, I> {
+    /// Unique random bytes precompile address
+    /// Unlike `RandomSeed`, every call returns a different value. The block seed is mixed
+    /// with the execution id (see `ExecutionId`), the caller and the number of
+    /// previous calls in the execution:
+    /// `keccak(random_seed ++ execution_id ++ caller ++ counter)`.
+    ///
+    /// Address: `0xf16b189cdc31a6a3324f95527abe8ace0a24fbd2`
+    /// This address is computed as: `&keccak("uniqueRandomSeed")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0xf16b189c, 0xdc31a6a3324f95527abe8ace0a24fbd2);
+
+    pub fn new(random_seed: H256, execution_id: &'a ExecutionId<I>, eth_gas_per_tgas: u64) -> Self {
+        Self {
+            random_seed,
+            execution_id,
+            eth_gas_per_tgas,
+            counter: Cell::new(0),
+        }
+    }
+}
+
+impl<'a, I: IO + Copy> UniqueRandomSeed<'a, I> {
+    fn next_seed(&self, caller: &Address) -> H256 {
+        let counter = self.counter.get();
+        self.counter.set(counter + 1);
+
+        let mut data = Vec::with_capacity(32 + 32 + 20 + 8);
+        data.extend_from_slice(self.random_seed.as_bytes());
+        data.extend_from_slice(self.execution_id.get().as_bytes());
+        data.extend_from_slice(caller.as_bytes());
+        data.extend_from_slice(&counter.to_be_bytes());
+        sdk::keccak(&data)
+    }
+}
+
+impl<'a, I: IO + Copy> Precompile for UniqueRandomSeed<'a, I> {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        near_gas::to_eth_gas(costs::RANDOM_BYTES_GAS, self.eth_gas_per_tgas)
+            .and_then(|cost| cost.into_u64().checked_add(costs::MIX_GAS.into_u64()))
+            .map(EthGas::new)
+            .ok_or(ExitError::OutOfGas)
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
             }
         }
 
-        Ok(PrecompileOutput::without_logs(cost, self.random_seed.as_bytes().to_vec()).into())
+        let seed = self.next_seed(&context.caller);
+        Ok(PrecompileOutput::without_logs(cost, seed.as_bytes().to_vec()).into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::prelude::sdk::types::near_account_to_evm_address;
-    use crate::random::RandomSeed;
+    use crate::prelude::types::EthGas;
+    use crate::prelude::{AccountId, Address, H256};
+    use crate::random::{ExecutionId, RandomSeed, UniqueRandomSeed};
+    use crate::utils::{new_context, EmptyStorage};
+    use crate::Precompile;
+
+    fn new_execution_id(transaction_hash: H256) -> ExecutionId<EmptyStorage> {
+        let predecessor_account_id = AccountId::new("aurora").unwrap();
+        ExecutionId::new(EmptyStorage, transaction_hash, predecessor_account_id)
+    }
 
     #[test]
     fn test_precompile_id() {
@@ -64,5 +225,71 @@ mod tests {
             RandomSeed::ADDRESS,
             near_account_to_evm_address("randomSeed".as_bytes())
         );
+        assert_eq!(
+            UniqueRandomSeed::ADDRESS,
+            near_account_to_evm_address("uniqueRandomSeed".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_unique_random_seed() {
+        let seed = H256::repeat_byte(1);
+        let run = |precompile: &UniqueRandomSeed<EmptyStorage>, caller: Address| {
+            let context = evm::Context {
+                caller,
+                ..new_context()
+            };
+            precompile.run(&[], None, &context, false).unwrap().output
+        };
+
+        let execution_id = new_execution_id(H256::repeat_byte(2));
+        let precompile = UniqueRandomSeed::new(seed, &execution_id, 4_000);
+        let first = run(&precompile, Address::zero());
+        let second = run(&precompile, Address::zero());
+        let other_caller = run(&precompile, Address::repeat_byte(3));
+        assert_ne!(first, seed.as_bytes());
+        assert_ne!(first, second);
+        assert_ne!(second, other_caller);
+
+        // The same inputs give the same sequence.
+        let precompile = UniqueRandomSeed::new(seed, &execution_id, 4_000);
+        assert_eq!(run(&precompile, Address::zero()), first);
+
+        // A different execution gives a different sequence.
+        let other_execution_id = new_execution_id(H256::repeat_byte(4));
+        let precompile = UniqueRandomSeed::new(seed, &other_execution_id, 4_000);
+        assert_ne!(run(&precompile, Address::zero()), first);
+
+        // `RandomSeed` keeps returning the block seed.
+        let precompile = RandomSeed::new(seed, 4_000);
+        for _ in 0..2 {
+            let output = precompile.run(&[], None, &new_context(), false).unwrap();
+            assert_eq!(output.output, seed.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_random_seed_gas() {
+        // Both precompiles charge at the configured ratio, the same as `required_gas`.
+        let seed = H256::repeat_byte(1);
+        let precompile = RandomSeed::new(seed, 8_000);
+        let cost = precompile
+            .run(&[], None, &new_context(), false)
+            .unwrap()
+            .cost;
+        assert_eq!(cost, 24);
+        assert_eq!(precompile.required_gas(&[]).unwrap(), EthGas::new(cost));
+
+        let execution_id = new_execution_id(H256::repeat_byte(2));
+        let precompile = UniqueRandomSeed::new(seed, &execution_id, 8_000);
+        let cost = precompile
+            .run(&[], None, &new_context(), false)
+            .unwrap()
+            .cost;
+        assert_eq!(cost, 72);
+        assert_eq!(precompile.required_gas(&[]).unwrap(), EthGas::new(cost));
+
+        let res = precompile.run(&[], Some(EthGas::new(71)), &new_context(), false);
+        assert!(matches!(res, Err(evm::ExitError::OutOfGas)));
     }
 }
@@ -0,0 +1,38 @@
+use crate::prelude::{
+    parameters::{DisabledPrecompileMode, SetPrecompileStatusArgs},
+    sdk::io::{StorageIntermediate, IO},
+    storage::{bytes_to_key, KeyPrefix},
+    types::RawAddress,
+    Address, BTreeMap, BorshSerialize, Vec,
+};
+
+/// Key under which the precompiles disabled by the owner are kept in the engine config storage.
+const REGISTRY_KEY: &[u8] = b"DISABLED_PRECOMPILES";
+
+/// Returns the precompiles disabled by the owner. All precompiles are enabled by default.
+pub fn get_disabled<I: IO>(io: &I) -> BTreeMap<Address, DisabledPrecompileMode> {
+    read_disabled(io)
+        .into_iter()
+        .map(|(address, mode)| (Address::from(address), mode))
+        .collect()
+}
+
+/// Enables or disables a single precompile address.
+pub fn set_status<I: IO>(io: &mut I, args: &SetPrecompileStatusArgs) {
+    let mut disabled = read_disabled(io);
+    disabled.retain(|(address, _)| address != &args.address);
+    if let Some(mode) = args.disabled {
+        disabled.push((args.address, mode));
+    }
+    io.write_storage(
+        &bytes_to_key(KeyPrefix::Config, REGISTRY_KEY),
+        &disabled.try_to_vec().expect("ERR_SER"),
+    );
+}
+
+/// Returns the disabled precompiles in the form they are stored in.
+pub fn read_disabled<I: IO>(io: &I) -> Vec<(RawAddress, DisabledPrecompileMode)> {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, REGISTRY_KEY))
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
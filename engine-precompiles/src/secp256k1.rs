@@ -59,7 +59,7 @@ impl ECRecover {
 }
 
 impl Precompile for ECRecover {
-    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(costs::ECRECOVER_BASE)
     }
 
@@ -70,7 +70,7 @@ impl Precompile for ECRecover {
         _context: &Context,
         _is_static: bool,
     ) -> EvmPrecompileResult {
-        let cost = Self::required_gas(input)?;
+        let cost = self.required_gas(input)?;
         if let Some(target_gas) = target_gas {
             if cost > target_gas {
                 return Err(ExitError::OutOfGas);
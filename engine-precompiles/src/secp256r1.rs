@@ -0,0 +1,174 @@
+use crate::prelude::types::EthGas;
+use crate::prelude::{Address, Vec};
+use crate::{EvmPrecompileResult, Precompile, PrecompileOutput};
+use evm::{Context, ExitError};
+use p256::elliptic_curve::group::ff::{Field, PrimeField};
+use p256::elliptic_curve::group::{Curve, Group};
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    /// Cost of the P256VERIFY operation (see RIP-7212).
+    pub(super) const P256_VERIFY: EthGas = EthGas::new(3_450);
+}
+
+mod consts {
+    /// Input length: the message hash, the signature `r` and `s` and the public key
+    /// coordinates `x` and `y`, each as a 32-byte word.
+    pub(super) const INPUT_LEN: usize = 160;
+}
+
+/// Verifies a secp256r1 signature over a prehashed message. Returns `None` for any invalid
+/// input so the caller can treat it the same as a failed verification.
+fn verify(input: &[u8]) -> Option<()> {
+    if input.len() != consts::INPUT_LEN {
+        return None;
+    }
+    let word = |i: usize| {
+        let mut bytes = FieldBytes::default();
+        bytes.copy_from_slice(&input[(i * 32)..((i + 1) * 32)]);
+        bytes
+    };
+
+    let z = Scalar::from_bytes_reduced(&word(0));
+    let r =
+        Option::<Scalar>::from(Scalar::from_repr(word(1))).filter(|r| !bool::from(r.is_zero()))?;
+    let s =
+        Option::<Scalar>::from(Scalar::from_repr(word(2))).filter(|s| !bool::from(s.is_zero()))?;
+    let public_key = Option::<AffinePoint>::from(AffinePoint::from_encoded_point(
+        &EncodedPoint::from_affine_coordinates(&word(3), &word(4), false),
+    ))?;
+
+    let s_inv = Option::<Scalar>::from(s.invert())?;
+    let point = ProjectivePoint::generator() * (z * s_inv)
+        + ProjectivePoint::from(public_key) * (r * s_inv);
+    if bool::from(point.is_identity()) {
+        return None;
+    }
+
+    let x = *point.to_affine().to_encoded_point(false).x()?;
+    if Scalar::from_bytes_reduced(&x) == r {
+        Some(())
+    } else {
+        None
+    }
+}
+
+pub struct P256Verify;
+
+impl P256Verify {
+    pub const ADDRESS: Address = super::make_address(0, 0x100);
+}
+
+impl Precompile for P256Verify {
+    fn required_gas(&self, _input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::P256_VERIFY)
+    }
+
+    /// Takes in a message hash, a signature and a public key on the secp256r1 curve and
+    /// returns `1` as a 32-byte word if the signature is valid. Returns empty output
+    /// otherwise, including for malformed input.
+    ///
+    /// See: https://github.com/ethereum/RIPs/blob/master/RIPS/rip-7212.md
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let output = match verify(input) {
+            Some(()) => {
+                let mut output = [0u8; 32];
+                output[31] = 1;
+                output.to_vec()
+            }
+            None => Vec::new(),
+        };
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::String;
+    use crate::utils::new_context;
+
+    use super::*;
+
+    // Valid signature test vector from RIP-7212.
+    const VALID_INPUT: &str = "\
+        4cee90eb86eaa050036147a12d49004b6b9c72bd725d39d4785011fe190f0b4d\
+        a73bd4903f0ce3b639bbbf6e8e80d16931ff4bcf5993d58468e8fb19086e8cac\
+        36dbcd03009df8c59286b162af3bd7fcc0450c9aa81be5d10d312af6c66b1d60\
+        4aebd3099c618202fcfe16ae7770b0c49ab5eadf74b754204a3bb6060e44eff3\
+        7618b065f9832de4ca6ca971a7a1adc826d0f7c00181a5fb2ddf79ae00b4e10e";
+
+    /// The Wycheproof `ecdsa_secp256r1_sha256` vectors in the RIP-7212 input format. The
+    /// messages are prehashed with SHA-256; signatures which are not strict DER or have
+    /// integers longer than 32 bytes can't be encoded in the input and are left out.
+    const TEST_VECTORS: &str = include_str!("res/p256_verify.json");
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct TestVector {
+        name: String,
+        input: String,
+        expected: String,
+    }
+
+    fn run(input: &[u8]) -> Vec<u8> {
+        P256Verify
+            .run(input, Some(EthGas::new(3_450)), &new_context(), false)
+            .unwrap()
+            .output
+    }
+
+    #[test]
+    fn test_p256_verify() {
+        let input = hex::decode(VALID_INPUT).unwrap();
+        let expected =
+            hex::decode("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+        assert_eq!(run(&input), expected);
+
+        // out of gas test
+        let res = P256Verify.run(&input, Some(EthGas::new(3_449)), &new_context(), false);
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+
+        // wrong message hash
+        let mut wrong_hash = input.clone();
+        wrong_hash[0] ^= 1;
+        assert!(run(&wrong_hash).is_empty());
+
+        // zero `r`
+        let mut zero_r = input.clone();
+        zero_r[32..64].copy_from_slice(&[0u8; 32]);
+        assert!(run(&zero_r).is_empty());
+
+        // public key not on curve
+        let mut invalid_key = input.clone();
+        invalid_key[159] ^= 1;
+        assert!(run(&invalid_key).is_empty());
+
+        // invalid input length
+        assert!(run(&input[..159]).is_empty());
+        assert!(run(&[input.as_slice(), &[0]].concat()).is_empty());
+        assert!(run(&[]).is_empty());
+
+        let vectors: Vec<TestVector> = serde_json::from_str(TEST_VECTORS).unwrap();
+        for vector in vectors {
+            let input = hex::decode(&vector.input).unwrap();
+            assert_eq!(hex::encode(run(&input)), vector.expected, "{}", vector.name);
+        }
+    }
+}
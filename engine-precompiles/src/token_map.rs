@@ -0,0 +1,207 @@
+use super::{EvmPrecompileResult, Precompile};
+use crate::prelude::{
+    sdk::io::{StorageIntermediate, IO},
+    storage::{bytes_to_key, KeyPrefix},
+    Vec,
+};
+use crate::prelude::{types::EthGas, Address};
+use crate::PrecompileOutput;
+use evm::{Context, ExitError};
+
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    /// Same as a cold `SLOAD` (see EIP-2929).
+    pub(super) const TOKEN_LOOKUP_BASE: EthGas = EthGas::new(2_100);
+
+    /// The cost per word of input.
+    pub(super) const TOKEN_LOOKUP_PER_WORD: EthGas = EthGas::new(3);
+}
+
+mod consts {
+    pub(super) const TOKEN_LOOKUP_WORD_LEN: u64 = 32;
+}
+
+fn lookup_gas(input: &[u8]) -> EthGas {
+    (input.len() as u64 + consts::TOKEN_LOOKUP_WORD_LEN - 1) / consts::TOKEN_LOOKUP_WORD_LEN
+        * costs::TOKEN_LOOKUP_PER_WORD
+        + costs::TOKEN_LOOKUP_BASE
+}
+
+fn read_map<I: IO>(io: &I, prefix: KeyPrefix, key: &[u8]) -> Option<Vec<u8>> {
+    io.read_storage(bytes_to_key(prefix, key).as_slice())
+        .map(|s| s.to_vec())
+}
+
+/// Looks up the NEP-141 token bridged to an ERC-20 deployed by the engine.
+pub struct Nep141FromErc20<I> {
+    io: I,
+}
+
+impl<I> Nep141FromErc20<I> {
+    /// NEP-141 from ERC-20 lookup precompile address
+    ///
+    /// Input: the 20-byte ERC-20 address (optionally left-padded to a 32-byte word).
+    /// Output: the UTF-8 bytes of the NEP-141 account ID, or empty if the token is not mapped.
+    ///
+    /// Address: `0x8f374af69f163e381ed74bd71dce785368515c7e`
+    /// This address is computed as: `&keccak("nep141FromErc20")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0x8f374af6, 0x9f163e381ed74bd71dce785368515c7e);
+
+    pub fn new(io: I) -> Self {
+        Self { io }
+    }
+}
+
+impl<I: IO> Precompile for Nep141FromErc20<I> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(lookup_gas(input))
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let erc20_address = match input.len() {
+            20 => input,
+            32 if input[..12] == [0u8; 12] => &input[12..],
+            _ => {
+                return Err(ExitError::Other(crate::prelude::Cow::from(
+                    "ERR_INVALID_ADDRESS",
+                )))
+            }
+        };
+
+        let output =
+            read_map(&self.io, KeyPrefix::Erc20Nep141Map, erc20_address).unwrap_or_default();
+
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+/// Looks up the ERC-20 deployed by the engine for a NEP-141 token.
+pub struct Erc20FromNep141<I> {
+    io: I,
+}
+
+impl<I> Erc20FromNep141<I> {
+    /// ERC-20 from NEP-141 lookup precompile address
+    ///
+    /// Input: the UTF-8 bytes of the NEP-141 account ID.
+    /// Output: the ERC-20 address as a 32-byte word, or empty if the token is not mapped.
+    ///
+    /// Address: `0x3fc1dba56cb6e79bec7f727d84d01c17a533da50`
+    /// This address is computed as: `&keccak("erc20FromNep141")[12..]`
+    pub const ADDRESS: Address =
+        super::make_address(0x3fc1dba5, 0x6cb6e79bec7f727d84d01c17a533da50);
+
+    pub fn new(io: I) -> Self {
+        Self { io }
+    }
+}
+
+impl<I: IO> Precompile for Erc20FromNep141<I> {
+    fn required_gas(&self, input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(lookup_gas(input))
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = self.required_gas(input)?;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let output = read_map(&self.io, KeyPrefix::Nep141Erc20Map, input)
+            .filter(|address| address.len() == 20)
+            .map(|address| {
+                let mut word = [0u8; 32];
+                word[12..].copy_from_slice(&address);
+                word.to_vec()
+            })
+            .unwrap_or_default();
+
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Erc20FromNep141, Nep141FromErc20};
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::types::EthGas;
+    use crate::utils::{new_context, EmptyStorage};
+    use crate::Precompile;
+    use evm::ExitError;
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(
+            Nep141FromErc20::<EmptyStorage>::ADDRESS,
+            near_account_to_evm_address("nep141FromErc20".as_bytes())
+        );
+        assert_eq!(
+            Erc20FromNep141::<EmptyStorage>::ADDRESS,
+            near_account_to_evm_address("erc20FromNep141".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_lookup_gas() {
+        assert_eq!(
+            Nep141FromErc20::new(EmptyStorage)
+                .required_gas(&[0u8; 20])
+                .unwrap(),
+            EthGas::new(2_103)
+        );
+        assert_eq!(
+            Erc20FromNep141::new(EmptyStorage)
+                .required_gas(b"a-very-long-token-name.bridge.near")
+                .unwrap(),
+            EthGas::new(2_106)
+        );
+
+        let res = Nep141FromErc20::new(EmptyStorage).run(
+            &[0u8; 20],
+            Some(EthGas::new(2_102)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(res, Err(ExitError::OutOfGas)));
+    }
+
+    #[test]
+    fn test_nep141_from_erc20_input() {
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(&[1u8; 20]);
+        assert!(Nep141FromErc20::new(EmptyStorage)
+            .run(&word, None, &new_context(), false)
+            .is_ok());
+
+        word[0] = 1;
+        assert!(Nep141FromErc20::new(EmptyStorage)
+            .run(&word, None, &new_context(), false)
+            .is_err());
+        assert!(Nep141FromErc20::new(EmptyStorage)
+            .run(&[1u8; 19], None, &new_context(), false)
+            .is_err());
+    }
+}
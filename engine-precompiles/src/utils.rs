@@ -1,3 +1,4 @@
+use crate::prelude::sdk::io::{StorageIntermediate, IO};
 use evm::Context;
 
 pub fn new_context() -> Context {
@@ -7,3 +8,55 @@ pub fn new_context() -> Context {
         apparent_value: Default::default(),
     }
 }
+
+/// Engine storage without any values.
+#[derive(Clone, Copy)]
+pub struct EmptyStorage;
+
+pub struct EmptyValue;
+
+impl StorageIntermediate for EmptyValue {
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn is_empty(&self) -> bool {
+        true
+    }
+
+    fn copy_to_slice(&self, _buffer: &mut [u8]) {}
+}
+
+impl IO for EmptyStorage {
+    type StorageValue = EmptyValue;
+
+    fn read_input(&self) -> Self::StorageValue {
+        EmptyValue
+    }
+
+    fn return_output(&mut self, _value: &[u8]) {}
+
+    fn read_storage(&self, _key: &[u8]) -> Option<Self::StorageValue> {
+        None
+    }
+
+    fn storage_has_key(&self, _key: &[u8]) -> bool {
+        false
+    }
+
+    fn write_storage(&mut self, _key: &[u8], _value: &[u8]) -> Option<Self::StorageValue> {
+        None
+    }
+
+    fn write_storage_direct(
+        &mut self,
+        _key: &[u8],
+        _value: Self::StorageValue,
+    ) -> Option<Self::StorageValue> {
+        None
+    }
+
+    fn remove_storage(&mut self, _key: &[u8]) -> Option<Self::StorageValue> {
+        None
+    }
+}
@@ -104,6 +104,22 @@ pub fn ecrecover(hash: H256, signature: &[u8]) -> Result<Address, ECRecoverErr>
     }
 }
 
+/// Verify an ed25519 signature of the given message.
+#[cfg(all(feature = "contract", feature = "ed25519_verify"))]
+pub fn ed25519_verify(public_key: &[u8; 32], signature: &[u8; 64], message: &[u8]) -> bool {
+    unsafe {
+        let result = exports::ed25519_verify(
+            signature.len() as u64,
+            signature.as_ptr() as u64,
+            message.len() as u64,
+            message.as_ptr() as u64,
+            public_key.len() as u64,
+            public_key.as_ptr() as u64,
+        );
+        result == (true as u64)
+    }
+}
+
 #[cfg(feature = "contract")]
 pub fn log(data: &str) {
     log_utf8(data.as_bytes())
@@ -1,5 +1,5 @@
 use crate::io::StorageIntermediate;
-use crate::prelude::NearGas;
+use crate::prelude::{NearGas, Vec};
 use crate::promise::PromiseId;
 use aurora_engine_types::account_id::AccountId;
 use aurora_engine_types::parameters::{PromiseAction, PromiseBatchAction, PromiseCreateArgs};
@@ -312,6 +312,12 @@ impl crate::promise::PromiseHandler for Runtime {
         PromiseId::new(id)
     }
 
+    fn promise_and(&mut self, promises: &[PromiseId]) -> PromiseId {
+        let ids: Vec<u64> = promises.iter().map(|promise| promise.raw()).collect();
+        let id = unsafe { exports::promise_and(ids.as_ptr() as _, ids.len() as _) };
+        PromiseId::new(id)
+    }
+
     fn promise_create_batch(&mut self, args: &PromiseBatchAction) -> PromiseId {
         let account_id = args.target_account_id.as_bytes();
 
@@ -352,6 +358,9 @@ impl crate::promise::PromiseHandler for Runtime {
                         *gas,
                     )
                 },
+                PromiseAction::CreateAccount => unsafe {
+                    exports::promise_batch_action_create_account(id);
+                },
             }
         }
 
@@ -409,6 +418,15 @@ pub(crate) mod exports {
             malleability_flag: u64,
             register_id: u64,
         ) -> u64;
+        #[cfg(feature = "ed25519_verify")]
+        pub(crate) fn ed25519_verify(
+            sig_len: u64,
+            sig_ptr: u64,
+            msg_len: u64,
+            msg_ptr: u64,
+            pub_key_len: u64,
+            pub_key_ptr: u64,
+        ) -> u64;
         // #####################
         // # Miscellaneous API #
         // #####################
@@ -442,13 +460,13 @@ pub(crate) mod exports {
             amount_ptr: u64,
             gas: u64,
         ) -> u64;
-        fn promise_and(promise_idx_ptr: u64, promise_idx_count: u64) -> u64;
+        pub(crate) fn promise_and(promise_idx_ptr: u64, promise_idx_count: u64) -> u64;
         pub(crate) fn promise_batch_create(account_id_len: u64, account_id_ptr: u64) -> u64;
         fn promise_batch_then(promise_index: u64, account_id_len: u64, account_id_ptr: u64) -> u64;
         // #######################
         // # Promise API actions #
         // #######################
-        fn promise_batch_action_create_account(promise_index: u64);
+        pub(crate) fn promise_batch_action_create_account(promise_index: u64);
         pub(crate) fn promise_batch_action_deploy_contract(
             promise_index: u64,
             code_len: u64,
@@ -26,6 +26,8 @@ pub trait PromiseHandler {
         base: PromiseId,
         callback: &PromiseCreateArgs,
     ) -> PromiseId;
+    /// Joins the given promises into one which resolves once all of them do.
+    fn promise_and(&mut self, promises: &[PromiseId]) -> PromiseId;
     fn promise_create_batch(&mut self, args: &PromiseBatchAction) -> PromiseId;
     fn promise_return(&mut self, promise: PromiseId);
 
@@ -26,6 +26,10 @@ impl PromiseHandler for Noop {
         PromiseId::new(0)
     }
 
+    fn promise_and(&mut self, _promises: &[PromiseId]) -> PromiseId {
+        PromiseId::new(0)
+    }
+
     fn promise_create_batch(&mut self, _args: &PromiseBatchAction) -> PromiseId {
         PromiseId::new(0)
     }
@@ -203,6 +203,7 @@ mod test {
             owner_id: "aurora".parse().unwrap(),
             bridge_prover_id: "prover.bridge.near".parse().unwrap(),
             upgrade_delay_blocks: 0,
+            unprotected_transactions_policy: Default::default(),
         };
 
         // Initialize engine and connector states in storage.
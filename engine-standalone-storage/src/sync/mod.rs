@@ -1,6 +1,9 @@
 use aurora_engine::{connector, engine, parameters};
+use aurora_engine_precompiles::{cross_contract, near_gas, registry};
 use aurora_engine_sdk::env::{self, Env, DEFAULT_PREPAID_GAS};
-use aurora_engine_types::TryFrom;
+use aurora_engine_sdk::io::IO;
+use aurora_engine_types::parameters::PromiseWithCallbackArgs;
+use aurora_engine_types::{Address, TryFrom, U256};
 use borsh::BorshDeserialize;
 
 pub mod types;
@@ -72,13 +75,36 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                     tx_hash
                 }
 
+                #[cfg(feature = "meta-call")]
+                TransactionKind::MetaCallV2(input) => {
+                    // Only promises possible from `meta_call_v2` are exit precompiles and we cannot act on those promises
+                    let mut handler = crate::promise::Noop;
+                    let _result = engine::meta_call_v2(io, &env, input, &mut handler)?;
+
+                    near_tx_hash
+                }
+
                 TransactionKind::Call(args) => {
-                    // Only promises possible from `call` are exit precompiles and we cannot act on those promises
+                    // Only promises possible from `call` are exit precompiles and the `near_deposit`
+                    // wrapping attached NEAR; we cannot act on those promises
                     let mut handler = crate::promise::Noop;
                     let mut engine =
                         engine::Engine::new(relayer_address, env.current_account_id(), io, &env)?;
 
-                    let _result = engine.call_with_args(args, &mut handler)?;
+                    // As in the contract, attached NEAR is wrapped into wNEAR and the call is then
+                    // made by the `CallWithWrappedNear` callback
+                    if env.attached_deposit == 0 {
+                        let _result = engine.call_with_args(args, &mut handler)?;
+                    } else {
+                        let wnear = engine::get_wnear_address(&io)
+                            .ok_or(engine::CallWithNearError::WnearNotSet)?;
+                        let _promise = engine.call_with_attached_near(
+                            args,
+                            wnear,
+                            env.attached_deposit,
+                            &mut handler,
+                        )?;
+                    }
 
                     near_tx_hash
                 }
@@ -95,7 +121,8 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                 }
 
                 TransactionKind::DeployErc20(args) => {
-                    // No promises can be created by `deploy_erc20_token`
+                    // The result of the `ft_metadata` promise created by `deploy_erc20_token` is
+                    // replayed as a separate `SyncErc20MetadataCallback` transaction
                     let mut handler = crate::promise::Noop;
                     let _result = engine::deploy_erc20_token(args, io, &env, &mut handler)?;
                     near_tx_hash
@@ -123,6 +150,38 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                     near_tx_hash
                 }
 
+                TransactionKind::DeployErc721(args) => {
+                    // No promises can be created by `deploy_erc721_token`
+                    let mut handler = crate::promise::Noop;
+                    let _result = engine::deploy_erc721_token(args, io, &env, &mut handler)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::NftOnTransferCallback { args, token } => {
+                    // Minting the ERC-721 token creates no promises
+                    let mut handler = crate::promise::Noop;
+                    let _result = engine::mint_erc721(
+                        &args.nep171,
+                        token,
+                        Address(args.recipient),
+                        io,
+                        &env,
+                        &mut handler,
+                    )?;
+                    near_tx_hash
+                }
+
+                TransactionKind::RefundNftOnError {
+                    args,
+                    promise_result,
+                } => {
+                    // Minting the ERC-721 token again creates no promises
+                    let mut handler = crate::promise::Noop;
+                    let _result =
+                        engine::refund_nft_on_error(args, promise_result, io, &env, &mut handler)?;
+                    near_tx_hash
+                }
+
                 TransactionKind::Deposit(raw_proof) => {
                     let mut connector_contract = connector::EthConnectorContract::init_instance(io);
                     let promise_args = connector_contract.deposit(
@@ -148,26 +207,253 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                     )?;
 
                     if let Some(promise_args) = maybe_promise_args {
-                        let on_transfer_args =
-                            aurora_engine::json::parse_json(&promise_args.base.args)
-                                .and_then(|json| {
-                                    parameters::NEP141FtOnTransferArgs::try_from(json).ok()
-                                })
-                                .expect("Connector finish_deposit function must return valid args");
-                        let engine = engine::Engine::new(
+                        replay_ft_on_transfer(
+                            &mut connector_contract,
+                            &promise_args,
                             relayer_address,
-                            env.current_account_id(),
                             io,
                             &env,
                         )?;
-                        connector_contract.ft_on_transfer(&engine, &on_transfer_args)?;
-                        // `ft_on_transfer` always returns an unused amount of 0 if it executes
-                        // successfully, meaning that `ft_resolve_transfer` will do nothing,
-                        // so we skip the promise_args callback.
                     }
 
                     near_tx_hash
                 }
+
+                TransactionKind::DepositBatch { args, verified } => {
+                    let mut connector_contract = connector::EthConnectorContract::init_instance(io);
+                    let (_verify_calls, finish_call) = connector_contract.deposit_batch(
+                        args,
+                        env.current_account_id(),
+                        env.predecessor_account_id(),
+                        env.prepaid_gas,
+                    )?;
+
+                    // Unlike a single deposit, proofs of a batch may fail to verify without
+                    // failing the transaction, so their results are given with the message.
+                    let finish_args =
+                        parameters::FinishDepositBatchCallArgs::try_from_slice(&finish_call.args)
+                            .expect("Connector deposit_batch function must return valid args");
+                    let (_statuses, promises) = connector_contract.finish_deposit_batch(
+                        env.predecessor_account_id(),
+                        env.current_account_id(),
+                        finish_args,
+                        verified,
+                    )?;
+
+                    for promise_args in promises.iter() {
+                        replay_ft_on_transfer(
+                            &mut connector_contract,
+                            promise_args,
+                            relayer_address,
+                            io,
+                            &env,
+                        )?;
+                    }
+
+                    near_tx_hash
+                }
+
+                TransactionKind::DepositErc20(raw_proof) => {
+                    let mut connector_contract = connector::EthConnectorContract::init_instance(io);
+                    let promise_args = connector_contract.deposit_erc20(
+                        raw_proof,
+                        env.current_account_id(),
+                        env.predecessor_account_id(),
+                    )?;
+
+                    // As for ETH deposits, the proof must be valid if the transaction succeeded,
+                    // so we move straight to the callback.
+                    let finish_args = parameters::FinishDepositErc20CallArgs::try_from_slice(
+                        &promise_args.callback.args,
+                    )
+                    .expect("Connector deposit_erc20 function must return valid args");
+                    connector_contract.finish_deposit_erc20(&finish_args)?;
+
+                    // Minting the ERC-20 creates no promises
+                    let mut handler = crate::promise::Noop;
+                    let _result = engine::mint_erc20(
+                        Address(finish_args.erc20),
+                        Address(finish_args.recipient),
+                        U256::from_big_endian(&finish_args.amount),
+                        io,
+                        &env,
+                        &mut handler,
+                    )?;
+
+                    near_tx_hash
+                }
+
+                TransactionKind::MigrateUsedProofs(args) => {
+                    let _migrated = connector::EthConnectorContract::init_instance(io)
+                        .migrate_used_proofs(args);
+                    near_tx_hash
+                }
+
+                TransactionKind::SetProofWatermark(args) => {
+                    connector::EthConnectorContract::init_instance(io).set_proof_watermark(args);
+                    near_tx_hash
+                }
+
+                TransactionKind::SetErc20Locker(args) => {
+                    connector::EthConnectorContract::init_instance(io).set_erc20_locker(args);
+                    near_tx_hash
+                }
+
+                TransactionKind::RegisterBridgedErc20(args) => {
+                    connector::EthConnectorContract::init_instance(io)
+                        .register_bridged_erc20(args)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::SetBridgeFees(args) => {
+                    connector::EthConnectorContract::init_instance(io).set_bridge_fees(args)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::GrantRole(args) => {
+                    connector::EthConnectorContract::init_instance(io).grant_role(args);
+                    near_tx_hash
+                }
+
+                TransactionKind::RevokeRole(args) => {
+                    connector::EthConnectorContract::init_instance(io).revoke_role(args);
+                    near_tx_hash
+                }
+
+                TransactionKind::SetPausedFlags(args) => {
+                    connector::EthConnectorContract::init_instance(io).set_paused_flags(args);
+                    near_tx_hash
+                }
+
+                TransactionKind::SyncErc20MetadataCallback { args, metadata } => {
+                    // Setting the metadata of the bridged ERC-20 creates no promises
+                    let mut handler = crate::promise::Noop;
+                    let _result =
+                        engine::set_erc20_metadata(&args.nep141, metadata, io, &env, &mut handler)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::CallWithWrappedNear {
+                    args,
+                    promise_result,
+                } => {
+                    // Only promises possible are the refund of NEAR which could not be wrapped
+                    // and the exit precompiles; we cannot act on those promises
+                    let mut handler = crate::promise::Noop;
+                    let mut engine = engine::Engine::new(
+                        Address(args.origin),
+                        env.current_account_id(),
+                        io,
+                        &env,
+                    )?;
+                    let _result =
+                        engine.call_with_wrapped_near(args, promise_result, &mut handler)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::SetWnearAddress(args) => {
+                    let mut io = io;
+                    engine::set_wnear_address(&mut io, args.address.map(Address));
+                    near_tx_hash
+                }
+
+                TransactionKind::SetEvmLogEvents(args) => {
+                    let mut io = io;
+                    engine::set_evm_log_events(&mut io, args.enabled);
+                    near_tx_hash
+                }
+
+                TransactionKind::StateMigration => {
+                    let mut io = io;
+                    engine::migrate_state(&mut io)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::SetUnprotectedTransactionsPolicy(policy) => {
+                    let mut io = io;
+                    let mut state = engine::get_state(&io)?;
+                    state.unprotected_transactions_policy = policy;
+                    engine::set_state(&mut io, state);
+                    near_tx_hash
+                }
+
+                TransactionKind::SetUnprotectedTransactionAllowed(args) => {
+                    let mut io = io;
+                    engine::set_unprotected_transaction_allowed(
+                        &mut io,
+                        &H256(args.transaction_hash),
+                        args.allowed,
+                    );
+                    near_tx_hash
+                }
+
+                TransactionKind::SetXccPolicy(policy) => {
+                    let mut io = io;
+                    cross_contract::set_policy(&mut io, &policy);
+                    near_tx_hash
+                }
+
+                TransactionKind::SetXccRouterCode(code) => {
+                    let mut io = io;
+                    cross_contract::set_router_code(&mut io, &code);
+                    near_tx_hash
+                }
+
+                TransactionKind::DeployXccRouter(args) => {
+                    // The router is only recorded by `XccRouterDeployed`, the deployment promises
+                    // are not acted on, but the call is still checked as the contract does
+                    let _promises = engine::deploy_xcc_router_promise(
+                        io,
+                        Address(args.address),
+                        env.predecessor_account_id(),
+                        env.attached_deposit(),
+                        &env.current_account_id(),
+                    )?;
+                    near_tx_hash
+                }
+
+                TransactionKind::XccRouterDeployed {
+                    args,
+                    promise_result,
+                } => {
+                    // The refund of a failed deployment is a NEAR transfer we cannot act on
+                    let mut handler = crate::promise::Noop;
+                    let mut io = io;
+                    engine::xcc_router_deployed(args, promise_result, &mut io, &mut handler);
+                    near_tx_hash
+                }
+
+                TransactionKind::SetNearGasRatio(eth_gas_per_tgas) => {
+                    let mut io = io;
+                    near_gas::set_eth_gas_per_tgas(&mut io, eth_gas_per_tgas)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::SetPrecompileStatus(args) => {
+                    let mut io = io;
+                    registry::set_status(&mut io, &args);
+                    near_tx_hash
+                }
+
+                TransactionKind::EvmCallback {
+                    args,
+                    promise_result,
+                } => {
+                    // Only promises possible from `evm_callback` are the ones of the precompiles
+                    // called by the callback and we cannot act on those promises
+                    let mut handler = crate::promise::Noop;
+                    let mut engine = engine::Engine::new(
+                        cross_contract::ADDRESS,
+                        env.current_account_id(),
+                        io,
+                        &env,
+                    )?;
+
+                    let _result =
+                        engine.receive_promise_result(args, promise_result, &mut handler)?;
+
+                    near_tx_hash
+                }
             };
 
             let diff = io.get_transaction_diff();
@@ -182,8 +468,28 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
     }
 }
 
+/// Replays the `ft_on_transfer` call scheduled when a deposit with a message is finished.
+fn replay_ft_on_transfer<I: IO + Copy, E: Env>(
+    connector_contract: &mut connector::EthConnectorContract<I>,
+    promise_args: &PromiseWithCallbackArgs,
+    relayer_address: Address,
+    io: I,
+    env: &E,
+) -> Result<(), error::Error> {
+    let on_transfer_args = aurora_engine::json::parse_json(&promise_args.base.args)
+        .and_then(|json| parameters::NEP141FtOnTransferArgs::try_from(json).ok())
+        .expect("Connector finish_deposit function must return valid args");
+    let engine = engine::Engine::new(relayer_address, env.current_account_id(), io, env)?;
+    connector_contract.ft_on_transfer(&engine, &on_transfer_args)?;
+    // `ft_on_transfer` always returns an unused amount of 0 if it executes
+    // successfully, meaning that `ft_resolve_transfer` will do nothing,
+    // so we skip the promise_args callback.
+    Ok(())
+}
+
 pub mod error {
     use aurora_engine::{connector, engine};
+    use aurora_engine_precompiles::near_gas;
 
     #[derive(Debug)]
     pub enum Error {
@@ -191,9 +497,21 @@ pub mod error {
         EngineState(engine::EngineStateError),
         Engine(engine::EngineError),
         DeployErc20(engine::DeployErc20Error),
+        SetErc20Metadata(engine::SetErc20MetadataError),
+        DeployErc721(engine::DeployErc721Error),
+        MintErc721(engine::MintErc721Error),
+        CallWithNear(engine::CallWithNearError),
         FtOnTransfer(connector::error::FtTransferCallError),
         Deposit(connector::error::DepositError),
         FinishDeposit(connector::error::FinishDepositError),
+        RegisterBridgedErc20(connector::error::RegisterBridgedErc20Error),
+        SetBridgeFees(connector::error::SetBridgeFeesError),
+        ProofUsed(connector::error::ProofUsed),
+        MintErc20(engine::MintErc20Error),
+        NearGasRatio(near_gas::ZeroRatioError),
+        DeployXccRouter(engine::DeployXccRouterError),
+        #[cfg(feature = "meta-call")]
+        MetaCallV2(engine::MetaCallV2Error),
     }
 
     impl From<crate::Error> for Error {
@@ -216,6 +534,26 @@ pub mod error {
             Self::DeployErc20(e)
         }
     }
+    impl From<engine::SetErc20MetadataError> for Error {
+        fn from(e: engine::SetErc20MetadataError) -> Self {
+            Self::SetErc20Metadata(e)
+        }
+    }
+    impl From<engine::DeployErc721Error> for Error {
+        fn from(e: engine::DeployErc721Error) -> Self {
+            Self::DeployErc721(e)
+        }
+    }
+    impl From<engine::MintErc721Error> for Error {
+        fn from(e: engine::MintErc721Error) -> Self {
+            Self::MintErc721(e)
+        }
+    }
+    impl From<engine::CallWithNearError> for Error {
+        fn from(e: engine::CallWithNearError) -> Self {
+            Self::CallWithNear(e)
+        }
+    }
     impl From<connector::error::FtTransferCallError> for Error {
         fn from(e: connector::error::FtTransferCallError) -> Self {
             Self::FtOnTransfer(e)
@@ -226,9 +564,45 @@ pub mod error {
             Self::Deposit(e)
         }
     }
+    impl From<connector::error::RegisterBridgedErc20Error> for Error {
+        fn from(e: connector::error::RegisterBridgedErc20Error) -> Self {
+            Self::RegisterBridgedErc20(e)
+        }
+    }
+    impl From<connector::error::SetBridgeFeesError> for Error {
+        fn from(e: connector::error::SetBridgeFeesError) -> Self {
+            Self::SetBridgeFees(e)
+        }
+    }
+    impl From<connector::error::ProofUsed> for Error {
+        fn from(e: connector::error::ProofUsed) -> Self {
+            Self::ProofUsed(e)
+        }
+    }
+    impl From<engine::MintErc20Error> for Error {
+        fn from(e: engine::MintErc20Error) -> Self {
+            Self::MintErc20(e)
+        }
+    }
     impl From<connector::error::FinishDepositError> for Error {
         fn from(e: connector::error::FinishDepositError) -> Self {
             Self::FinishDeposit(e)
         }
     }
+    impl From<near_gas::ZeroRatioError> for Error {
+        fn from(e: near_gas::ZeroRatioError) -> Self {
+            Self::NearGasRatio(e)
+        }
+    }
+    impl From<engine::DeployXccRouterError> for Error {
+        fn from(e: engine::DeployXccRouterError) -> Self {
+            Self::DeployXccRouter(e)
+        }
+    }
+    #[cfg(feature = "meta-call")]
+    impl From<engine::MetaCallV2Error> for Error {
+        fn from(e: engine::MetaCallV2Error) -> Self {
+            Self::MetaCallV2(e)
+        }
+    }
 }
@@ -1,6 +1,12 @@
+use aurora_engine::engine::UnprotectedTransactionsPolicy;
 use aurora_engine::parameters;
 use aurora_engine::transaction::EthTransactionKind;
 use aurora_engine_types::account_id::AccountId;
+use aurora_engine_types::parameters::{
+    CrossContractCallPolicy, DeployXccRouterArgs, EvmCallbackCallArgs, RefundNftCallArgs,
+    SetPrecompileStatusArgs, XccRouterDeployedArgs,
+};
+use aurora_engine_types::types::PromiseResult;
 use aurora_engine_types::H256;
 
 /// Type describing the format of messages sent to the storage layer for keeping
@@ -45,12 +51,94 @@ pub enum TransactionKind {
     Submit(EthTransactionKind),
     /// Ethereum transaction triggered by a NEAR account
     Call(parameters::CallArgs),
+    /// Input here represents the EIP-712 signed meta-call relayed to the engine
+    #[cfg(feature = "meta-call")]
+    MetaCallV2(Vec<u8>),
     /// Input here represents the EVM code used to create the new contract
     Deploy(Vec<u8>),
     /// New bridged token
     DeployErc20(parameters::DeployErc20TokenArgs),
     /// This type of transaction can impact the aurora state because of the bridge
     FtOnTransfer(parameters::NEP141FtOnTransferArgs),
+    /// New bridged NFT
+    DeployErc721(parameters::DeployErc721TokenArgs),
+    /// Token returned by the `nft_token` promise of `nft_on_transfer`, delivered to
+    /// `nft_on_transfer_callback` which mints it as an ERC-721 token
+    NftOnTransferCallback {
+        args: parameters::NftOnTransferCallbackArgs,
+        token: parameters::NftToken,
+    },
+    /// Result of the `nft_transfer` promise of an NFT exit, delivered to `refund_nft_on_error`
+    RefundNftOnError {
+        args: RefundNftCallArgs,
+        promise_result: PromiseResult,
+    },
     /// Bytes here will be parsed into `aurora_engine::proof::Proof`
     Deposit(Vec<u8>),
+    /// Batch of deposits of ETH from Ethereum, along with the results of the `verify_log_entry`
+    /// calls delivered to `finish_deposit_batch`, in the order of the proofs which were verified
+    DepositBatch {
+        args: parameters::DepositBatchCallArgs,
+        verified: Vec<bool>,
+    },
+    /// Deposit of ERC-20 tokens locked on Ethereum, minted to their bridged Aurora ERC-20
+    DepositErc20(Vec<u8>),
+    /// Owner moving the used proofs recorded under the legacy keys to the compact registry
+    MigrateUsedProofs(parameters::MigrateUsedProofsCallArgs),
+    /// Owner setting the block number below which deposit proofs are rejected
+    SetProofWatermark(parameters::SetProofWatermarkCallArgs),
+    /// Set the address of the ERC-20 locker on Ethereum
+    SetErc20Locker(parameters::SetErc20LockerCallArgs),
+    /// Register the Aurora ERC-20 minted for deposits of an Ethereum token
+    RegisterBridgedErc20(parameters::RegisterBridgedErc20CallArgs),
+    /// Fee manager setting the fees of the eth-connector
+    SetBridgeFees(parameters::SetBridgeFeesCallArgs),
+    /// Owner granting a role of the eth-connector
+    GrantRole(parameters::ConnectorRoleCallArgs),
+    /// Owner revoking a role of the eth-connector
+    RevokeRole(parameters::ConnectorRoleCallArgs),
+    /// Pause or unpause manager changing the paused flows of the eth-connector
+    SetPausedFlags(parameters::PauseEthConnectorCallArgs),
+    /// Metadata of a bridged NEP-141 token delivered to `sync_erc20_metadata_callback`, by the
+    /// `ft_metadata` promise of `DeployErc20` or of the owner's `sync_erc20_metadata`
+    SyncErc20MetadataCallback {
+        args: parameters::SyncErc20MetadataArgs,
+        metadata: parameters::Erc20Metadata,
+    },
+    /// Owner setting the wNEAR ERC-20 which NEAR attached to `Call` is wrapped into
+    SetWnearAddress(parameters::SetWnearAddressArgs),
+    /// Owner enabling or disabling the NEP-297 events of EVM logs
+    SetEvmLogEvents(parameters::SetEvmLogEventsArgs),
+    /// Migration of the engine state to the layout of newly deployed code, by `state_migration`
+    StateMigration,
+    /// Owner setting whether transactions without EIP-155 replay protection are accepted
+    SetUnprotectedTransactionsPolicy(UnprotectedTransactionsPolicy),
+    /// Owner adding or removing an unprotected transaction from the allow-list
+    SetUnprotectedTransactionAllowed(parameters::SetUnprotectedTransactionAllowedArgs),
+    /// Result of the `near_deposit` promise of a call with attached NEAR, delivered to
+    /// `call_with_wrapped_near` which makes the call once the NEAR is wrapped
+    CallWithWrappedNear {
+        args: parameters::WrappedNearCallArgs,
+        promise_result: PromiseResult,
+    },
+    /// Owner setting the limits on the promises of the cross-contract call precompile
+    SetXccPolicy(CrossContractCallPolicy),
+    /// Owner setting the code of the routers deployed for the cross-contract call precompile
+    SetXccRouterCode(Vec<u8>),
+    /// Deployment of the router of an EVM address, paid by the NEAR attached to the call
+    DeployXccRouter(DeployXccRouterArgs),
+    /// Result of the router deployment of `deploy_xcc_router`, delivered to `xcc_router_deployed`
+    XccRouterDeployed {
+        args: XccRouterDeployedArgs,
+        promise_result: PromiseResult,
+    },
+    /// Owner setting the EVM gas charged per Tgas by the precompiles which use NEAR gas
+    SetNearGasRatio(u64),
+    /// Owner enabling or disabling a precompile
+    SetPrecompileStatus(SetPrecompileStatusArgs),
+    /// Result of a cross-contract call promise delivered to the EVM contract which created it
+    EvmCallback {
+        args: EvmCallbackCallArgs,
+        promise_result: PromiseResult,
+    },
 }
@@ -8,19 +8,20 @@ use near_vm_logic::mocks::mock_external::MockedExternal;
 use near_vm_logic::types::ReturnData;
 use near_vm_logic::{VMContext, VMOutcome};
 use near_vm_runner::{MockCompiledContractCache, VMError};
-use rlp::RlpStream;
-use secp256k1::{self, Message, PublicKey, SecretKey};
+use secp256k1::SecretKey;
 
 use crate::prelude::fungible_token::{FungibleToken, FungibleTokenMetadata};
 use crate::prelude::parameters::{
     InitCallArgs, NewCallArgs, SubmitResult, TransactionStatus, ViewCallArgs,
 };
 use crate::prelude::transaction::{
-    eip_1559::{self, SignedTransaction1559, Transaction1559},
-    eip_2930::{self, SignedTransaction2930, Transaction2930},
+    eip_1559::{SignedTransaction1559, Transaction1559},
+    eip_2930::{SignedTransaction2930, Transaction2930},
     legacy::{LegacyEthSignedTransaction, TransactionLegacy},
+    signing::{self, UnsignedTransaction},
+    EthTransactionKind,
 };
-use crate::prelude::{sdk, Address, Wei, H256, U256};
+use crate::prelude::{Address, Wei, H256, U256};
 use crate::test_utils::solidity::{ContractConstructor, DeployedContract};
 
 // TODO(Copied from #84): Make sure that there is only one Signer after both PR are merged.
@@ -32,6 +33,8 @@ pub fn origin() -> String {
 pub(crate) const SUBMIT: &str = "submit";
 pub(crate) const CALL: &str = "call";
 pub(crate) const DEPLOY_ERC20: &str = "deploy_erc20_token";
+pub(crate) const SET_PRECOMPILE_STATUS: &str = "set_precompile_status";
+pub(crate) const META_CALL_V2: &str = "meta_call_v2";
 
 pub(crate) mod erc20;
 pub(crate) mod exit_precompile;
@@ -201,7 +204,11 @@ impl AuroraRunner {
 
         if let Some(standalone_runner) = &mut self.standalone_runner {
             if maybe_error.is_none()
-                && (method_name == SUBMIT || method_name == CALL || method_name == DEPLOY_ERC20)
+                && (method_name == SUBMIT
+                    || method_name == CALL
+                    || method_name == DEPLOY_ERC20
+                    || method_name == SET_PRECOMPILE_STATUS
+                    || method_name == META_CALL_V2)
             {
                 standalone_runner
                     .submit_raw(method_name, &self.context)
@@ -665,23 +672,13 @@ pub(crate) fn sign_transaction(
     chain_id: Option<u64>,
     secret_key: &SecretKey,
 ) -> LegacyEthSignedTransaction {
-    let mut rlp_stream = RlpStream::new();
-    tx.rlp_append_unsigned(&mut rlp_stream, chain_id);
-    let message_hash = sdk::keccak(rlp_stream.as_raw());
-    let message = Message::parse_slice(message_hash.as_bytes()).unwrap();
-
-    let (signature, recovery_id) = secp256k1::sign(&message, secret_key);
-    let v: u64 = match chain_id {
-        Some(chain_id) => (recovery_id.serialize() as u64) + 2 * chain_id + 35,
-        None => (recovery_id.serialize() as u64) + 27,
-    };
-    let r = U256::from_big_endian(&signature.r.b32());
-    let s = U256::from_big_endian(&signature.s.b32());
-    LegacyEthSignedTransaction {
+    let unsigned = UnsignedTransaction::Legacy {
         transaction: tx,
-        v,
-        r,
-        s,
+        chain_id,
+    };
+    match unsigned.sign(secret_key) {
+        EthTransactionKind::Legacy(signed) => signed,
+        _ => unreachable!(),
     }
 }
 
@@ -689,21 +686,9 @@ pub(crate) fn sign_access_list_transaction(
     tx: Transaction2930,
     secret_key: &SecretKey,
 ) -> SignedTransaction2930 {
-    let mut rlp_stream = RlpStream::new();
-    rlp_stream.append(&eip_2930::TYPE_BYTE);
-    tx.rlp_append_unsigned(&mut rlp_stream);
-    let message_hash = sdk::keccak(rlp_stream.as_raw());
-    let message = Message::parse_slice(message_hash.as_bytes()).unwrap();
-
-    let (signature, recovery_id) = secp256k1::sign(&message, secret_key);
-    let r = U256::from_big_endian(&signature.r.b32());
-    let s = U256::from_big_endian(&signature.s.b32());
-
-    SignedTransaction2930 {
-        transaction: tx,
-        parity: recovery_id.serialize(),
-        r,
-        s,
+    match UnsignedTransaction::Eip2930(tx).sign(secret_key) {
+        EthTransactionKind::Eip2930(signed) => signed,
+        _ => unreachable!(),
     }
 }
 
@@ -711,28 +696,14 @@ pub(crate) fn sign_eip_1559_transaction(
     tx: Transaction1559,
     secret_key: &SecretKey,
 ) -> SignedTransaction1559 {
-    let mut rlp_stream = RlpStream::new();
-    rlp_stream.append(&eip_1559::TYPE_BYTE);
-    tx.rlp_append_unsigned(&mut rlp_stream);
-    let message_hash = sdk::keccak(rlp_stream.as_raw());
-    let message = Message::parse_slice(message_hash.as_bytes()).unwrap();
-
-    let (signature, recovery_id) = secp256k1::sign(&message, secret_key);
-    let r = U256::from_big_endian(&signature.r.b32());
-    let s = U256::from_big_endian(&signature.s.b32());
-
-    SignedTransaction1559 {
-        transaction: tx,
-        parity: recovery_id.serialize(),
-        r,
-        s,
+    match UnsignedTransaction::Eip1559(tx).sign(secret_key) {
+        EthTransactionKind::Eip1559(signed) => signed,
+        _ => unreachable!(),
     }
 }
 
 pub(crate) fn address_from_secret_key(sk: &SecretKey) -> Address {
-    let pk = PublicKey::from_secret_key(sk);
-    let hash = sdk::keccak(&pk.serialize()[1..]);
-    Address::from_slice(&hash[12..])
+    signing::address_from_secret_key(sk)
 }
 
 pub(crate) fn parse_eth_gas(output: &VMOutcome) -> u64 {
@@ -12,6 +12,8 @@ pub enum PromiseArgs {
         callback: PromiseCreateArgs,
     },
     Batch(PromiseBatchAction),
+    #[allow(dead_code)]
+    And(Vec<PromiseId>),
 }
 
 /// Doesn't actually schedule any promises, only tracks what promises should be scheduled
@@ -63,6 +65,13 @@ impl PromiseHandler for PromiseTracker {
         PromiseId::new(id)
     }
 
+    fn promise_and(&mut self, promises: &[PromiseId]) -> PromiseId {
+        let id = self.take_id();
+        self.scheduled_promises
+            .insert(id, PromiseArgs::And(promises.to_vec()));
+        PromiseId::new(id)
+    }
+
     fn promise_create_batch(&mut self, args: &PromiseBatchAction) -> PromiseId {
         let id = self.take_id();
         self.scheduled_promises
@@ -2,6 +2,7 @@ use aurora_engine::engine;
 use aurora_engine::parameters::{CallArgs, DeployErc20TokenArgs, SubmitResult, TransactionStatus};
 use aurora_engine::transaction::legacy::{LegacyEthSignedTransaction, TransactionLegacy};
 use aurora_engine_sdk::env::{self, Env};
+use aurora_engine_types::parameters::SetPrecompileStatusArgs;
 use aurora_engine_types::types::NearGas;
 use aurora_engine_types::{types::Wei, Address, H256, U256};
 use borsh::BorshDeserialize;
@@ -151,6 +152,19 @@ impl StandaloneRunner {
         env.signer_account_id = ctx.signer_account_id.as_ref().parse().unwrap();
         env.prepaid_gas = NearGas::new(ctx.prepaid_gas);
 
+        #[cfg(feature = "meta-call")]
+        if method_name == test_utils::META_CALL_V2 {
+            let mut handler = mocks::promise::PromiseTracker::default();
+            let transaction_hash = aurora_engine_sdk::keccak(&ctx.input);
+            let io = Self::get_engine_io(&mut self.storage, &env, 0, transaction_hash);
+            let result = engine::meta_call_v2(io.engine_io, &env, ctx.input.clone(), &mut handler)
+                .map_err(mocks::unsafe_to_string)
+                .unwrap();
+            io.finish()
+                .commit(&mut self.storage, &mut self.cumulative_diff);
+            return Ok(result);
+        }
+
         let storage = &mut self.storage;
         if method_name == test_utils::SUBMIT {
             let transaction_bytes = &ctx.input;
@@ -188,6 +202,17 @@ impl StandaloneRunner {
                 0,
                 Vec::new(),
             ))
+        } else if method_name == test_utils::SET_PRECOMPILE_STATUS {
+            let args = SetPrecompileStatusArgs::try_from_slice(&ctx.input).unwrap();
+            let transaction_hash = aurora_engine_sdk::keccak(&ctx.input);
+            let mut io = Self::get_engine_io(storage, &env, 0, transaction_hash);
+            aurora_engine_precompiles::registry::set_status(&mut io.engine_io, &args);
+            io.finish().commit(storage, &mut self.cumulative_diff);
+            Ok(SubmitResult::new(
+                TransactionStatus::Succeed(Vec::new()),
+                0,
+                Vec::new(),
+            ))
         } else {
             panic!("Unsupported standalone method {}", method_name);
         }
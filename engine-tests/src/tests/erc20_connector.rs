@@ -3,6 +3,7 @@ use crate::test_utils;
 use crate::test_utils::{create_eth_transaction, origin, AuroraRunner};
 use aurora_engine::parameters::{CallArgs, FunctionCallArgsV2, SubmitResult};
 use aurora_engine::transaction::legacy::LegacyEthSignedTransaction;
+use aurora_engine_precompiles::token_map::{Erc20FromNep141, Nep141FromErc20};
 use borsh::{BorshDeserialize, BorshSerialize};
 use ethabi::Token;
 use near_vm_logic::VMOutcome;
@@ -308,13 +309,9 @@ fn test_relayer_charge_fee() {
     let token = runner.deploy_erc20_token(&nep141);
     let recipient = runner.create_account().address;
 
-    let recipient_balance = runner.get_balance(recipient.into());
-    assert_eq!(recipient_balance, INITIAL_BALANCE);
-
     let relayer = create_ethereum_address();
     runner.register_relayer(alice.clone(), relayer);
-    let relayer_balance = runner.get_balance(relayer);
-    assert_eq!(relayer_balance, Wei::zero());
+    assert_eq!(runner.get_balance(relayer), Wei::zero());
 
     let balance = runner.balance_of(token, recipient, origin());
     assert_eq!(balance, U256::from(0));
@@ -322,26 +319,139 @@ fn test_relayer_charge_fee() {
     let fee_encoded = &mut [0; 32];
     U256::from(fee).to_big_endian(fee_encoded);
 
-    runner.ft_on_transfer(
-        nep141,
+    let res = runner.ft_on_transfer(
+        nep141.clone(),
+        alice.clone(),
         alice.clone(),
-        alice,
         amount,
         hex::encode(recipient) + &hex::encode(fee_encoded),
     );
+    assert_eq!(res, "\"0\"");
 
-    let recipient_balance_end = runner.get_balance(recipient.into());
+    // The legacy fee is paid in ETH by the recipient, who gets all the bridged tokens
     assert_eq!(
-        recipient_balance_end,
+        runner.get_balance(recipient.into()),
         Wei::new_u64(INITIAL_BALANCE.raw().as_u64() - fee)
     );
-    let relayer_balance = runner.get_balance(relayer);
-    assert_eq!(relayer_balance, Wei::new_u64(fee));
-
+    assert_eq!(runner.get_balance(relayer), Wei::new_u64(fee));
+    let balance = runner.balance_of(token, recipient, origin());
+    assert_eq!(balance, U256::from(amount));
+    let balance = runner.balance_of(token, relayer.into(), origin());
+    assert_eq!(balance, U256::zero());
+
+    // The fee is not truncated: a fee above the ETH balance of the recipient is not paid
+    // and everything is returned
+    let fee = U256::from(u64::MAX) + 1;
+    fee.to_big_endian(fee_encoded);
+    let res = runner.ft_on_transfer(
+        nep141,
+        alice.clone(),
+        alice,
+        amount,
+        hex::encode(recipient) + &hex::encode(fee_encoded),
+    );
+    assert_eq!(res, format!("\"{}\"", amount));
+    assert_eq!(runner.get_balance(relayer), Wei::new_u64(51));
     let balance = runner.balance_of(token, recipient, origin());
     assert_eq!(balance, U256::from(amount));
 }
 
+#[test]
+fn test_ft_on_transfer_call() {
+    let mut runner = AuroraRunner::new();
+    // Standalone runner presently does not support ft_on_transfer
+    runner.standalone_runner = None;
+    let amount = 10;
+    let fee = 3;
+    let nep141 = "tt.testnet".to_string();
+    let alice = "alice".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    let relayer = create_ethereum_address();
+    runner.register_relayer(alice.clone(), relayer);
+
+    // The recipient makes the call, so it must be the address of the sender
+    let recipient =
+        aurora_engine_sdk::types::near_account_to_evm_address(alice.as_bytes()).to_fixed_bytes();
+    let receiver = runner.create_account().address;
+    let msg = |recipient: RawAddress| {
+        json!({
+            "version": 1,
+            "recipient": format!("0x{}", hex::encode(recipient)),
+            "fee": fee.to_string(),
+            "contract": format!("0x{}", hex::encode(token)),
+            "input": hex::encode(build_input(
+                "transfer(address,uint256)",
+                &[Token::Address(receiver.into()), Token::Uint(U256::from(4))],
+            )),
+        })
+        .to_string()
+    };
+
+    // The call spends the tokens minted to the recipient
+    let res = runner.ft_on_transfer(
+        nep141.clone(),
+        alice.clone(),
+        alice.clone(),
+        amount,
+        msg(recipient),
+    );
+    assert_eq!(res, "\"0\"");
+    assert_eq!(
+        runner.balance_of(token, recipient, origin()),
+        U256::from(amount - fee - 4)
+    );
+    assert_eq!(runner.balance_of(token, receiver, origin()), U256::from(4));
+    assert_eq!(
+        runner.balance_of(token, relayer.into(), origin()),
+        U256::from(fee)
+    );
+
+    // Another recipient cannot be made to call anything
+    let other_recipient = runner.create_account().address;
+    let res = runner.ft_on_transfer(nep141, alice.clone(), alice, amount, msg(other_recipient));
+    assert_eq!(res, format!("\"{}\"", amount));
+    assert_eq!(
+        runner.balance_of(token, other_recipient, origin()),
+        U256::zero()
+    );
+    assert_eq!(runner.balance_of(token, receiver, origin()), U256::from(4));
+}
+
+#[test]
+fn test_ft_on_transfer_call_fail_refund() {
+    let mut runner = AuroraRunner::new();
+    // Standalone runner presently does not support ft_on_transfer
+    runner.standalone_runner = None;
+    let amount = 10;
+    let fee = 3;
+    let nep141 = "tt.testnet".to_string();
+    let alice = "alice".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    let recipient =
+        aurora_engine_sdk::types::near_account_to_evm_address(alice.as_bytes()).to_fixed_bytes();
+    let relayer = create_ethereum_address();
+    runner.register_relayer(alice.clone(), relayer);
+
+    // The ERC-20 has no function with this selector, so the call reverts
+    let msg = json!({
+        "version": 1,
+        "recipient": format!("0x{}", hex::encode(recipient)),
+        "fee": fee.to_string(),
+        "contract": format!("0x{}", hex::encode(token)),
+        "input": "0xdeadbeef",
+    })
+    .to_string();
+    let res = runner.ft_on_transfer(nep141, alice.clone(), alice, amount, msg);
+
+    // The whole amount is returned, and neither the tokens nor the fee are minted
+    assert_eq!(res, format!("\"{}\"", amount));
+    assert_eq!(runner.balance_of(token, recipient, origin()), U256::zero());
+    assert_eq!(
+        runner.balance_of(token, relayer.into(), origin()),
+        U256::zero()
+    );
+}
+
 #[test]
 fn test_transfer_erc20_token() {
     let mut runner = AuroraRunner::new();
@@ -386,6 +496,42 @@ fn test_transfer_erc20_token() {
     );
 }
 
+#[test]
+fn test_token_map_precompiles() {
+    let mut runner = AuroraRunner::new();
+    let nep141 = "tt.testnet".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    // The lookups do not change the state, so the outputs of the standalone
+    // engine are checked separately.
+    let mut standalone_runner = runner.standalone_runner.take().unwrap();
+
+    let mut lookup = |precompile: Address, input: Vec<u8>| {
+        let result = runner.evm_call(precompile.into(), input, origin());
+        result.check_ok();
+        let output = test_utils::unwrap_success(result.submit_result());
+        let standalone_result = standalone_runner
+            .submit_raw(test_utils::CALL, &runner.context)
+            .unwrap();
+        assert_eq!(test_utils::unwrap_success(standalone_result), output);
+        output
+    };
+
+    assert_eq!(
+        lookup(Nep141FromErc20::<()>::ADDRESS, token.to_vec()),
+        nep141.as_bytes()
+    );
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&token);
+    assert_eq!(
+        lookup(Erc20FromNep141::<()>::ADDRESS, nep141.into_bytes()),
+        word
+    );
+
+    // Tokens which are not mapped give an empty output
+    assert!(lookup(Nep141FromErc20::<()>::ADDRESS, vec![1u8; 20]).is_empty());
+    assert!(lookup(Erc20FromNep141::<()>::ADDRESS, b"other.testnet".to_vec()).is_empty());
+}
+
 // Simulation tests for exit to NEAR precompile.
 // Note: `AuroraRunner` is not suitable for these tests because
 // it does not execute promises; but `near-sdk-sim` does.
@@ -396,7 +542,7 @@ mod sim_tests {
     use crate::test_utils::exit_precompile::TesterConstructor;
     use crate::tests::state_migration::{deploy_evm, AuroraAccount};
     use aurora_engine::parameters::{
-        CallArgs, DeployErc20TokenArgs, FunctionCallArgsV2, SubmitResult,
+        CallArgs, DeployErc20TokenArgs, DeployErc721TokenArgs, FunctionCallArgsV2, SubmitResult,
     };
     use borsh::BorshSerialize;
     use near_sdk_sim::UserAccount;
@@ -486,6 +632,60 @@ mod sim_tests {
         );
     }
 
+    #[test]
+    fn test_exit_to_near_refund_to_burner() {
+        // Deploy Aurora; deploy NEP-141; bridge NEP-141 to ERC-20 on Aurora
+        let TestExitToNearContext {
+            ft_owner,
+            ft_owner_address,
+            nep_141,
+            erc20,
+            aurora,
+        } = test_exit_to_near_common();
+
+        // Move tokens to a contract which exits them, so the burner is not the origin
+        let constructor = test_utils::solidity::ContractConstructor::compile_from_source(
+            "src/tests/res",
+            "target/solidity_build",
+            "exit_forwarder.sol",
+            "ExitForwarder",
+        );
+        let deploy_data = constructor.deploy_without_constructor(0.into()).data;
+        let submit_result: SubmitResult = aurora.call("deploy_code", &deploy_data).unwrap_borsh();
+        let forwarder = constructor.deployed_at(Address::from_slice(&test_utils::unwrap_success(
+            submit_result,
+        )));
+        let transfer_tx = erc20.transfer(forwarder.address, FT_EXIT_AMOUNT.into(), 0.into());
+        call_from(&ft_owner, erc20.0.address, transfer_tx.data, &aurora);
+
+        // `ft_transfer_call` to an account without code refunds the whole amount
+        let recipient = format!("{}:hello", ft_owner.account_id.as_str());
+        let exit_tx = forwarder.call_method_with_args(
+            "withdrawToNear",
+            &[
+                ethabi::Token::Address(erc20.0.address),
+                ethabi::Token::Bytes(recipient.into_bytes()),
+                ethabi::Token::Uint(FT_EXIT_AMOUNT.into()),
+            ],
+            0.into(),
+        );
+        call_from(&ft_owner, forwarder.address, exit_tx.data, &aurora);
+
+        assert_eq!(
+            nep_141_balance_of(aurora.contract.account_id.as_str(), &nep_141, &aurora),
+            FT_TRANSFER_AMOUNT
+        );
+        // The refund goes to the contract which burned the tokens, not to the origin
+        assert_eq!(
+            erc20_balance(&erc20, forwarder.address, &aurora),
+            FT_EXIT_AMOUNT.into()
+        );
+        assert_eq!(
+            erc20_balance(&erc20, ft_owner_address, &aurora),
+            (FT_TRANSFER_AMOUNT - FT_EXIT_AMOUNT).into()
+        );
+    }
+
     #[test]
     fn test_exit_to_near_eth() {
         // Same test as above, but exit ETH instead of a bridged NEP-141
@@ -595,6 +795,135 @@ mod sim_tests {
         );
     }
 
+    #[test]
+    fn test_exit_nft_to_near() {
+        let aurora = deploy_evm();
+        let nft_owner = aurora.user.create_user(
+            "nft_owner.root".parse().unwrap(),
+            near_sdk_sim::STORAGE_AMOUNT,
+        );
+        let nft_owner_address =
+            aurora_engine_sdk::types::near_account_to_evm_address(nft_owner.account_id.as_bytes());
+
+        // Bridge a NEP-171 token; the NEP-171 account is not called until the exit
+        let nep_171 = "nft.root";
+        let args = DeployErc721TokenArgs {
+            nep171: nep_171.parse().unwrap(),
+        };
+        let result = aurora.call("deploy_erc721_token", &args.try_to_vec().unwrap());
+        let addr_bytes: Vec<u8> = result.unwrap_borsh();
+        let erc721 = Address::from_slice(&addr_bytes);
+
+        // Mint the ERC-721 token as the engine, its admin
+        let near_token_id = "token:1";
+        let input = super::build_input(
+            "mint(address,string,string)",
+            &[
+                ethabi::Token::Address(nft_owner_address),
+                ethabi::Token::String(near_token_id.to_string()),
+                ethabi::Token::String("https://example.com/1.json".to_string()),
+            ],
+        );
+        let call_args = CallArgs::V2(FunctionCallArgsV2 {
+            contract: erc721.0,
+            value: WeiU256::default(),
+            input,
+        });
+        aurora
+            .contract
+            .call(
+                aurora.contract.account_id(),
+                "call",
+                &call_args.try_to_vec().unwrap(),
+                near_sdk_sim::DEFAULT_GAS,
+                0,
+            )
+            .assert_success();
+
+        // Withdraw the token; the ERC-721 is burned and `nft_transfer` is scheduled
+        let token_id = U256::from_big_endian(&super::keccak256(near_token_id.as_bytes()));
+        let input = super::build_input(
+            "withdrawToNear(bytes,uint256)",
+            &[
+                ethabi::Token::Bytes(nft_owner.account_id.as_bytes().to_vec()),
+                ethabi::Token::Uint(token_id),
+            ],
+        );
+        let call_args = CallArgs::V2(FunctionCallArgsV2 {
+            contract: erc721.0,
+            value: WeiU256::default(),
+            input,
+        });
+        let result = nft_owner.call(
+            aurora.contract.account_id(),
+            "call",
+            &call_args.try_to_vec().unwrap(),
+            near_sdk_sim::DEFAULT_GAS,
+            0,
+        );
+        result.assert_success();
+        let submit_result: SubmitResult = result.unwrap_borsh();
+        test_utils::unwrap_success(submit_result);
+        assert!(result
+            .promise_results()
+            .iter()
+            .flatten()
+            .any(|outcome| outcome.outcome().executor_id.as_str() == nep_171));
+
+        // `nft.root` does not exist, so the transfer fails and the token is minted again
+        // to its owner with the same URI
+        let view = |signature: &str| {
+            let input = super::build_input(signature, &[ethabi::Token::Uint(token_id)]);
+            let call_args = CallArgs::V2(FunctionCallArgsV2 {
+                contract: erc721.0,
+                value: WeiU256::default(),
+                input,
+            });
+            let result = aurora.call("call", &call_args.try_to_vec().unwrap());
+            let submit_result: SubmitResult = result.unwrap_borsh();
+            test_utils::unwrap_success(submit_result)
+        };
+        assert_eq!(
+            view("ownerOf(uint256)"),
+            ethabi::encode(&[ethabi::Token::Address(nft_owner_address)])
+        );
+        assert_eq!(
+            view("tokenURI(uint256)"),
+            ethabi::encode(&[ethabi::Token::String(
+                "https://example.com/1.json".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_deploy_erc20_metadata() {
+        let aurora = deploy_evm();
+        let nep_141 = deploy_nep_141(
+            FT_ACCOUNT,
+            aurora.user.account_id.as_str(),
+            FT_TOTAL_SUPPLY,
+            &aurora,
+        );
+
+        // Deploying the ERC-20 fetches `ft_metadata` and applies it in the callback
+        let erc20 = deploy_erc20_from_nep_141(&nep_141, &aurora);
+        let expected = vec![
+            ethabi::Token::String("Example NEAR fungible token".to_string()),
+            ethabi::Token::String("EXAMPLE".to_string()),
+            ethabi::Token::Uint(24.into()),
+        ];
+        assert_eq!(erc20_metadata(&erc20, &aurora), expected);
+
+        // The owner can fetch it again
+        let args = DeployErc20TokenArgs {
+            nep141: nep_141.account_id().as_str().parse().unwrap(),
+        };
+        aurora
+            .call("sync_erc20_metadata", &args.try_to_vec().unwrap())
+            .assert_success();
+        assert_eq!(erc20_metadata(&erc20, &aurora), expected);
+    }
+
     fn test_exit_to_near_eth_common() -> TestExitToNearEthContext {
         let aurora = deploy_evm();
         let chain_id = test_utils::AuroraRunner::default().chain_id;
@@ -733,6 +1062,29 @@ mod sim_tests {
             .assert_success();
     }
 
+    fn call_from(
+        source: &near_sdk_sim::UserAccount,
+        contract: Address,
+        input: Vec<u8>,
+        aurora: &AuroraAccount,
+    ) {
+        let call_args = CallArgs::V2(FunctionCallArgsV2 {
+            contract: contract.0,
+            value: WeiU256::default(),
+            input,
+        });
+        let result = source.call(
+            aurora.contract.account_id(),
+            "call",
+            &call_args.try_to_vec().unwrap(),
+            near_sdk_sim::DEFAULT_GAS,
+            0,
+        );
+        result.assert_success();
+        let submit_result: SubmitResult = result.unwrap_borsh();
+        test_utils::unwrap_success(submit_result);
+    }
+
     fn transfer_nep_141_to_erc_20(
         nep_141: &near_sdk_sim::UserAccount,
         erc20: &ERC20,
@@ -798,6 +1150,29 @@ mod sim_tests {
         U256::from_big_endian(&test_utils::unwrap_success(submit_result))
     }
 
+    fn erc20_metadata(erc20: &ERC20, aurora: &AuroraAccount) -> Vec<ethabi::Token> {
+        ["name", "symbol", "decimals"]
+            .iter()
+            .flat_map(|method| {
+                let tx = erc20.0.call_method_without_args(method, 0.into());
+                let call_args = CallArgs::V2(FunctionCallArgsV2 {
+                    contract: erc20.0.address.0,
+                    value: WeiU256::default(),
+                    input: tx.data,
+                });
+                let result = aurora.call("call", &call_args.try_to_vec().unwrap());
+                let submit_result: SubmitResult = result.unwrap_borsh();
+                erc20
+                    .0
+                    .abi
+                    .function(method)
+                    .unwrap()
+                    .decode_output(&test_utils::unwrap_success(submit_result))
+                    .unwrap()
+            })
+            .collect()
+    }
+
     fn deploy_erc20_from_nep_141(
         nep_141: &near_sdk_sim::UserAccount,
         aurora: &AuroraAccount,
@@ -7,6 +7,7 @@ use aurora_engine::connector::{
 };
 use aurora_engine::fungible_token::FungibleTokenMetadata;
 use aurora_engine::parameters::{
+    BridgeFees, ConnectorRole, ConnectorRoleCallArgs, DepositBatchStatus, FeeRate, FeeSchedule,
     InitCallArgs, NewCallArgs, RegisterRelayerCallArgs, WithdrawResult,
 };
 use aurora_engine_types::types::Fee;
@@ -26,6 +27,7 @@ const PROVER_ACCOUNT: &'static str = "eth_connector.root";
 const CUSTODIAN_ADDRESS: &'static str = "096DE9C2B8A5B8c22cEe3289B101f6960d68E51E";
 const DEPOSITED_AMOUNT: u128 = 800400;
 const DEPOSITED_FEE: u128 = 400;
+const FEE_COLLECTOR: &'static str = "fee_collector.root";
 const RECIPIENT_ETH_ADDRESS: &'static str = "891b2749238b27ff58e951088e55b04de71dc374";
 const EVM_CUSTODIAN_ADDRESS: &'static str = "096DE9C2B8A5B8c22cEe3289B101f6960d68E51E";
 const DEPOSITED_EVM_AMOUNT: u128 = 10200;
@@ -883,6 +885,170 @@ fn test_admin_controlled_only_admin_can_pause() {
     res.assert_success();
 }
 
+#[test]
+fn test_pause_manager_can_pause_but_not_unpause() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+
+    // Only the owner can grant roles
+    let args = ConnectorRoleCallArgs {
+        role: ConnectorRole::PauseManager,
+        account_id: str_to_account_id(user_account.account_id().as_str()),
+    };
+    let res = user_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "grant_role",
+        &args.try_to_vec().unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    assert!(matches!(
+        res.promise_results()[1].clone().unwrap().outcome().status,
+        ExecutionStatus::Failure(_)
+    ));
+    contract
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "grant_role",
+            &args.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+
+    let res = call_set_paused_flags(&user_account, CONTRACT_ACC, PAUSE_DEPOSIT);
+    res.assert_success();
+
+    // Unpausing requires the unpause manager role
+    let res = call_set_paused_flags(&user_account, CONTRACT_ACC, UNPAUSE_ALL);
+    assert!(matches!(
+        res.promise_results()[1].clone().unwrap().outcome().status,
+        ExecutionStatus::Failure(_)
+    ));
+    let res = call_set_paused_flags(&contract, CONTRACT_ACC, UNPAUSE_ALL);
+    res.assert_success();
+}
+
+#[test]
+fn test_metadata_manager_can_only_set_metadata() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+    contract
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "grant_role",
+            &ConnectorRoleCallArgs {
+                role: ConnectorRole::MetadataManager,
+                account_id: str_to_account_id(user_account.account_id().as_str()),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+
+    let metadata = FungibleTokenMetadata {
+        name: "Wrapped Ether".to_string(),
+        ..Default::default()
+    };
+
+    // The prover account and the custodian address can only be changed by the owner
+    let res = user_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "set_eth_connector_contract_data",
+        &InitCallArgs {
+            prover_account: str_to_account_id(user_account.account_id().as_str()),
+            eth_custodian_address: "0000000000000000000000000000000000000001".into(),
+            metadata: metadata.clone(),
+        }
+        .try_to_vec()
+        .unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    assert!(matches!(
+        res.promise_results()[1].clone().unwrap().outcome().status,
+        ExecutionStatus::Failure(_)
+    ));
+
+    user_account
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_eth_connector_metadata",
+            &metadata.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+    let res = master_account.view(CONTRACT_ACC.parse().unwrap(), "ft_metadata", &[]);
+    let metadata: serde_json::Value = serde_json::from_slice(&res.unwrap()).unwrap();
+    assert_eq!(metadata["name"], "Wrapped Ether");
+
+    // The deposits still go to the same custodian
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+}
+
+#[test]
+fn test_contract_data_manager_can_set_contract_data() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+    let set_contract_data = |account: &UserAccount, name: &str| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_eth_connector_contract_data",
+            &InitCallArgs {
+                prover_account: str_to_account_id(PROVER_ACCOUNT),
+                eth_custodian_address: CUSTODIAN_ADDRESS.into(),
+                metadata: FungibleTokenMetadata {
+                    name: name.to_string(),
+                    ..Default::default()
+                },
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let metadata_name = || {
+        let res = master_account.view(CONTRACT_ACC.parse().unwrap(), "ft_metadata", &[]);
+        let metadata: serde_json::Value = serde_json::from_slice(&res.unwrap()).unwrap();
+        metadata["name"].clone()
+    };
+
+    // The contract data can only be set by the owner or a contract data manager
+    let res = set_contract_data(&user_account, "Wrapped Ether");
+    assert!(matches!(
+        res.promise_results()[1].clone().unwrap().outcome().status,
+        ExecutionStatus::Failure(_)
+    ));
+    set_contract_data(&contract, "Owner Ether").assert_success();
+    assert_eq!(metadata_name(), "Owner Ether");
+
+    contract
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "grant_role",
+            &ConnectorRoleCallArgs {
+                role: ConnectorRole::ContractDataManager,
+                account_id: str_to_account_id(user_account.account_id().as_str()),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+    set_contract_data(&user_account, "Wrapped Ether").assert_success();
+    assert_eq!(metadata_name(), "Wrapped Ether");
+
+    // The deposits still go to the same custodian
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+}
+
 #[test]
 fn test_admin_controlled_admin_can_peform_actions_when_paused() {
     let (_master_account, contract) = init(CUSTODIAN_ADDRESS);
@@ -1478,8 +1644,225 @@ fn test_ft_transfer_wrong_u128_json_type() {
     );
 }
 
+fn call_set_bridge_fees(account: &UserAccount, contract: &str, fees: BridgeFees) {
+    account
+        .call(
+            contract.parse().unwrap(),
+            "set_bridge_fees",
+            &fees.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+}
+
+fn flat_fee(fee: u128) -> Option<FeeSchedule> {
+    Some(FeeSchedule {
+        rate: FeeRate::Flat(Fee::new(fee)),
+        min: Fee::new(0),
+        max: Fee::new(fee),
+    })
+}
+
+#[test]
+fn test_deposit_with_bridge_fee() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let bridge_fee = 100;
+    call_set_bridge_fees(
+        &contract,
+        CONTRACT_ACC,
+        BridgeFees {
+            deposit: flat_fee(bridge_fee),
+            fee_collector: Some(str_to_account_id(FEE_COLLECTOR)),
+            ..Default::default()
+        },
+    );
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE - bridge_fee);
+
+    // The relayer still gets its whole fee
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE);
+
+    let balance = get_eth_on_near_balance(&master_account, FEE_COLLECTOR, CONTRACT_ACC);
+    assert_eq!(balance, bridge_fee);
+
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT);
+}
+
+#[test]
+fn test_withdraw_eth_from_near_with_bridge_fee() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let bridge_fee = 10;
+    call_set_bridge_fees(
+        &contract,
+        CONTRACT_ACC,
+        BridgeFees {
+            withdraw: flat_fee(bridge_fee),
+            fee_collector: Some(str_to_account_id(FEE_COLLECTOR)),
+            ..Default::default()
+        },
+    );
+
+    let withdraw_amount = 100;
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "withdraw",
+        &WithdrawCallArgs {
+            recipient_address: validate_eth_address(RECIPIENT_ETH_ADDRESS),
+            amount: withdraw_amount,
+        }
+        .try_to_vec()
+        .unwrap(),
+        DEFAULT_GAS,
+        1,
+    );
+    res.assert_success();
+    let result: WithdrawResult = res.unwrap_borsh();
+    assert_eq!(result.amount, withdraw_amount - bridge_fee);
+
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE - withdraw_amount);
+
+    let balance = get_eth_on_near_balance(&master_account, FEE_COLLECTOR, CONTRACT_ACC);
+    assert_eq!(balance, bridge_fee);
+
+    // Only the withdrawn amount leaves NEAR
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - withdraw_amount + bridge_fee);
+}
+
+#[test]
+fn test_ft_transfer_with_bridge_fee() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    call_set_bridge_fees(
+        &contract,
+        CONTRACT_ACC,
+        BridgeFees {
+            ft_transfer: Some(FeeSchedule {
+                rate: FeeRate::BasisPoints(1_000),
+                min: Fee::new(1),
+                max: Fee::new(1_000),
+            }),
+            fee_collector: Some(str_to_account_id(FEE_COLLECTOR)),
+            ..Default::default()
+        },
+    );
+
+    let transfer = |sender: &UserAccount, receiver_id: &str, amount: u128| {
+        sender
+            .call(
+                CONTRACT_ACC.parse().unwrap(),
+                "ft_transfer",
+                json!({
+                    "receiver_id": receiver_id,
+                    "amount": amount.to_string(),
+                })
+                .to_string()
+                .as_bytes(),
+                DEFAULT_GAS,
+                1,
+            )
+            .assert_success();
+    };
+
+    // Users pay 10% of the amount to the fee collector
+    let transfer_amount = 1_000;
+    transfer(&user_account, CONTRACT_ACC, transfer_amount);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE - transfer_amount);
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE + 900);
+    let balance = get_eth_on_near_balance(&master_account, FEE_COLLECTOR, CONTRACT_ACC);
+    assert_eq!(balance, 100);
+
+    // The connector itself is not charged
+    transfer(&contract, DEPOSITED_RECIPIENT, transfer_amount);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE - 100);
+    let balance = get_eth_on_near_balance(&master_account, FEE_COLLECTOR, CONTRACT_ACC);
+    assert_eq!(balance, 100);
+
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT);
+}
+
+fn call_deposit_batch(account: &UserAccount, contract: &str, proofs: &[Proof]) -> ExecutionResult {
+    // Borsh-encoded the same as `DepositBatchCallArgs`
+    account.call(
+        contract.parse().unwrap(),
+        "deposit_batch",
+        &proofs.to_vec().try_to_vec().unwrap(),
+        DEFAULT_GAS,
+        0,
+    )
+}
+
+#[test]
+fn test_deposit_batch_with_invalid_proofs() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+
+    let valid_proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let invalid_proof = Proof {
+        log_entry_data: vec![0xde, 0xad, 0xbe, 0xef],
+        ..valid_proof.clone()
+    };
+    let res = call_deposit_batch(
+        &contract,
+        CONTRACT_ACC,
+        &[valid_proof.clone(), invalid_proof, valid_proof],
+    );
+    res.assert_success();
+
+    let statuses: Vec<DepositBatchStatus> = res.unwrap_borsh();
+    assert_eq!(statuses.len(), 3);
+    assert_eq!(statuses[0], DepositBatchStatus::Finished);
+    assert!(matches!(statuses[1], DepositBatchStatus::Failed(_)));
+    assert_eq!(
+        statuses[2],
+        DepositBatchStatus::Failed("ERR_PROOF_EXIST".to_string())
+    );
+
+    // Only the valid proof was deposited, once
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT);
+}
+
+#[test]
+fn test_deposit_batch_not_enough_gas() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+
+    // Each verified proof needs gas for its verification and for finishing its deposit
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let res = call_deposit_batch(
+        &contract,
+        CONTRACT_ACC,
+        &[proof.clone(), proof.clone(), proof.clone(), proof],
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_NOT_ENOUGH_GAS_FOR_DEPOSIT_BATCH",
+        "Expected failure as the batch needs more gas than attached, but deposit succeeded",
+    );
+
+    assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, 0);
+}
+
 /// Bytes for a NEAR smart contract implementing `ft_on_transfer`
-fn dummy_ft_receiver_bytes() -> Vec<u8> {
+pub(crate) fn dummy_ft_receiver_bytes() -> Vec<u8> {
     let base_path = std::path::Path::new("../etc").join("ft-receiver");
     let output_path = base_path.join("target/wasm32-unknown-unknown/release/ft_receiver.wasm");
     crate::test_utils::rust::compile(base_path);
@@ -1,8 +1,16 @@
 use {
-    crate::prelude::meta_parsing::{near_erc712_domain, parse_meta_call, prepare_meta_call_args},
-    crate::prelude::parameters::MetaCallArgs,
+    crate::prelude::meta_parsing::{
+        aurora_eip712_domain, near_erc712_domain, parse_meta_call, parse_meta_call_v2,
+        prepare_meta_call_args, prepare_meta_call_v2_args, recover_signer,
+    },
+    crate::prelude::parameters::{MetaCallArgs, MetaCallArgsV2, SubmitResult, TransactionStatus},
     crate::prelude::sdk::keccak,
-    crate::prelude::{u256_to_arr, Address, BorshSerialize, InternalMetaCallArgs, Wei, U256},
+    crate::prelude::{
+        u256_to_arr, Address, BorshDeserialize, BorshSerialize, InternalMetaCallArgs,
+        InternalMetaCallArgsV2, Wei, U256,
+    },
+    crate::test_utils,
+    aurora_engine::engine::current_address,
     near_crypto::{InMemorySigner, KeyType, PublicKey, Signature, Signer},
 };
 
@@ -117,3 +125,282 @@ fn test_meta_parsing() {
 
     assert_eq!(result.sender, signer_addr);
 }
+
+fn encode_meta_call_v2_function_args(
+    signer: &dyn Signer,
+    domain_separator: &[u8; 32],
+    sender: Option<Address>,
+    args: InternalMetaCallArgsV2,
+    method_def: &str,
+) -> Vec<u8> {
+    // The zero address is signed for a meta-call made from the ECDSA signer
+    let args = InternalMetaCallArgsV2 {
+        sender: sender.unwrap_or_else(Address::zero),
+        ..args
+    };
+    let (msg, _) = match prepare_meta_call_v2_args(domain_separator, method_def, &args) {
+        Ok(x) => x,
+        Err(_) => panic!("Failed to prepare"),
+    };
+    match signer.sign(&msg) {
+        Signature::ED25519(_) => panic!("Wrong Signer"),
+        Signature::SECP256K1(sig) => {
+            let mut signature = Into::<[u8; 65]>::into(sig.clone()).to_vec();
+            signature[64] += 27;
+            MetaCallArgsV2 {
+                sender: sender.map(|address| address.0),
+                signature,
+                nonce: u256_to_arr(&args.nonce),
+                deadline: args.deadline,
+                fee_amount: args.fee_amount.to_bytes(),
+                fee_address: args.fee_address.0,
+                contract_address: args.contract_address.0,
+                value: args.value.to_bytes(),
+                gas_limit: args.gas_limit,
+                method_def: method_def.to_string(),
+                args: args.input,
+            }
+            .try_to_vec()
+            .expect("Failed to serialize")
+        }
+    }
+}
+
+#[test]
+fn test_meta_parsing_v2() {
+    let chain_id = 1313161555;
+    let signer = InMemorySigner::from_seed("doesnt".parse().unwrap(), KeyType::SECP256K1, "a");
+    let signer_addr = public_key_to_address(signer.public_key.clone());
+    let engine_address = Address::from_slice(&[0xaa; 20]);
+    let domain_separator = aurora_eip712_domain(U256::from(chain_id), engine_address);
+    let fee_address = Address::from_slice(&[0xbb; 20]);
+
+    let args = || InternalMetaCallArgsV2 {
+        sender: Address::zero(),
+        nonce: U256::from(14),
+        deadline: 1_700_000_000,
+        fee_amount: Wei::new_u64(6),
+        fee_address,
+        contract_address: signer_addr,
+        value: Wei::zero(),
+        gas_limit: 100_000,
+        // RLP encode of ["0x09"]
+        input: hex::decode("c109").unwrap(),
+    };
+    let meta_tx = encode_meta_call_v2_function_args(
+        &signer,
+        &domain_separator,
+        None,
+        args(),
+        "adopt(uint256 petId)",
+    );
+
+    let (result, sender_signature) = parse_meta_call_v2(&domain_separator, meta_tx.clone())
+        .unwrap_or_else(|_| panic!("Fail meta_tx"));
+    assert!(sender_signature.is_none());
+    assert_eq!(result.sender, signer_addr);
+    assert_eq!(result.nonce, U256::from(14));
+    assert_eq!(result.deadline, 1_700_000_000);
+    assert_eq!(result.fee_amount, Wei::new_u64(6));
+    assert_eq!(result.fee_address, fee_address);
+    assert_eq!(result.gas_limit, 100_000);
+    let mut expected_input = keccak(b"adopt(uint256)")[..4].to_vec();
+    expected_input.extend_from_slice(&u256_to_arr(&U256::from(9)));
+    assert_eq!(result.input, expected_input);
+
+    // The signature is bound to the engine it was made for
+    let other_domain_separator =
+        aurora_eip712_domain(U256::from(chain_id), Address::from_slice(&[0xcc; 20]));
+    if let Ok((result, _)) = parse_meta_call_v2(&other_domain_separator, meta_tx) {
+        assert_ne!(result.sender, signer_addr);
+    }
+
+    // An explicit sender is returned with its signature, to be verified by the engine
+    let wallet = Address::from_slice(&[0xdd; 20]);
+    let meta_tx = encode_meta_call_v2_function_args(
+        &signer,
+        &domain_separator,
+        Some(wallet),
+        args(),
+        "adopt(uint256 petId)",
+    );
+    let (result, sender_signature) = parse_meta_call_v2(&domain_separator, meta_tx.clone())
+        .unwrap_or_else(|_| panic!("Fail meta_tx"));
+    assert_eq!(result.sender, wallet);
+    let sender_signature = sender_signature.unwrap();
+    assert_eq!(
+        recover_signer(&sender_signature.message, &sender_signature.signature).ok(),
+        Some(signer_addr)
+    );
+
+    // The sender is signed, so the signature can't be replayed from another wallet
+    let mut replayed_meta_tx = MetaCallArgsV2::try_from_slice(&meta_tx).unwrap();
+    replayed_meta_tx.sender = Some([0xee; 20]);
+    let (result, sender_signature) =
+        parse_meta_call_v2(&domain_separator, replayed_meta_tx.try_to_vec().unwrap())
+            .unwrap_or_else(|_| panic!("Fail meta_tx"));
+    assert_eq!(result.sender, Address::from_slice(&[0xee; 20]));
+    let sender_signature = sender_signature.unwrap();
+    assert_ne!(
+        recover_signer(&sender_signature.message, &sender_signature.signature).ok(),
+        Some(signer_addr)
+    );
+}
+
+#[test]
+fn test_meta_call_v2_fee() {
+    let mut runner = test_utils::deploy_evm();
+    let signer = InMemorySigner::from_seed("doesnt".parse().unwrap(), KeyType::SECP256K1, "a");
+    let signer_addr = public_key_to_address(signer.public_key.clone());
+    let domain_separator = aurora_eip712_domain(
+        U256::from(runner.chain_id),
+        current_address(&test_utils::str_to_account_id(&runner.aurora_account_id)),
+    );
+    let fee_address = Address::from_slice(&[0xbb; 20]);
+    let empty_address = Address::from_slice(&[0xcc; 20]);
+    let revert_address = Address::from_slice(&[0xdd; 20]);
+    runner.create_address(signer_addr, Wei::new_u64(1_000), U256::zero());
+    // PUSH1 0 PUSH1 0 REVERT
+    runner.create_address_with_code(
+        revert_address,
+        Wei::zero(),
+        U256::zero(),
+        hex::decode("60006000fd").unwrap(),
+    );
+
+    let args = |nonce: u64, deadline: u64, contract_address: Address| InternalMetaCallArgsV2 {
+        sender: Address::zero(),
+        nonce: U256::from(nonce),
+        deadline,
+        fee_amount: Wei::new_u64(6),
+        fee_address,
+        contract_address,
+        value: Wei::zero(),
+        gas_limit: 100_000,
+        // RLP encode of ["0x09"]
+        input: hex::decode("c109").unwrap(),
+    };
+    let meta_call = |runner: &mut test_utils::AuroraRunner, args: InternalMetaCallArgsV2| {
+        let meta_tx = encode_meta_call_v2_function_args(
+            &signer,
+            &domain_separator,
+            None,
+            args,
+            "adopt(uint256 petId)",
+        );
+        runner.call("meta_call_v2", "relayer.near", meta_tx)
+    };
+
+    // The fee is moved from the sender to the fee address
+    let (outcome, maybe_error) = meta_call(&mut runner, args(0, 1_000_000, empty_address));
+    assert!(maybe_error.is_none());
+    let result =
+        SubmitResult::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    assert!(result.status.is_ok());
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        signer_addr,
+        Wei::new_u64(994),
+        U256::from(1),
+    );
+    assert_eq!(runner.get_balance(fee_address), Wei::new_u64(6));
+
+    // The fee stays paid if the call reverts
+    let (outcome, maybe_error) = meta_call(&mut runner, args(1, 1_000_000, revert_address));
+    assert!(maybe_error.is_none());
+    let result =
+        SubmitResult::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    assert!(matches!(result.status, TransactionStatus::Revert(_)));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        signer_addr,
+        Wei::new_u64(988),
+        U256::from(2),
+    );
+    assert_eq!(runner.get_balance(fee_address), Wei::new_u64(12));
+
+    // Meta-calls past their deadline are rejected without paying the fee
+    let (_, maybe_error) = meta_call(&mut runner, args(2, 0, empty_address));
+    let error_message = format!("{:?}", maybe_error.unwrap());
+    assert!(error_message.contains("ERR_META_TX_EXPIRED"));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        signer_addr,
+        Wei::new_u64(988),
+        U256::from(2),
+    );
+    assert_eq!(runner.get_balance(fee_address), Wei::new_u64(12));
+}
+
+#[test]
+fn test_meta_call_v2_wallet_replay() {
+    let mut runner = test_utils::deploy_evm();
+    let signer = InMemorySigner::from_seed("doesnt".parse().unwrap(), KeyType::SECP256K1, "a");
+    let signer_addr = public_key_to_address(signer.public_key.clone());
+    let domain_separator = aurora_eip712_domain(
+        U256::from(runner.chain_id),
+        current_address(&test_utils::str_to_account_id(&runner.aurora_account_id)),
+    );
+
+    // Two EIP-1271 wallets accepting the signatures of the same owner
+    let mut deployer = test_utils::Signer::random();
+    let deployer_address = test_utils::address_from_secret_key(&deployer.secret_key);
+    runner.create_address(deployer_address, Wei::zero(), U256::zero());
+    let mut deploy_wallet = || {
+        let constructor = test_utils::solidity::ContractConstructor::compile_from_source(
+            "src/tests/res",
+            "target/solidity_build",
+            "wallet.sol",
+            "Wallet",
+        );
+        let nonce = deployer.use_nonce();
+        let wallet = runner.deploy_contract(
+            &deployer.secret_key,
+            |c| c.deploy_with_args(nonce.into(), &[ethabi::Token::Address(signer_addr)]),
+            constructor,
+        );
+        wallet.address
+    };
+    let wallet = deploy_wallet();
+    let other_wallet = deploy_wallet();
+
+    let args = InternalMetaCallArgsV2 {
+        sender: wallet,
+        nonce: runner.get_nonce(wallet),
+        deadline: 1_000_000,
+        fee_amount: Wei::zero(),
+        fee_address: Address::from_slice(&[0xbb; 20]),
+        contract_address: Address::from_slice(&[0xcc; 20]),
+        value: Wei::zero(),
+        gas_limit: 100_000,
+        // RLP encode of ["0x09"]
+        input: hex::decode("c109").unwrap(),
+    };
+    let meta_tx = encode_meta_call_v2_function_args(
+        &signer,
+        &domain_separator,
+        Some(wallet),
+        args,
+        "adopt(uint256 petId)",
+    );
+
+    // The signature made for one wallet is rejected from the other one
+    let mut replayed_meta_tx = MetaCallArgsV2::try_from_slice(&meta_tx).unwrap();
+    replayed_meta_tx.sender = Some(other_wallet.0);
+    replayed_meta_tx.nonce = u256_to_arr(&runner.get_nonce(other_wallet));
+    let (_, maybe_error) = runner.call(
+        "meta_call_v2",
+        "relayer.near",
+        replayed_meta_tx.try_to_vec().unwrap(),
+    );
+    let error_message = format!("{:?}", maybe_error.unwrap());
+    assert!(error_message.contains("ERR_META_TX_INVALID_SIGNATURE"));
+
+    let nonce = runner.get_nonce(wallet);
+    let (outcome, maybe_error) = runner.call("meta_call_v2", "relayer.near", meta_tx);
+    assert!(maybe_error.is_none());
+    let result =
+        SubmitResult::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    assert!(result.status.is_ok());
+    assert_eq!(runner.get_nonce(wallet), nonce + 1);
+}
@@ -14,3 +14,4 @@ mod standalone;
 mod standard_precompiles;
 mod state_migration;
 pub(crate) mod uniswap;
+mod xcc;
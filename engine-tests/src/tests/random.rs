@@ -1,6 +1,11 @@
+use crate::prelude::Wei;
 use crate::test_utils;
 use crate::test_utils::random::{Random, RandomConstructor};
+use aurora_engine::parameters::TransactionStatus;
+use aurora_engine::transaction::legacy::TransactionLegacy;
+use aurora_engine_precompiles::random::RandomSeed;
 use aurora_engine_types::H256;
+use borsh::BorshSerialize;
 
 #[test]
 fn test_random_number_precompile() {
@@ -17,3 +22,42 @@ fn test_random_number_precompile() {
     let counter_value = random.random_seed(&mut runner, &mut signer);
     assert_eq!(counter_value, random_seed);
 }
+
+#[test]
+fn test_random_seed_near_gas_ratio() {
+    // The precompiles which use NEAR gas charge at the ratio set by the owner
+    let mut runner = test_utils::deploy_evm();
+    // The ratio is not set in the standalone engine
+    runner.standalone_runner = None;
+    let mut signer = test_utils::Signer::random();
+    runner.create_address(
+        test_utils::address_from_secret_key(&signer.secret_key),
+        Wei::zero(),
+        signer.nonce.into(),
+    );
+    let owner = runner.aurora_account_id.clone();
+
+    let (_, error) = runner.call("set_near_gas_ratio", &owner, 0u64.try_to_vec().unwrap());
+    assert!(error
+        .unwrap()
+        .to_string()
+        .contains("ERR_ZERO_NEAR_GAS_RATIO"));
+
+    // The seed costs 3 Ggas, which is now 3 million EVM gas
+    let (_, error) = runner.call(
+        "set_near_gas_ratio",
+        &owner,
+        1_000_000_000u64.try_to_vec().unwrap(),
+    );
+    assert!(error.is_none());
+
+    let mut call_seed = |gas_limit: u64| {
+        let tx = TransactionLegacy {
+            gas_limit: gas_limit.into(),
+            ..test_utils::transfer(RandomSeed::ADDRESS, Wei::zero(), signer.use_nonce().into())
+        };
+        runner.submit_transaction(&signer.secret_key, tx).unwrap()
+    };
+    assert_eq!(call_seed(2_000_000).status, TransactionStatus::OutOfGas);
+    assert!(call_seed(4_000_000).status.is_ok());
+}
@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IExit {
+    function withdrawToNear(bytes memory recipient, uint256 amount) external;
+}
+
+// Exits the bridged tokens it holds, so that the burner is not the transaction origin.
+contract ExitForwarder {
+    function withdrawToNear(IExit token, bytes memory recipient, uint256 amount) public {
+        token.withdrawToNear(recipient, amount);
+    }
+}
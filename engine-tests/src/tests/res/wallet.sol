@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+// EIP-1271 wallet accepting the ECDSA signatures of its owner
+contract Wallet {
+    address public owner;
+
+    constructor(address _owner) {
+        owner = _owner;
+    }
+
+    function isValidSignature(bytes32 hash, bytes memory signature) public view returns (bytes4) {
+        if (signature.length != 65) {
+            return 0xffffffff;
+        }
+        bytes32 r;
+        bytes32 s;
+        uint8 v;
+        assembly {
+            r := mload(add(signature, 32))
+            s := mload(add(signature, 64))
+            v := byte(0, mload(add(signature, 96)))
+        }
+        if (ecrecover(hash, v, r, s) == owner) {
+            return 0x1626ba7e;
+        }
+        return 0xffffffff;
+    }
+}
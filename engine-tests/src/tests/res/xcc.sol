@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract XccTester {
+    address immutable precompile;
+    uint public callbackCount;
+    bool public lastSuccess;
+    bytes public lastResult;
+
+    constructor(address _precompile) {
+        precompile = _precompile;
+    }
+
+    // `input` is the Borsh-encoded `CrossContractCallArgs`
+    function crossContractCall(bytes memory input) public {
+        (bool success, ) = precompile.call(input);
+        require(success, "XCC failed");
+    }
+
+    function onResult(bool success, bytes memory result) public {
+        require(msg.sender == precompile, "Not the precompile");
+        callbackCount += 1;
+        lastSuccess = success;
+        lastResult = result;
+    }
+}
@@ -4,7 +4,13 @@ use crate::test_utils;
 use crate::tests::state_migration;
 use aurora_engine::fungible_token::FungibleTokenMetadata;
 use aurora_engine::parameters::{SubmitResult, TransactionStatus};
+use aurora_engine::transaction::legacy::TransactionLegacy;
+use aurora_engine_precompiles::ed25519::Ed25519Verify;
+use aurora_engine_precompiles::identity::Identity;
+use aurora_engine_precompiles::near_gas;
 use aurora_engine_sdk as sdk;
+use aurora_engine_types::parameters::{DisabledPrecompileMode, SetPrecompileStatusArgs};
+use aurora_engine_types::types::NearGas;
 use borsh::{BorshDeserialize, BorshSerialize};
 use rand::RngCore;
 use secp256k1::SecretKey;
@@ -33,6 +39,7 @@ fn test_state_format() {
         "04000000626f7373",
         "1300000070726f7665725f6d6370726f76795f66616365",
         "0300000000000000",
+        "00",
     ]
     .concat();
     assert_eq!(hex::encode(state.try_to_vec().unwrap()), expected_hex);
@@ -503,6 +510,115 @@ fn test_eth_transfer_charging_gas_not_enough_balance() {
     test_utils::validate_address_balance_and_nonce(&runner, relayer, Wei::zero(), 0.into());
 }
 
+#[test]
+fn test_ed25519_verify_near_gas() {
+    // The EVM gas charged by the ed25519 precompile must cover the NEAR gas the contract
+    // burns verifying the signature, in wasm or with the host function of `ed25519_host`.
+    let (mut runner, mut signer, _) = initialize_transfer();
+
+    // Signed with the RFC 8032 TEST 1 key
+    let public_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    let signature = "\
+        080312ea44e83a63be9cd7eef022e910792b38c654fbb3b1ba7ab30d277a911c\
+        6e458b76ff93c0d587c8022e8835b062645e26be239e66cdd235f231644d040f";
+    let message = vec![0xab; 1024];
+    let input = [
+        message,
+        hex::decode([public_key, signature].concat()).unwrap(),
+    ]
+    .concat();
+
+    let mut submit = |address: Address| {
+        runner
+            .submit_with_signer_profiled(&mut signer, |nonce| TransactionLegacy {
+                data: input.clone(),
+                ..test_utils::transfer(address, Wei::zero(), nonce)
+            })
+            .unwrap()
+    };
+    let (result, profile) = submit(Ed25519Verify::ADDRESS);
+    let mut valid = [0u8; 32];
+    valid[31] = 1;
+    assert_eq!(test_utils::unwrap_success_slice(&result), valid);
+    // The identity precompile takes the same input without verifying it
+    let (baseline, baseline_profile) = submit(Identity::ADDRESS);
+    test_utils::unwrap_success_slice(&baseline);
+
+    let near_gas = NearGas::new(profile.all_gas() - baseline_profile.all_gas());
+    let eth_gas = result.gas_used - baseline.gas_used;
+    let expected = near_gas::to_eth_gas(near_gas, near_gas::DEFAULT_ETH_GAS_PER_TGAS).unwrap();
+    assert!(
+        eth_gas >= expected.into_u64(),
+        "{} EVM gas charged for {:?} burnt",
+        eth_gas,
+        near_gas
+    );
+}
+
+#[test]
+fn test_set_precompile_status() {
+    let (mut runner, mut signer, _) = initialize_transfer();
+    let owner = runner.aurora_account_id.clone();
+    let input = b"hello".to_vec();
+
+    let set_status = |runner: &mut test_utils::AuroraRunner, caller: &str, disabled| {
+        let args = SetPrecompileStatusArgs {
+            address: Identity::ADDRESS.0,
+            disabled,
+        };
+        runner
+            .call("set_precompile_status", caller, args.try_to_vec().unwrap())
+            .1
+    };
+    let mut call_identity = |runner: &mut test_utils::AuroraRunner| {
+        runner
+            .submit_with_signer(&mut signer, |nonce| TransactionLegacy {
+                data: input.clone(),
+                ..test_utils::transfer(Identity::ADDRESS, Wei::zero(), nonce)
+            })
+            .unwrap()
+            .status
+    };
+
+    // Only the owner can change the status of a precompile
+    let error = set_status(
+        &mut runner,
+        "stranger.near",
+        Some(DisabledPrecompileMode::Revert),
+    );
+    assert!(error.unwrap().to_string().contains("ERR_NOT_ALLOWED"));
+    assert_eq!(
+        call_identity(&mut runner),
+        TransactionStatus::Succeed(input.clone())
+    );
+
+    // Disabled like an account without code
+    let error = set_status(
+        &mut runner,
+        &owner,
+        Some(DisabledPrecompileMode::EmptyAccount),
+    );
+    assert!(error.is_none());
+    assert_eq!(
+        call_identity(&mut runner),
+        TransactionStatus::Succeed(Vec::new())
+    );
+
+    // Disabled by reverting
+    set_status(&mut runner, &owner, Some(DisabledPrecompileMode::Revert));
+    assert_eq!(
+        call_identity(&mut runner),
+        TransactionStatus::Revert(Vec::new())
+    );
+
+    // Enabled again
+    set_status(&mut runner, &owner, None);
+    assert_eq!(
+        call_identity(&mut runner),
+        TransactionStatus::Succeed(input.clone())
+    );
+}
+
 fn initialize_transfer() -> (test_utils::AuroraRunner, test_utils::Signer, Address) {
     // set up Aurora runner and accounts
     let mut runner = test_utils::deploy_evm();
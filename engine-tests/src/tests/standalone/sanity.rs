@@ -1,8 +1,24 @@
-use crate::test_utils::standalone::mocks::{promise, storage};
+use crate::test_utils::{
+    self,
+    standalone::mocks::{promise, storage},
+};
+use aurora_engine::connector::{error::FinishDepositError, EthConnectorContract};
 use aurora_engine::engine;
-use aurora_engine_sdk::env::DEFAULT_PREPAID_GAS;
-use aurora_engine_types::types::Wei;
+use aurora_engine::parameters::{
+    CallArgs, DeployErc20TokenArgs, FinishDepositCallArgs, FunctionCallArgsV2,
+    MigrateUsedProofsCallArgs, TransactionStatus, UsedProofsStorage, WrappedNearCallArgs,
+};
+use aurora_engine::transaction::legacy::TransactionLegacy;
+use aurora_engine_precompiles::bls12_381::Bls12381G1Add;
+use aurora_engine_precompiles::random::UniqueRandomSeed;
+use aurora_engine_sdk::env::{Env, DEFAULT_PREPAID_GAS};
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+use aurora_engine_types::parameters::PromiseAction;
+use aurora_engine_types::storage::{bytes_to_key, EthConnectorStorageId, KeyPrefix};
+use aurora_engine_types::types::{NearGas, PromiseResult, Wei, WeiU256};
 use aurora_engine_types::{account_id::AccountId, Address, H256, U256};
+use borsh::BorshDeserialize;
+use secp256k1::SecretKey;
 use std::sync::RwLock;
 
 #[test]
@@ -19,6 +35,7 @@ fn test_deploy_code() {
         owner_id: owner_id.clone(),
         bridge_prover_id: "mr_the_prover".parse().unwrap(),
         upgrade_delay_blocks: 0,
+        unprotected_transactions_policy: Default::default(),
     };
     let origin = Address([0u8; 20]);
     let storage = RwLock::new(storage::Storage::default());
@@ -63,6 +80,492 @@ fn test_deploy_code() {
     assert_eq!(engine::get_code(&io, &contract_address), code_to_deploy);
 }
 
+#[test]
+fn test_unprotected_transactions_policy() {
+    let owner_id: AccountId = "aurora".parse().unwrap();
+    let chain_id = 1313161554;
+    let state = engine::EngineState {
+        chain_id: aurora_engine_types::types::u256_to_arr(&U256::from(chain_id)),
+        owner_id: owner_id.clone(),
+        bridge_prover_id: "mr_the_prover".parse().unwrap(),
+        upgrade_delay_blocks: 0,
+        unprotected_transactions_policy: engine::UnprotectedTransactionsPolicy::Reject,
+    };
+    let storage = RwLock::new(storage::Storage::default());
+    let mut io = storage::StoragePointer(&storage);
+    let env = aurora_engine_sdk::env::Fixed {
+        signer_account_id: owner_id.clone(),
+        current_account_id: owner_id.clone(),
+        predecessor_account_id: owner_id.clone(),
+        block_height: 0,
+        block_timestamp: aurora_engine_sdk::env::Timestamp::new(0),
+        attached_deposit: 0,
+        random_seed: H256::zero(),
+        prepaid_gas: DEFAULT_PREPAID_GAS,
+    };
+    let mut handler = promise::PromiseTracker::default();
+    let secret_key = SecretKey::random(&mut rand::thread_rng());
+    let mut submit = |transaction_bytes: &[u8], io: storage::StoragePointer| {
+        engine::submit(
+            io,
+            &env,
+            transaction_bytes,
+            state.clone(),
+            owner_id.clone(),
+            Address::zero(),
+            &mut handler,
+        )
+        .map_err(|e| e.kind)
+    };
+
+    // Unprotected transactions are rejected
+    let transfer = test_utils::transfer(Address([1; 20]), Wei::zero(), U256::zero());
+    let unprotected = rlp::encode(&test_utils::sign_transaction(transfer, None, &secret_key));
+    assert_eq!(
+        submit(&unprotected, io).unwrap_err(),
+        engine::EngineErrorKind::UnprotectedTransaction
+    );
+
+    // unless allow-listed
+    let transaction_hash = aurora_engine_sdk::keccak(&unprotected);
+    engine::set_unprotected_transaction_allowed(&mut io, &transaction_hash, true);
+    assert!(engine::is_unprotected_transaction_allowed(
+        &io,
+        &transaction_hash
+    ));
+    submit(&unprotected, io).unwrap();
+
+    // Protected transactions are accepted
+    let transfer = test_utils::transfer(Address([1; 20]), Wei::zero(), U256::one());
+    let protected = rlp::encode(&test_utils::sign_transaction(
+        transfer,
+        Some(chain_id),
+        &secret_key,
+    ));
+    submit(&protected, io).unwrap();
+
+    engine::set_unprotected_transaction_allowed(&mut io, &transaction_hash, false);
+    assert!(!engine::is_unprotected_transaction_allowed(
+        &io,
+        &transaction_hash
+    ));
+}
+
+#[test]
+fn test_migrate_state() {
+    let storage = RwLock::new(storage::Storage::default());
+    let mut io = storage::StoragePointer(&storage);
+    // State saved before `unprotected_transactions_policy` was added
+    let legacy_state = hex::decode(
+        [
+            "000000000000000000000000000000000000000000000000000000000000029a",
+            "04000000626f7373",
+            "1300000070726f7665725f6d6370726f76795f66616365",
+            "0300000000000000",
+        ]
+        .concat(),
+    )
+    .unwrap();
+    io.write_storage(&bytes_to_key(KeyPrefix::Config, b"STATE"), &legacy_state);
+    assert!(engine::get_state(&io).is_err());
+
+    engine::migrate_state(&mut io).unwrap();
+    let state = engine::get_state(&io).unwrap();
+    assert_eq!(state.owner_id, "boss".parse().unwrap());
+    assert_eq!(state.upgrade_delay_blocks, 3);
+    assert_eq!(
+        state.unprotected_transactions_policy,
+        engine::UnprotectedTransactionsPolicy::Allow
+    );
+
+    // Migrating the current layout changes nothing
+    engine::migrate_state(&mut io).unwrap();
+    assert_eq!(engine::get_state(&io).unwrap().upgrade_delay_blocks, 3);
+}
+
+#[test]
+fn test_migrate_used_proofs() {
+    let storage = RwLock::new(storage::Storage::default());
+    let mut io = storage::StoragePointer(&storage);
+    let env = test_utils::standalone::mocks::default_env(0);
+    test_utils::standalone::mocks::init_evm(io, &env, 1313161554);
+    let aurora_id = env.current_account_id();
+    let finish_deposit = |proof_key: &str| {
+        let args = FinishDepositCallArgs {
+            new_owner_id: aurora_id.clone(),
+            amount: 100,
+            proof_key: proof_key.to_string(),
+            relayer_id: aurora_id.clone(),
+            fee: 0.into(),
+            msg: None,
+        };
+        EthConnectorContract::init_instance(io).finish_deposit(
+            aurora_id.clone(),
+            aurora_id.clone(),
+            args,
+            DEFAULT_PREPAID_GAS,
+        )
+    };
+
+    // Proof recorded before the compact registry
+    let legacy_proof_key = "legacy_proof";
+    let legacy_key = bytes_to_key(
+        KeyPrefix::EthConnector,
+        &[
+            &[EthConnectorStorageId::UsedEvent as u8],
+            legacy_proof_key.as_bytes(),
+        ]
+        .concat(),
+    );
+    io.write_borsh(&legacy_key, &0u8);
+
+    // Legacy keys are still used before the migration
+    assert!(matches!(
+        finish_deposit(legacy_proof_key),
+        Err(FinishDepositError::ProofUsed)
+    ));
+    let used_proofs = EthConnectorContract::init_instance(io).get_used_proofs_storage();
+    assert_eq!(used_proofs.entries, 0);
+
+    let args = MigrateUsedProofsCallArgs {
+        proof_keys: vec![legacy_proof_key.to_string(), "unknown_proof".to_string()],
+    };
+    let migrated = EthConnectorContract::init_instance(io).migrate_used_proofs(args);
+    assert_eq!(migrated, 1);
+    assert!(!io.storage_has_key(&legacy_key));
+    assert!(matches!(
+        finish_deposit(legacy_proof_key),
+        Err(FinishDepositError::ProofUsed)
+    ));
+
+    // A 35-byte key, a 1-byte value and the 40 bytes of the storage record
+    let used_proofs = EthConnectorContract::init_instance(io).get_used_proofs_storage();
+    assert_eq!(
+        used_proofs,
+        UsedProofsStorage {
+            entries: 1,
+            bytes: 76,
+        }
+    );
+
+    // Proofs used after the migration join the registry
+    finish_deposit("new_proof").unwrap();
+    assert!(matches!(
+        finish_deposit("new_proof"),
+        Err(FinishDepositError::ProofUsed)
+    ));
+    let used_proofs = EthConnectorContract::init_instance(io).get_used_proofs_storage();
+    assert_eq!(used_proofs.entries, 2);
+    assert_eq!(used_proofs.bytes, 2 * 76);
+
+    // Migrating again changes nothing
+    let args = MigrateUsedProofsCallArgs {
+        proof_keys: vec![legacy_proof_key.to_string()],
+    };
+    let migrated = EthConnectorContract::init_instance(io).migrate_used_proofs(args);
+    assert_eq!(migrated, 0);
+    let used_proofs = EthConnectorContract::init_instance(io).get_used_proofs_storage();
+    assert_eq!(used_proofs.entries, 2);
+}
+
+#[test]
+fn test_unique_random_seed_in_calls() {
+    let owner_id: AccountId = "aurora".parse().unwrap();
+    let state = engine::EngineState {
+        chain_id: aurora_engine_types::types::u256_to_arr(&U256::from(1313161554)),
+        owner_id: owner_id.clone(),
+        bridge_prover_id: "mr_the_prover".parse().unwrap(),
+        upgrade_delay_blocks: 0,
+        unprotected_transactions_policy: Default::default(),
+    };
+    let origin = Address([1u8; 20]);
+    let storage = RwLock::new(storage::Storage::default());
+    let io = storage::StoragePointer(&storage);
+    let env = aurora_engine_sdk::env::Fixed {
+        signer_account_id: owner_id.clone(),
+        current_account_id: owner_id.clone(),
+        predecessor_account_id: owner_id.clone(),
+        block_height: 0,
+        block_timestamp: aurora_engine_sdk::env::Timestamp::new(0),
+        attached_deposit: 0,
+        random_seed: H256::repeat_byte(7),
+        prepaid_gas: DEFAULT_PREPAID_GAS,
+    };
+    let mut handler = promise::PromiseTracker::default();
+    let mut engine = engine::Engine::new_with_state(state, origin, owner_id, io, &env);
+
+    // Returns the output of `UniqueRandomSeed`:
+    // STATICCALL(GAS, ADDRESS, 0, 0, 0, 32); RETURN(0, 32)
+    let code = [
+        hex::decode("602060006000600073").unwrap(),
+        UniqueRandomSeed::<()>::ADDRESS.as_bytes().to_vec(),
+        hex::decode("5afa5060206000f3").unwrap(),
+    ]
+    .concat();
+    let result = engine
+        .deploy_code(
+            origin,
+            Wei::zero(),
+            evm_deploy(&code),
+            u64::MAX,
+            Vec::new(),
+            &mut handler,
+        )
+        .unwrap();
+    let contract = match result.status {
+        TransactionStatus::Succeed(bytes) => Address::from_slice(&bytes),
+        other => panic!("Unexpected status: {:?}", other),
+    };
+    // The execution nonce is only incremented by the executions which use it
+    let execution_nonce = || {
+        io.read_storage(&bytes_to_key(KeyPrefix::Config, b"EXECUTION_NONCE"))
+            .map(|value| value.to_value::<u64>().unwrap())
+    };
+    assert_eq!(execution_nonce(), None);
+
+    // Calls from the same account in the same block, which have no transaction hash
+    let mut call = || {
+        let result = engine
+            .call(
+                origin,
+                contract,
+                Wei::zero(),
+                Vec::new(),
+                u64::MAX,
+                Vec::new(),
+                &mut handler,
+            )
+            .unwrap();
+        match result.status {
+            TransactionStatus::Succeed(bytes) => bytes,
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    };
+    let first = call();
+    let second = call();
+    assert_eq!(first.len(), 32);
+    assert_ne!(first, vec![0u8; 32]);
+    assert_ne!(first, second);
+    assert_eq!(execution_nonce(), Some(2));
+}
+
+#[test]
+fn test_bls12_381_precompiles_inactive() {
+    let mut runner = test_utils::standalone::StandaloneRunner::default();
+    let mut signer = test_utils::Signer::random();
+    runner.init_evm();
+    runner.mint_account(
+        test_utils::address_from_secret_key(&signer.secret_key),
+        Wei::zero(),
+        signer.nonce.into(),
+        None,
+    );
+
+    // The BLS12-381 precompiles are not part of the London set used by the engine, so
+    // calling one is a plain call to an account without code.
+    let tx = TransactionLegacy {
+        data: vec![0u8; 256],
+        ..test_utils::transfer(
+            Bls12381G1Add::ADDRESS,
+            Wei::zero(),
+            signer.use_nonce().into(),
+        )
+    };
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    assert_eq!(result.status, TransactionStatus::Succeed(Vec::new()));
+
+    runner.close();
+}
+
+#[test]
+fn test_call_with_attached_near() {
+    let storage = RwLock::new(storage::Storage::default());
+    let mut io = storage::StoragePointer(&storage);
+    let env = test_utils::standalone::mocks::default_env(0);
+    test_utils::standalone::mocks::init_evm(io, &env, 1313161554);
+    let mut handler = promise::PromiseTracker::default();
+
+    let wnear: AccountId = "wrap.near".parse().unwrap();
+    let args = DeployErc20TokenArgs {
+        nep141: wnear.clone(),
+    };
+    let wnear_address = engine::deploy_erc20_token(args, io, &env, &mut handler).unwrap();
+    let token = test_utils::erc20::ERC20(
+        test_utils::erc20::ERC20Constructor::load()
+            .0
+            .deployed_at(wnear_address),
+    );
+
+    // Attached NEAR is rejected until the wNEAR address is set
+    assert_eq!(engine::get_wnear_address(&io), None);
+    engine::set_wnear_address(&mut io, Some(wnear_address));
+    assert_eq!(engine::get_wnear_address(&io), Some(wnear_address));
+
+    let origin = Address([1u8; 20]);
+    let amount: u128 = 1_000_000;
+    let call_args = |input: Vec<u8>| {
+        CallArgs::V2(FunctionCallArgsV2 {
+            contract: wnear_address.0,
+            value: WeiU256::default(),
+            input,
+        })
+    };
+    let near_deposits = |handler: &promise::PromiseTracker| {
+        handler
+            .scheduled_promises
+            .values()
+            .filter_map(|promise| match promise {
+                promise::PromiseArgs::Create(args) if args.method == "near_deposit" => {
+                    Some((args.target_account_id.clone(), args.attached_balance))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let wrapped_near_args = |handler: &promise::PromiseTracker| {
+        handler
+            .scheduled_promises
+            .values()
+            .find_map(|promise| match promise {
+                promise::PromiseArgs::Callback { callback, .. }
+                    if callback.method == "call_with_wrapped_near" =>
+                {
+                    Some(WrappedNearCallArgs::try_from_slice(&callback.args).unwrap())
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+    let mut engine = engine::Engine::new(origin, env.current_account_id(), io, &env).unwrap();
+    let balance = |engine: &mut engine::Engine<_, _>| {
+        let input = token.balance_of(origin, U256::zero()).data;
+        let result = engine
+            .call_with_args(call_args(input), &mut promise::PromiseTracker::default())
+            .unwrap();
+        U256::from_big_endian(&test_utils::unwrap_success(result))
+    };
+
+    // The deposit is wrapped before any wNEAR is minted and the call is made by the callback
+    let mut handler = promise::PromiseTracker::default();
+    let input = token.balance_of(origin, U256::zero()).data;
+    engine
+        .call_with_attached_near(
+            call_args(input.clone()),
+            wnear_address,
+            amount,
+            &mut handler,
+        )
+        .unwrap();
+    assert_eq!(near_deposits(&handler), vec![(wnear.clone(), amount)]);
+    let args = wrapped_near_args(&handler);
+    assert_eq!(
+        args,
+        WrappedNearCallArgs {
+            wnear: wnear_address.0,
+            origin: origin.0,
+            payer: env.predecessor_account_id(),
+            amount,
+            call: call_args(input),
+        }
+    );
+    assert_eq!(balance(&mut engine), U256::zero());
+
+    // The NEAR is refunded to the payer if it could not be wrapped, and nothing is minted
+    let mut handler = promise::PromiseTracker::default();
+    let result = engine
+        .call_with_wrapped_near(args.clone(), PromiseResult::Failed, &mut handler)
+        .unwrap();
+    assert!(result.is_none());
+    let refund = handler
+        .scheduled_promises
+        .values()
+        .find_map(|promise| match promise {
+            promise::PromiseArgs::Batch(batch) => Some(batch),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(refund.target_account_id, env.predecessor_account_id());
+    assert!(matches!(
+        refund.actions.as_slice(),
+        [PromiseAction::Transfer { amount: refunded }] if *refunded == amount
+    ));
+    assert_eq!(balance(&mut engine), U256::zero());
+
+    // Once wrapped, the wNEAR is minted before the call runs
+    let result = engine
+        .call_with_wrapped_near(
+            args,
+            PromiseResult::Successful(Vec::new()),
+            &mut promise::PromiseTracker::default(),
+        )
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        result.status,
+        TransactionStatus::Succeed(
+            aurora_engine_types::types::u256_to_arr(&amount.into()).to_vec()
+        )
+    );
+    assert_eq!(balance(&mut engine), amount.into());
+
+    // The minted wNEAR is backed by the wrapped NEAR, so it is kept if the call reverts
+    let input = token
+        .transfer(Address([2u8; 20]), (3 * amount).into(), U256::zero())
+        .data;
+    let mut handler = promise::PromiseTracker::default();
+    engine
+        .call_with_attached_near(call_args(input), wnear_address, amount, &mut handler)
+        .unwrap();
+    let result = engine
+        .call_with_wrapped_near(
+            wrapped_near_args(&handler),
+            PromiseResult::Successful(Vec::new()),
+            &mut promise::PromiseTracker::default(),
+        )
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(result.status, TransactionStatus::Revert(_)));
+    assert_eq!(balance(&mut engine), (2 * amount).into());
+
+    // An ERC-20 not bridged from a NEP-141 cannot be wNEAR
+    let mut handler = promise::PromiseTracker::default();
+    let input = token.balance_of(origin, U256::zero()).data;
+    let result = engine.call_with_attached_near(
+        call_args(input.clone()),
+        Address([3u8; 20]),
+        amount,
+        &mut handler,
+    );
+    assert!(matches!(
+        result,
+        Err(engine::CallWithNearError::WnearNotFound)
+    ));
+
+    // The prepaid gas must cover the callback making the call
+    let low_gas_env = aurora_engine_sdk::env::Fixed {
+        prepaid_gas: NearGas::new(15_000_000_000_000),
+        ..env.clone()
+    };
+    let mut low_gas_engine =
+        engine::Engine::new(origin, env.current_account_id(), io, &low_gas_env).unwrap();
+    let result = low_gas_engine.call_with_attached_near(
+        call_args(input),
+        wnear_address,
+        amount,
+        &mut handler,
+    );
+    assert!(matches!(
+        result,
+        Err(engine::CallWithNearError::NotEnoughGas)
+    ));
+
+    // Unsetting the wNEAR address stops wrapping
+    engine::set_wnear_address(&mut io, None);
+    assert_eq!(engine::get_wnear_address(&io), None);
+}
+
 fn evm_deploy(code: &[u8]) -> Vec<u8> {
     let len = code.len();
     if len > u16::MAX as usize {
@@ -1,5 +1,7 @@
+use aurora_engine::admin_controlled::AdminControlled;
 use aurora_engine::deposit_event::TokenMessageData;
 use aurora_engine_sdk::env::{Env, Timestamp};
+use aurora_engine_sdk::io::IO;
 use aurora_engine_types::types::Fee;
 use aurora_engine_types::{account_id::AccountId, types::Wei, Address, H256, U256};
 use borsh::BorshSerialize;
@@ -66,6 +68,274 @@ fn test_consume_deposit_message() {
     runner.close()
 }
 
+#[test]
+fn test_consume_used_proofs_messages() {
+    let (mut runner, block_message) = initialize();
+
+    // Proof recorded before the compact registry
+    let legacy_proof_key = "legacy_proof";
+    let legacy_key = aurora_engine_types::storage::bytes_to_key(
+        aurora_engine_types::storage::KeyPrefix::EthConnector,
+        &[
+            &[aurora_engine_types::storage::EthConnectorStorageId::UsedEvent as u8],
+            legacy_proof_key.as_bytes(),
+        ]
+        .concat(),
+    );
+    let mut diff = engine_standalone_storage::Diff::default();
+    diff.modify(legacy_key.clone(), 0u8.try_to_vec().unwrap());
+    test_utils::standalone::storage::commit(
+        &mut runner.storage,
+        diff,
+        block_message.hash,
+        0,
+        H256([6u8; 32]),
+    );
+
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let transaction_message =
+        |near_tx_hash, position, transaction| sync::types::TransactionMessage {
+            block_hash: block_message.hash,
+            near_tx_hash,
+            position,
+            succeeded: true,
+            signer: signer.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+    let message = transaction_message(
+        H256([7u8; 32]),
+        1,
+        sync::types::TransactionKind::MigrateUsedProofs(
+            aurora_engine::parameters::MigrateUsedProofsCallArgs {
+                proof_keys: vec![legacy_proof_key.to_string()],
+            },
+        ),
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert!(!io.storage_has_key(&legacy_key));
+    assert_eq!(
+        aurora_engine::connector::EthConnectorContract::init_instance(io)
+            .get_used_proofs_storage()
+            .entries,
+        1
+    );
+
+    // Proofs of blocks below the watermark are rejected, the mock proof has no block number
+    let message = transaction_message(
+        H256([8u8; 32]),
+        2,
+        sync::types::TransactionKind::SetProofWatermark(
+            aurora_engine::parameters::SetProofWatermarkCallArgs {
+                min_block_number: Some(1),
+            },
+        ),
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+    let proof = mock_proof(Address([22u8; 20]), Wei::new_u64(123_456_789));
+    let message = transaction_message(
+        H256([9u8; 32]),
+        3,
+        sync::types::TransactionKind::Deposit(proof.try_to_vec().unwrap()),
+    );
+    let result = sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    );
+    assert!(matches!(
+        result,
+        Err(sync::error::Error::Deposit(
+            aurora_engine::connector::error::DepositError::ProofTooOld
+        ))
+    ));
+}
+
+#[test]
+fn test_consume_deposit_batch_message() {
+    let (mut runner, block_message) = initialize();
+
+    let verified_address = Address([22u8; 20]);
+    let unverified_address = Address([23u8; 20]);
+    let deposit_amount = Wei::new_u64(123_456_789);
+    let verified_proof = mock_proof(verified_address, deposit_amount);
+    let unverified_proof = aurora_engine::proof::Proof {
+        log_index: 2,
+        ..mock_proof(unverified_address, deposit_amount)
+    };
+
+    // The proof given twice can only be finished once
+    let args = aurora_engine::parameters::DepositBatchCallArgs {
+        proofs: vec![verified_proof.clone(), unverified_proof, verified_proof],
+    };
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash: H256([7u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.predecessor_account_id(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::DepositBatch {
+            args,
+            verified: vec![true, false, true],
+        },
+    };
+
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+
+    assert_eq!(runner.get_balance(&verified_address), deposit_amount);
+    assert_eq!(runner.get_balance(&unverified_address), Wei::zero());
+
+    runner.close()
+}
+
+#[test]
+fn test_consume_deposit_erc20_messages() {
+    let (mut runner, _) = initialize();
+
+    // An ERC-20 the engine can mint, which is not bridged from a NEP-141 token
+    let mut signer = test_utils::Signer::random();
+    let constructor = test_utils::erc20::ERC20Constructor::load();
+    let tx = constructor.deploy("Locked", "LCK", signer.use_nonce().into());
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    let token = test_utils::erc20::ERC20(
+        constructor
+            .0
+            .deployed_at(Address::from_slice(&test_utils::unwrap_success(result))),
+    );
+    let engine_address = aurora_engine::engine::current_address(&runner.env.current_account_id());
+    let tx = token.0.call_method_with_args(
+        "grantRole",
+        &[
+            ethabi::Token::FixedBytes(
+                aurora_engine_sdk::types::keccak(b"MINTER_ROLE")
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            ethabi::Token::Address(engine_address),
+        ],
+        signer.use_nonce().into(),
+    );
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    test_utils::unwrap_success(result);
+
+    runner.env.block_height += 1;
+    test_utils::standalone::mocks::insert_block(&mut runner.storage, runner.env.block_height);
+    let block_hash = test_utils::standalone::mocks::compute_block_hash(runner.env.block_height);
+    let signer_account_id = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    // Each message is at its own position, so that it sees the changes of the previous ones
+    let consume = |runner: &mut StandaloneRunner, position: u16, transaction| {
+        let message = sync::types::TransactionMessage {
+            block_hash,
+            near_tx_hash: H256::from_low_u64_be(position.into()),
+            position,
+            succeeded: true,
+            signer: signer_account_id.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(message)),
+        )
+    };
+
+    let locker_address = Address([0x10; 20]);
+    let eth_token = Address([0x20; 20]);
+    let args = aurora_engine::parameters::SetErc20LockerCallArgs {
+        locker_address: locker_address.0,
+    };
+    consume(
+        &mut runner,
+        0,
+        sync::types::TransactionKind::SetErc20Locker(args),
+    )
+    .unwrap();
+
+    // ERC-20s bridged from NEP-141 tokens cannot be registered
+    let nep141: AccountId = "some_nep141.near".parse().unwrap();
+    let args = aurora_engine::parameters::DeployErc20TokenArgs {
+        nep141: nep141.clone(),
+    };
+    consume(
+        &mut runner,
+        1,
+        sync::types::TransactionKind::DeployErc20(args),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height, 2, &[]);
+    let nep141_erc20 = aurora_engine::engine::get_erc20_from_nep141(&io, &nep141).unwrap();
+    let args = aurora_engine::parameters::RegisterBridgedErc20CallArgs {
+        eth_token: eth_token.0,
+        erc20: Address::from_slice(&nep141_erc20).0,
+    };
+    let result = consume(
+        &mut runner,
+        2,
+        sync::types::TransactionKind::RegisterBridgedErc20(args),
+    );
+    assert!(matches!(
+        result,
+        Err(sync::error::Error::RegisterBridgedErc20(_))
+    ));
+
+    let args = aurora_engine::parameters::RegisterBridgedErc20CallArgs {
+        eth_token: eth_token.0,
+        erc20: token.0.address.0,
+    };
+    consume(
+        &mut runner,
+        3,
+        sync::types::TransactionKind::RegisterBridgedErc20(args),
+    )
+    .unwrap();
+
+    // The proof of the tokens locked on Ethereum mints them on Aurora, only once
+    let recipient_address = Address([0x30; 20]);
+    let amount = U256::from(123_456_789);
+    let proof = mock_erc20_locked_proof(locker_address, eth_token, recipient_address, amount);
+    consume(
+        &mut runner,
+        4,
+        sync::types::TransactionKind::DepositErc20(proof.try_to_vec().unwrap()),
+    )
+    .unwrap();
+    let result = consume(
+        &mut runner,
+        5,
+        sync::types::TransactionKind::DepositErc20(proof.try_to_vec().unwrap()),
+    );
+    assert!(matches!(result, Err(sync::error::Error::ProofUsed(_))));
+
+    let tx = token.balance_of(recipient_address, signer.use_nonce().into());
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    assert_eq!(
+        U256::from_big_endian(&test_utils::unwrap_success(result)),
+        amount
+    );
+}
+
 #[test]
 fn test_consume_deploy_message() {
     let (mut runner, block_message) = initialize();
@@ -191,6 +461,201 @@ fn test_consume_deploy_erc20_message() {
     );
 }
 
+#[test]
+fn test_consume_erc20_metadata_message() {
+    let (mut runner, block_message) = initialize();
+
+    let token: AccountId = "some_nep141.near".parse().unwrap();
+    let args = aurora_engine::parameters::DeployErc20TokenArgs {
+        nep141: token.clone(),
+    };
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    // Each message is at its own position, so that it sees the changes of the previous ones
+    let transaction_message =
+        |near_tx_hash, position, transaction| sync::types::TransactionMessage {
+            block_hash: block_message.hash,
+            near_tx_hash,
+            position,
+            succeeded: true,
+            signer: signer.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+    let message = transaction_message(
+        H256([7u8; 32]),
+        0,
+        sync::types::TransactionKind::DeployErc20(args.clone()),
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+
+    // The result of the `ft_metadata` promise is delivered to the callback
+    let metadata = aurora_engine::parameters::Erc20Metadata {
+        name: "Some Token".to_string(),
+        symbol: "SOME".to_string(),
+        decimals: 18,
+    };
+    let message = transaction_message(
+        H256([8u8; 32]),
+        1,
+        sync::types::TransactionKind::SyncErc20MetadataCallback {
+            args,
+            metadata: metadata.clone(),
+        },
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    let erc20_address = aurora_engine::engine::get_erc20_from_nep141(&io, &token).unwrap();
+    let deployed_token = test_utils::erc20::ERC20Constructor::load()
+        .0
+        .deployed_at(Address::from_slice(&erc20_address));
+    let mut signer = test_utils::Signer::random();
+    let mut view = |method: &str| {
+        let tx = deployed_token.call_method_without_args(method, signer.use_nonce().into());
+        let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+        deployed_token
+            .abi
+            .function(method)
+            .unwrap()
+            .decode_output(&test_utils::unwrap_success(result))
+            .unwrap()
+    };
+    assert_eq!(view("name"), vec![ethabi::Token::String(metadata.name)]);
+    assert_eq!(view("symbol"), vec![ethabi::Token::String(metadata.symbol)]);
+    assert_eq!(
+        view("decimals"),
+        vec![ethabi::Token::Uint(metadata.decimals.into())]
+    );
+}
+
+#[test]
+fn test_consume_nft_messages() {
+    let (mut runner, block_message) = initialize();
+
+    let nep171: AccountId = "some_nep171.near".parse().unwrap();
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let transaction_message =
+        |near_tx_hash, position, transaction| sync::types::TransactionMessage {
+            block_hash: block_message.hash,
+            near_tx_hash,
+            position,
+            succeeded: true,
+            signer: signer.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+    // Each message is at its own position, so that it sees the changes of the previous ones
+    let mut position = 0;
+    let mut consume = |near_tx_hash, transaction| {
+        let message = transaction_message(near_tx_hash, position, transaction);
+        position += 1;
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(message)),
+        )
+        .unwrap();
+    };
+
+    // Bridge the NEP-171 token and deliver the `nft_token` result of a deposit
+    let args = aurora_engine::parameters::DeployErc721TokenArgs {
+        nep171: nep171.clone(),
+    };
+    consume(
+        H256([7u8; 32]),
+        sync::types::TransactionKind::DeployErc721(args),
+    );
+    let deposit_recipient = Address([170u8; 20]);
+    consume(
+        H256([8u8; 32]),
+        sync::types::TransactionKind::NftOnTransferCallback {
+            args: aurora_engine::parameters::NftOnTransferCallbackArgs {
+                nep171: nep171.clone(),
+                token_id: "token:1".to_string(),
+                recipient: deposit_recipient.0,
+            },
+            token: aurora_engine::parameters::NftToken {
+                token_id: "token:1".to_string(),
+                token_uri: "uri:1".to_string(),
+            },
+        },
+    );
+
+    // A failed `nft_transfer` of an exit mints the token again, a successful one does not
+    let refund_recipient = Address([187u8; 20]);
+    let refund_args = |token_id: &str| aurora_engine_types::parameters::RefundNftCallArgs {
+        nep171: nep171.clone(),
+        token_id: token_id.to_string(),
+        token_uri: "uri:2".to_string(),
+        recipient_address: refund_recipient.0,
+    };
+    consume(
+        H256([9u8; 32]),
+        sync::types::TransactionKind::RefundNftOnError {
+            args: refund_args("token:2"),
+            promise_result: aurora_engine_types::types::PromiseResult::Failed,
+        },
+    );
+    consume(
+        H256([10u8; 32]),
+        sync::types::TransactionKind::RefundNftOnError {
+            args: refund_args("token:3"),
+            promise_result: aurora_engine_types::types::PromiseResult::Successful(Vec::new()),
+        },
+    );
+
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    let erc721_address = aurora_engine::engine::get_erc721_from_nep171(&io, &nep171).unwrap();
+    let erc721_address = Address::from_slice(&erc721_address);
+    let mut signer = test_utils::Signer::random();
+    let mut view = |method: &str, near_token_id: &str| {
+        let token_id = U256::from_big_endian(
+            aurora_engine_sdk::types::keccak(near_token_id.as_bytes()).as_bytes(),
+        );
+        let data = [
+            ethabi::short_signature(method, &[ethabi::ParamType::Uint(256)]).as_slice(),
+            ethabi::encode(&[ethabi::Token::Uint(token_id)]).as_slice(),
+        ]
+        .concat();
+        let tx = aurora_engine::transaction::legacy::TransactionLegacy {
+            nonce: signer.use_nonce().into(),
+            gas_price: U256::zero(),
+            gas_limit: u64::MAX.into(),
+            to: Some(erc721_address),
+            value: Wei::zero(),
+            data,
+        };
+        runner.submit_transaction(&signer.secret_key, tx).unwrap()
+    };
+    let owner = |address: Address| ethabi::encode(&[ethabi::Token::Address(address)]);
+    let uri = |uri: &str| ethabi::encode(&[ethabi::Token::String(uri.to_string())]);
+
+    let result = view("ownerOf", "token:1");
+    assert_eq!(test_utils::unwrap_success(result), owner(deposit_recipient));
+    let result = view("tokenURI", "token:1");
+    assert_eq!(test_utils::unwrap_success(result), uri("uri:1"));
+    let result = view("ownerOf", "token:2");
+    assert_eq!(test_utils::unwrap_success(result), owner(refund_recipient));
+    let result = view("tokenURI", "token:2");
+    assert_eq!(test_utils::unwrap_success(result), uri("uri:2"));
+    assert!(!view("ownerOf", "token:3").status.is_ok());
+}
+
 #[test]
 fn test_consume_ft_on_transfer_message() {
     // Only need to check the case of aurora calling `ft_on_transfer` on itself, the other case
@@ -282,6 +747,164 @@ fn test_consume_call_message() {
     assert_eq!(runner.get_nonce(&caller_address), U256::one());
 }
 
+#[test]
+fn test_consume_call_message_with_attached_near() {
+    let (mut runner, block_message) = initialize();
+
+    let wnear: AccountId = "wrap.near".parse().unwrap();
+    let caller = "some_account.near";
+    let caller_address = aurora_engine_sdk::types::near_account_to_evm_address(caller.as_bytes());
+    let attached_near = 5_000_000;
+
+    // Bridge wNEAR and configure it as the token attached NEAR is wrapped into
+    let args = aurora_engine::parameters::DeployErc20TokenArgs {
+        nep141: wnear.clone(),
+    };
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash: H256([7u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.predecessor_account_id(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::DeployErc20(args),
+    };
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    let wnear_address = aurora_engine::engine::get_erc20_from_nep141(&io, &wnear).unwrap();
+    let call_args = simple_transfer_args(Address([1u8; 20]), Wei::zero());
+
+    // Attached NEAR is rejected while there is no wNEAR to wrap it into
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash: H256([10u8; 32]),
+        position: 1,
+        succeeded: true,
+        signer: caller.parse().unwrap(),
+        caller: caller.parse().unwrap(),
+        attached_near,
+        transaction: sync::types::TransactionKind::Call(call_args.clone()),
+    };
+    let result = sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    );
+    assert!(matches!(
+        result,
+        Err(sync::error::Error::CallWithNear(
+            aurora_engine::engine::CallWithNearError::WnearNotSet
+        ))
+    ));
+
+    runner.env.block_height += 1;
+    test_utils::standalone::mocks::insert_block(&mut runner.storage, runner.env.block_height);
+    let block_hash = test_utils::standalone::mocks::compute_block_hash(runner.env.block_height);
+    let args = aurora_engine::parameters::SetWnearAddressArgs {
+        address: Some(Address::from_slice(&wnear_address).0),
+    };
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash,
+        near_tx_hash: H256([8u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.predecessor_account_id(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::SetWnearAddress(args),
+    };
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+
+    // Call with attached NEAR
+    runner.env.block_height += 1;
+    runner.env.signer_account_id = caller.parse().unwrap();
+    runner.env.predecessor_account_id = caller.parse().unwrap();
+    test_utils::standalone::mocks::insert_block(&mut runner.storage, runner.env.block_height);
+    let block_hash = test_utils::standalone::mocks::compute_block_hash(runner.env.block_height);
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash,
+        near_tx_hash: H256([9u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.predecessor_account_id(),
+        attached_near,
+        transaction: sync::types::TransactionKind::Call(call_args.clone()),
+    };
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+
+    // The call is made by the callback once the attached NEAR is wrapped, the wNEAR is only
+    // minted then
+    let current_account_id = runner.env.current_account_id();
+    let wrapped_near_message =
+        |near_tx_hash, position, promise_result| sync::types::TransactionMessage {
+            block_hash,
+            near_tx_hash,
+            position,
+            succeeded: true,
+            signer: caller.parse().unwrap(),
+            caller: current_account_id.clone(),
+            attached_near: 0,
+            transaction: sync::types::TransactionKind::CallWithWrappedNear {
+                args: aurora_engine::parameters::WrappedNearCallArgs {
+                    wnear: Address::from_slice(&wnear_address).0,
+                    origin: caller_address.0,
+                    payer: caller.parse().unwrap(),
+                    amount: attached_near,
+                    call: call_args.clone(),
+                },
+                promise_result,
+            },
+        };
+    for (near_tx_hash, position, promise_result) in vec![
+        (
+            H256([11u8; 32]),
+            1,
+            aurora_engine_types::types::PromiseResult::Failed,
+        ),
+        (
+            H256([12u8; 32]),
+            2,
+            aurora_engine_types::types::PromiseResult::Successful(Vec::new()),
+        ),
+    ] {
+        let transaction_message = wrapped_near_message(near_tx_hash, position, promise_result);
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(transaction_message)),
+        )
+        .unwrap();
+    }
+
+    // Only the wrapped NEAR was minted as wNEAR of the caller
+    let deployed_token = test_utils::erc20::ERC20(
+        test_utils::erc20::ERC20Constructor::load()
+            .0
+            .deployed_at(Address::from_slice(&wnear_address)),
+    );
+    let signer = test_utils::Signer::random();
+    let tx = deployed_token.balance_of(caller_address, signer.nonce.into());
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    assert_eq!(
+        U256::from_big_endian(&test_utils::unwrap_success(result)).low_u128(),
+        attached_near
+    );
+}
+
 #[test]
 fn test_consume_submit_message() {
     let (mut runner, _) = initialize();
@@ -331,13 +954,594 @@ fn test_consume_submit_message() {
     assert_eq!(runner.get_nonce(&signer_address), U256::one());
 }
 
-fn mock_proof(recipient_address: Address, deposit_amount: Wei) -> aurora_engine::proof::Proof {
-    let eth_custodian_address = test_utils::standalone::mocks::ETH_CUSTODIAN_ADDRESS;
+#[test]
+fn test_consume_near_gas_ratio_message() {
+    let (mut runner, block_message) = initialize();
 
-    let fee = Fee::new(0);
-    let message = ["aurora", ":", hex::encode(&recipient_address).as_str()].concat();
-    let token_message_data: TokenMessageData =
-        TokenMessageData::parse_event_message_and_prepare_token_message_data(&message, fee)
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let transaction_message = |near_tx_hash, eth_gas_per_tgas| sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash,
+        position: 0,
+        succeeded: true,
+        signer: signer.clone(),
+        caller: caller.clone(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::SetNearGasRatio(eth_gas_per_tgas),
+    };
+    let message = transaction_message(H256([7u8; 32]), 8_000);
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert_eq!(
+        aurora_engine_precompiles::near_gas::get_eth_gas_per_tgas(&io),
+        8_000
+    );
+
+    // A ratio of zero is rejected, the same as by the contract
+    let message = transaction_message(H256([8u8; 32]), 0);
+    let result = sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    );
+    assert!(matches!(result, Err(sync::error::Error::NearGasRatio(_))));
+}
+
+#[test]
+fn test_consume_evm_log_events_message() {
+    let (mut runner, block_message) = initialize();
+
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash: H256([7u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.predecessor_account_id(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::SetEvmLogEvents(
+            aurora_engine::parameters::SetEvmLogEventsArgs { enabled: true },
+        ),
+    };
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert!(aurora_engine::engine::get_evm_log_events(&io));
+}
+
+#[test]
+fn test_consume_unprotected_transactions_messages() {
+    let (mut runner, _) = initialize();
+
+    let mut signer = test_utils::Signer::random();
+    let initial_balance = Wei::new_u64(800_000);
+    let transfer_amount = Wei::new_u64(115_321);
+    let signer_address = test_utils::address_from_secret_key(&signer.secret_key);
+    let recipient_address = Address([1u8; 20]);
+    runner.mint_account(signer_address, initial_balance, signer.nonce.into(), None);
+
+    runner.env.block_height += 1;
+    test_utils::standalone::mocks::insert_block(&mut runner.storage, runner.env.block_height);
+    let block_hash = test_utils::standalone::mocks::compute_block_hash(runner.env.block_height);
+
+    // State written by `new` with the arguments of `mocks::init_evm` before
+    // `unprotected_transactions_policy` was added
+    let legacy_state = hex::decode(
+        [
+            "000000000000000000000000000000000000000000000000000000004e454154",
+            "060000006175726f7261",
+            "120000006272696467655f70726f7665722e6e656172",
+            "0100000000000000",
+        ]
+        .concat(),
+    )
+    .unwrap();
+    let mut diff = engine_standalone_storage::Diff::default();
+    diff.modify(
+        aurora_engine_types::storage::bytes_to_key(
+            aurora_engine_types::storage::KeyPrefix::Config,
+            b"STATE",
+        ),
+        legacy_state,
+    );
+    test_utils::standalone::storage::commit(
+        &mut runner.storage,
+        diff,
+        block_hash,
+        0,
+        H256([6u8; 32]),
+    );
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert!(aurora_engine::engine::get_state(&io).is_err());
+
+    let signer_id = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let transaction_message =
+        |near_tx_hash, position, transaction| sync::types::TransactionMessage {
+            block_hash,
+            near_tx_hash,
+            position,
+            succeeded: true,
+            signer: signer_id.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+
+    // The pre-series state is migrated with unprotected transactions accepted
+    let message = transaction_message(
+        H256([7u8; 32]),
+        1,
+        sync::types::TransactionKind::StateMigration,
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    let state = aurora_engine::engine::get_state(&io).unwrap();
+    assert_eq!(
+        U256::from_big_endian(&state.chain_id),
+        U256::from(runner.chain_id)
+    );
+    assert_eq!(state.owner_id, runner.env.current_account_id());
+    assert_eq!(
+        state.bridge_prover_id,
+        test_utils::str_to_account_id("bridge_prover.near")
+    );
+    assert_eq!(state.upgrade_delay_blocks, 1);
+    assert_eq!(
+        state.unprotected_transactions_policy,
+        aurora_engine::engine::UnprotectedTransactionsPolicy::Allow
+    );
+
+    let message = transaction_message(
+        H256([8u8; 32]),
+        2,
+        sync::types::TransactionKind::SetUnprotectedTransactionsPolicy(
+            aurora_engine::engine::UnprotectedTransactionsPolicy::Reject,
+        ),
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+
+    // A transaction signed without a chain ID is rejected until it is allow-listed
+    let transaction = test_utils::transfer(
+        recipient_address,
+        transfer_amount,
+        signer.use_nonce().into(),
+    );
+    let signed_transaction = test_utils::sign_transaction(transaction, None, &signer.secret_key);
+    let transaction_hash = aurora_engine_sdk::keccak(&rlp::encode(&signed_transaction));
+    let eth_transaction =
+        aurora_engine::transaction::EthTransactionKind::Legacy(signed_transaction);
+    let message = transaction_message(
+        H256([9u8; 32]),
+        3,
+        sync::types::TransactionKind::Submit(eth_transaction.clone()),
+    );
+    let result = sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    );
+    assert!(matches!(
+        result,
+        Err(sync::error::Error::Engine(
+            aurora_engine::engine::EngineError {
+                kind: aurora_engine::engine::EngineErrorKind::UnprotectedTransaction,
+                ..
+            }
+        ))
+    ));
+
+    let message = transaction_message(
+        H256([10u8; 32]),
+        4,
+        sync::types::TransactionKind::SetUnprotectedTransactionAllowed(
+            aurora_engine::parameters::SetUnprotectedTransactionAllowedArgs {
+                transaction_hash: transaction_hash.0,
+                allowed: true,
+            },
+        ),
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+    let message = transaction_message(
+        H256([11u8; 32]),
+        5,
+        sync::types::TransactionKind::Submit(eth_transaction),
+    );
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+
+    assert_eq!(runner.get_balance(&recipient_address), transfer_amount);
+    assert_eq!(runner.get_nonce(&signer_address), U256::one());
+}
+
+#[test]
+fn test_consume_bridge_fees_message() {
+    let (mut runner, block_message) = initialize();
+
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let transaction_message = |near_tx_hash, fees| sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash,
+        position: 0,
+        succeeded: true,
+        signer: signer.clone(),
+        caller: caller.clone(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::SetBridgeFees(fees),
+    };
+    let fee_schedule = |rate| aurora_engine::parameters::FeeSchedule {
+        rate,
+        min: Fee::new(0),
+        max: Fee::new(1_000),
+    };
+    let fees = aurora_engine::parameters::BridgeFees {
+        deposit: Some(fee_schedule(aurora_engine::parameters::FeeRate::Flat(
+            Fee::new(10),
+        ))),
+        withdraw: None,
+        ft_transfer: Some(fee_schedule(
+            aurora_engine::parameters::FeeRate::BasisPoints(25),
+        )),
+        fee_collector: Some("fees.near".parse().unwrap()),
+    };
+    let message = transaction_message(H256([7u8; 32]), fees.clone());
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert_eq!(
+        aurora_engine::connector::EthConnectorContract::init_instance(io).get_bridge_fees(),
+        fees
+    );
+
+    // An invalid fee schedule is rejected, the same as by the contract
+    let invalid_fees = aurora_engine::parameters::BridgeFees {
+        withdraw: Some(fee_schedule(
+            aurora_engine::parameters::FeeRate::BasisPoints(10_001),
+        )),
+        ..fees
+    };
+    let message = transaction_message(H256([8u8; 32]), invalid_fees);
+    let result = sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(message)),
+    );
+    assert!(matches!(result, Err(sync::error::Error::SetBridgeFees(_))));
+}
+
+#[test]
+fn test_consume_connector_admin_messages() {
+    let (mut runner, block_message) = initialize();
+
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let consume = |runner: &mut StandaloneRunner, near_tx_hash, transaction| {
+        let message = sync::types::TransactionMessage {
+            block_hash: block_message.hash,
+            near_tx_hash,
+            position: 0,
+            succeeded: true,
+            signer: signer.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(message)),
+        )
+        .unwrap();
+    };
+    let role_args = aurora_engine::parameters::ConnectorRoleCallArgs {
+        role: aurora_engine::parameters::ConnectorRole::PauseManager,
+        account_id: "manager.near".parse().unwrap(),
+    };
+    let has_role = |runner: &StandaloneRunner| {
+        let io =
+            runner
+                .storage
+                .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+        aurora_engine::connector::EthConnectorContract::init_instance(io)
+            .has_role(role_args.role, &role_args.account_id)
+    };
+
+    consume(
+        &mut runner,
+        H256([7u8; 32]),
+        sync::types::TransactionKind::GrantRole(role_args.clone()),
+    );
+    assert!(has_role(&runner));
+
+    let paused_mask = aurora_engine::connector::PAUSE_DEPOSIT;
+    consume(
+        &mut runner,
+        H256([8u8; 32]),
+        sync::types::TransactionKind::SetPausedFlags(
+            aurora_engine::parameters::PauseEthConnectorCallArgs { paused_mask },
+        ),
+    );
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert_eq!(
+        aurora_engine::connector::EthConnectorContract::init_instance(io).get_paused(),
+        paused_mask
+    );
+
+    consume(
+        &mut runner,
+        H256([9u8; 32]),
+        sync::types::TransactionKind::RevokeRole(role_args.clone()),
+    );
+    assert!(!has_role(&runner));
+}
+
+#[test]
+fn test_consume_precompile_status_messages() {
+    let (mut runner, block_message) = initialize();
+
+    let address = aurora_engine_precompiles::identity::Identity::ADDRESS;
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let transaction_message = |near_tx_hash, disabled| sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash,
+        position: 0,
+        succeeded: true,
+        signer: signer.clone(),
+        caller: caller.clone(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::SetPrecompileStatus(
+            aurora_engine_types::parameters::SetPrecompileStatusArgs {
+                address: address.0,
+                disabled,
+            },
+        ),
+    };
+    let mut consume_and_read = |near_tx_hash, disabled| {
+        let message = transaction_message(near_tx_hash, disabled);
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(message)),
+        )
+        .unwrap();
+        let io =
+            runner
+                .storage
+                .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+        aurora_engine_precompiles::registry::get_disabled(&io)
+    };
+
+    let mode = aurora_engine_types::parameters::DisabledPrecompileMode::Revert;
+    let disabled = consume_and_read(H256([7u8; 32]), Some(mode));
+    assert_eq!(
+        disabled.into_iter().collect::<Vec<_>>(),
+        vec![(address, mode)]
+    );
+
+    let disabled = consume_and_read(H256([8u8; 32]), None);
+    assert!(disabled.is_empty());
+}
+
+#[test]
+fn test_consume_xcc_messages() {
+    let (mut runner, block_message) = initialize();
+
+    let policy = aurora_engine_types::parameters::CrossContractCallPolicy {
+        max_attached_balance: 0,
+        max_attached_gas: 100_000_000_000_000,
+        allowed_targets: vec!["target.near".parse().unwrap()],
+    };
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash: block_message.hash,
+        near_tx_hash: H256([7u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.predecessor_account_id(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::SetXccPolicy(policy.clone()),
+    };
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert_eq!(
+        aurora_engine_precompiles::cross_contract::get_policy(&io),
+        policy
+    );
+
+    // Deliver a promise result to a contract
+    let mut signer = test_utils::Signer::random();
+    let constructor = test_utils::solidity::ContractConstructor::compile_from_source(
+        "src/tests/res",
+        "target/solidity_build",
+        "xcc.sol",
+        "XccTester",
+    );
+    let tx = constructor.deploy_with_args(
+        signer.use_nonce().into(),
+        &[ethabi::Token::Address(
+            aurora_engine_precompiles::cross_contract::ADDRESS,
+        )],
+    );
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    let tester = constructor.deployed_at(Address::from_slice(&test_utils::unwrap_success(result)));
+
+    runner.env.block_height += 1;
+    test_utils::standalone::mocks::insert_block(&mut runner.storage, runner.env.block_height);
+    let block_hash = test_utils::standalone::mocks::compute_block_hash(runner.env.block_height);
+    let args = aurora_engine_types::parameters::EvmCallbackCallArgs {
+        contract: tester.address.0,
+        selector: tester.abi.function("onResult").unwrap().short_signature(),
+        gas_limit: 1_000_000,
+    };
+    let transaction_message = sync::types::TransactionMessage {
+        block_hash,
+        near_tx_hash: H256([8u8; 32]),
+        position: 0,
+        succeeded: true,
+        signer: runner.env.signer_account_id(),
+        caller: runner.env.current_account_id(),
+        attached_near: 0,
+        transaction: sync::types::TransactionKind::EvmCallback {
+            args,
+            promise_result: aurora_engine_types::types::PromiseResult::Successful(
+                b"result".to_vec(),
+            ),
+        },
+    };
+    sync::consume_message(
+        &mut runner.storage,
+        sync::types::Message::Transaction(Box::new(transaction_message)),
+    )
+    .unwrap();
+
+    let tx = tester.call_method_without_args("lastResult", signer.use_nonce().into());
+    let result = runner.submit_transaction(&signer.secret_key, tx).unwrap();
+    let output = tester
+        .abi
+        .function("lastResult")
+        .unwrap()
+        .decode_output(&test_utils::unwrap_success(result))
+        .unwrap();
+    assert_eq!(output, vec![ethabi::Token::Bytes(b"result".to_vec())]);
+}
+
+#[test]
+fn test_consume_xcc_router_messages() {
+    let (mut runner, block_message) = initialize();
+
+    let signer = runner.env.signer_account_id();
+    let caller = runner.env.predecessor_account_id();
+    let consume = |runner: &mut StandaloneRunner, near_tx_hash, transaction| {
+        let message = sync::types::TransactionMessage {
+            block_hash: block_message.hash,
+            near_tx_hash,
+            position: 0,
+            succeeded: true,
+            signer: signer.clone(),
+            caller: caller.clone(),
+            attached_near: 0,
+            transaction,
+        };
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(message)),
+        )
+    };
+
+    let code = b"router code".to_vec();
+    consume(
+        &mut runner,
+        H256([7u8; 32]),
+        sync::types::TransactionKind::SetXccRouterCode(code.clone()),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert_eq!(
+        aurora_engine_precompiles::cross_contract::get_router_code(&io),
+        Some(code)
+    );
+
+    // The deployment is checked as by the contract, no NEAR is attached to pay for it
+    let result = consume(
+        &mut runner,
+        H256([10u8; 32]),
+        sync::types::TransactionKind::DeployXccRouter(
+            aurora_engine_types::parameters::DeployXccRouterArgs { address: [2u8; 20] },
+        ),
+    );
+    assert!(matches!(
+        result,
+        Err(sync::error::Error::DeployXccRouter(
+            aurora_engine::engine::DeployXccRouterError::InsufficientDeposit
+        ))
+    ));
+
+    let deployed =
+        |address: Address, promise_result| sync::types::TransactionKind::XccRouterDeployed {
+            args: aurora_engine_types::parameters::XccRouterDeployedArgs {
+                address: address.0,
+                payer: "payer.near".parse().unwrap(),
+                amount: 1,
+            },
+            promise_result,
+        };
+    let failed_address = Address([1u8; 20]);
+    let address = Address([2u8; 20]);
+    consume(
+        &mut runner,
+        H256([8u8; 32]),
+        deployed(
+            failed_address,
+            aurora_engine_types::types::PromiseResult::Failed,
+        ),
+    )
+    .unwrap();
+    consume(
+        &mut runner,
+        H256([9u8; 32]),
+        deployed(
+            address,
+            aurora_engine_types::types::PromiseResult::Successful(Vec::new()),
+        ),
+    )
+    .unwrap();
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert!(!aurora_engine_precompiles::cross_contract::is_router_deployed(&io, &failed_address));
+    assert!(aurora_engine_precompiles::cross_contract::is_router_deployed(&io, &address));
+}
+
+fn mock_proof(recipient_address: Address, deposit_amount: Wei) -> aurora_engine::proof::Proof {
+    let eth_custodian_address = test_utils::standalone::mocks::ETH_CUSTODIAN_ADDRESS;
+
+    let fee = Fee::new(0);
+    let message = ["aurora", ":", hex::encode(&recipient_address).as_str()].concat();
+    let token_message_data: TokenMessageData =
+        TokenMessageData::parse_event_message_and_prepare_token_message_data(&message, fee)
             .unwrap();
 
     let deposit_event = aurora_engine::deposit_event::DepositedEvent {
@@ -377,6 +1581,40 @@ fn mock_proof(recipient_address: Address, deposit_amount: Wei) -> aurora_engine:
     }
 }
 
+fn mock_erc20_locked_proof(
+    locker_address: Address,
+    eth_token: Address,
+    recipient_address: Address,
+    amount: U256,
+) -> aurora_engine::proof::Proof {
+    let event_schema = ethabi::Event {
+        name: aurora_engine::deposit_event::LOCKED_EVENT.into(),
+        inputs: aurora_engine::deposit_event::Erc20LockedEvent::event_params(),
+        anonymous: false,
+    };
+    let log_entry = aurora_engine::log_entry::LogEntry {
+        address: locker_address,
+        topics: vec![
+            event_schema.signature(),
+            crate::prelude::H256::from(eth_token),
+            // the sender is not important
+            crate::prelude::H256::zero(),
+        ],
+        data: ethabi::encode(&[
+            ethabi::Token::Uint(amount),
+            ethabi::Token::String(hex::encode(recipient_address)),
+        ]),
+    };
+    aurora_engine::proof::Proof {
+        log_index: 1,
+        log_entry_data: rlp::encode(&log_entry).to_vec(),
+        receipt_index: 1,
+        receipt_data: Vec::new(),
+        header_data: Vec::new(),
+        proof: Vec::new(),
+    }
+}
+
 fn simple_transfer_args(
     dest_address: Address,
     transfer_amount: Wei,
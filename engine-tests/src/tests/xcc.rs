@@ -0,0 +1,191 @@
+use crate::prelude::{Address, WeiU256};
+use crate::test_utils::{self, solidity};
+use crate::tests::eth_connector::dummy_ft_receiver_bytes;
+use crate::tests::state_migration::{deploy_evm, AuroraAccount};
+use aurora_engine::parameters::{CallArgs, FunctionCallArgsV2, SubmitResult};
+use aurora_engine_precompiles::cross_contract;
+use aurora_engine_types::parameters::{
+    CrossContractCallArgs, CrossContractCallPolicy, DeployXccRouterArgs, EvmCallbackArgs,
+    PromiseCreateArgs,
+};
+use borsh::BorshSerialize;
+use serde_json::json;
+
+const RECEIVER_ACCOUNT: &str = "receiver.root";
+const TGAS: u64 = 1_000_000_000_000;
+
+#[test]
+fn test_xcc_promise_with_evm_callback() {
+    let aurora = deploy_evm();
+    let receiver = aurora.user.deploy(
+        &dummy_ft_receiver_bytes(),
+        RECEIVER_ACCOUNT.parse().unwrap(),
+        near_sdk_sim::STORAGE_AMOUNT,
+    );
+
+    // The owner allows calling the receiver and sets the router code
+    let policy = CrossContractCallPolicy {
+        max_attached_balance: 0,
+        max_attached_gas: 150 * TGAS,
+        allowed_targets: vec![RECEIVER_ACCOUNT.parse().unwrap()],
+    };
+    aurora
+        .call("set_xcc_policy", &policy.try_to_vec().unwrap())
+        .assert_success();
+    aurora
+        .call("set_xcc_router_code", &xcc_router_bytes())
+        .assert_success();
+
+    let constructor = solidity::ContractConstructor::compile_from_source(
+        "src/tests/res",
+        "target/solidity_build",
+        "xcc.sol",
+        "XccTester",
+    );
+    let deploy_data = constructor
+        .abi
+        .constructor()
+        .unwrap()
+        .encode_input(
+            constructor.code.clone(),
+            &[ethabi::Token::Address(cross_contract::ADDRESS)],
+        )
+        .unwrap();
+    let submit_result: SubmitResult = aurora.call("deploy_code", &deploy_data).unwrap_borsh();
+    let tester = constructor.deployed_at(Address::from_slice(&test_utils::unwrap_success(
+        submit_result,
+    )));
+    let router_account_id = cross_contract::router_account_id(
+        &tester.address,
+        &aurora.contract.account_id.as_str().parse().unwrap(),
+    )
+    .unwrap();
+
+    // Anyone may pay for the router of an address, but only once
+    let deploy_args = DeployXccRouterArgs {
+        address: tester.address.0,
+    };
+    let result = aurora.user.call(
+        aurora.contract.account_id(),
+        "deploy_xcc_router",
+        &deploy_args.try_to_vec().unwrap(),
+        near_sdk_sim::DEFAULT_GAS,
+        near_sdk_sim::STORAGE_AMOUNT,
+    );
+    result.assert_success();
+    assert!(result.promise_errors().is_empty());
+    let result = aurora.user.call(
+        aurora.contract.account_id(),
+        "deploy_xcc_router",
+        &deploy_args.try_to_vec().unwrap(),
+        near_sdk_sim::DEFAULT_GAS,
+        near_sdk_sim::STORAGE_AMOUNT,
+    );
+    assert!(!result.is_ok());
+
+    let args = CrossContractCallArgs {
+        promise: PromiseCreateArgs {
+            target_account_id: RECEIVER_ACCOUNT.parse().unwrap(),
+            method: "ft_on_transfer".into(),
+            args: json!({ "sender_id": "alice.root", "amount": "1", "msg": "" })
+                .to_string()
+                .into_bytes(),
+            attached_balance: 0,
+            attached_gas: 10 * TGAS,
+        },
+        callback: Some(EvmCallbackArgs {
+            selector: selector(&tester, "onResult"),
+            gas_limit: 1_000_000,
+            attached_gas: 100 * TGAS,
+        }),
+    };
+    let input = tester
+        .abi
+        .function("crossContractCall")
+        .unwrap()
+        .encode_input(&[ethabi::Token::Bytes(args.try_to_vec().unwrap())])
+        .unwrap();
+    let result = evm_call(&aurora, &tester, input);
+    result.assert_success();
+    let submit_result: SubmitResult = result.unwrap_borsh();
+    test_utils::unwrap_success(submit_result);
+    assert!(result.promise_errors().is_empty());
+
+    // The receiver is called by the router, not the engine, and its result
+    // is delivered to the EVM contract by `evm_callback`
+    let executors: Vec<String> = result
+        .promise_results()
+        .iter()
+        .flatten()
+        .map(|outcome| outcome.outcome().executor_id.to_string())
+        .collect();
+    assert!(executors.contains(&router_account_id.to_string()));
+    assert!(executors.contains(&receiver.account_id.to_string()));
+
+    assert_eq!(
+        view(&aurora, &tester, "callbackCount"),
+        ethabi::Token::Uint(1.into())
+    );
+    assert_eq!(
+        view(&aurora, &tester, "lastSuccess"),
+        ethabi::Token::Bool(true)
+    );
+    assert_eq!(
+        view(&aurora, &tester, "lastResult"),
+        ethabi::Token::Bytes(b"\"0\"".to_vec())
+    );
+
+    // Targets which are not allowed by the policy are rejected in the EVM
+    let args = CrossContractCallArgs {
+        promise: PromiseCreateArgs {
+            target_account_id: "other.root".parse().unwrap(),
+            ..args.promise
+        },
+        callback: None,
+    };
+    let input = tester
+        .abi
+        .function("crossContractCall")
+        .unwrap()
+        .encode_input(&[ethabi::Token::Bytes(args.try_to_vec().unwrap())])
+        .unwrap();
+    let submit_result: SubmitResult = evm_call(&aurora, &tester, input).unwrap_borsh();
+    assert!(!submit_result.status.is_ok());
+}
+
+fn evm_call(
+    aurora: &AuroraAccount,
+    contract: &solidity::DeployedContract,
+    input: Vec<u8>,
+) -> near_sdk_sim::ExecutionResult {
+    let call_args = CallArgs::V2(FunctionCallArgsV2 {
+        contract: contract.address.0,
+        value: WeiU256::default(),
+        input,
+    });
+    aurora.call("call", &call_args.try_to_vec().unwrap())
+}
+
+fn view(
+    aurora: &AuroraAccount,
+    contract: &solidity::DeployedContract,
+    method: &str,
+) -> ethabi::Token {
+    let function = contract.abi.function(method).unwrap();
+    let input = function.encode_input(&[]).unwrap();
+    let submit_result: SubmitResult = evm_call(aurora, contract, input).unwrap_borsh();
+    let output = test_utils::unwrap_success(submit_result);
+    function.decode_output(&output).unwrap().pop().unwrap()
+}
+
+fn selector(contract: &solidity::DeployedContract, method: &str) -> [u8; 4] {
+    contract.abi.function(method).unwrap().short_signature()
+}
+
+/// Bytes of the router contract deployed for the EVM addresses using the precompile
+fn xcc_router_bytes() -> Vec<u8> {
+    let base_path = std::path::Path::new("../etc").join("xcc-router");
+    let output_path = base_path.join("target/wasm32-unknown-unknown/release/xcc_router.wasm");
+    test_utils::rust::compile(base_path);
+    std::fs::read(output_path).unwrap()
+}
@@ -0,0 +1,41 @@
+use crate::String;
+use core::fmt::Write;
+
+/// Escapes a string embedded in JSON arguments to avoid json injection attacks.
+/// Quotes, backslashes and all control characters are escaped, as JSON strings
+/// cannot contain them unescaped.
+pub fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{08}' => escaped.push_str("\\b"),
+            '\u{0c}' => escaped.push_str("\\f"),
+            c if c < '\u{20}' => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("plain text"), "plain text");
+        assert_eq!(escape_json(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_json("a\nb\rc\td"), r#"a\nb\rc\td"#);
+        assert_eq!(escape_json("\u{08}\u{0c}"), r#"\b\f"#);
+        assert_eq!(escape_json("\u{0}\u{1f}"), r#"\u0000\u001f"#);
+        assert_eq!(escape_json("\u{7f}ü"), "\u{7f}ü");
+        assert_eq!(escape_json(r#"", "amount": "1"#), r#"\", \"amount\": \"1"#);
+    }
+}
@@ -41,6 +41,7 @@ pub enum PromiseAction {
         attached_yocto: u128,
         gas: u64,
     },
+    CreateAccount,
 }
 
 #[must_use]
@@ -64,3 +65,87 @@ pub struct RefundCallArgs {
     pub erc20_address: Option<EthAddress>,
     pub amount: RawU256,
 }
+
+/// Borsh-encoded parameters for the `refund_nft_on_error` function, the callback of the
+/// `nft_transfer` promise created by the exit NFT to NEAR precompile.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub struct RefundNftCallArgs {
+    pub nep171: AccountId,
+    pub token_id: String,
+    pub token_uri: String,
+    /// Owner of the burned ERC-721 token, to which it is minted again.
+    pub recipient_address: EthAddress,
+}
+
+/// Borsh-encoded input of the cross-contract call precompile.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct CrossContractCallArgs {
+    /// NEAR function call to schedule.
+    pub promise: PromiseCreateArgs,
+    /// Method of the calling EVM contract which receives the promise result, if any.
+    pub callback: Option<EvmCallbackArgs>,
+}
+
+/// Describes the EVM method a NEAR promise result is delivered to.
+/// The method must accept `(bool success, bytes result)`.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
+pub struct EvmCallbackArgs {
+    pub selector: [u8; 4],
+    /// EVM gas limit for executing the callback method.
+    pub gas_limit: u64,
+    /// NEAR gas attached to the callback receipt.
+    pub attached_gas: u64,
+}
+
+/// Borsh-encoded parameters for the `evm_callback` function.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
+pub struct EvmCallbackCallArgs {
+    pub contract: EthAddress,
+    pub selector: [u8; 4],
+    pub gas_limit: u64,
+}
+
+/// How a precompile disabled by the owner behaves when it is called.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisabledPrecompileMode {
+    /// The address behaves like an account without code: calls succeed with empty output.
+    EmptyAccount,
+    /// Calls revert with empty output.
+    Revert,
+}
+
+/// Borsh-encoded parameters for the `set_precompile_status` function.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub struct SetPrecompileStatusArgs {
+    pub address: RawAddress,
+    /// `None` enables the precompile again.
+    pub disabled: Option<DisabledPrecompileMode>,
+}
+
+/// Owner-managed limits on the promises the cross-contract call precompile may create.
+/// The default policy does not allow attaching any NEAR gas, which disables the precompile.
+#[derive(Debug, Default, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub struct CrossContractCallPolicy {
+    /// Maximum amount of yoctoNEAR attached to a single call.
+    pub max_attached_balance: Balance,
+    /// Maximum NEAR gas attached to a call and its callback combined.
+    pub max_attached_gas: u64,
+    /// Accounts which may be called. An empty list denies every account.
+    pub allowed_targets: Vec<AccountId>,
+}
+
+/// Borsh-encoded parameters for the `deploy_xcc_router` function.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub struct DeployXccRouterArgs {
+    /// EVM address whose cross-contract calls are executed by the router.
+    pub address: EthAddress,
+}
+
+/// Borsh-encoded parameters for the `xcc_router_deployed` callback.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub struct XccRouterDeployedArgs {
+    pub address: EthAddress,
+    /// Account refunded with `amount` if the router could not be created.
+    pub payer: AccountId,
+    pub amount: Balance,
+}
@@ -20,6 +20,8 @@ pub enum KeyPrefix {
     Generation = 0x7,
     Nep141Erc20Map = 0x8,
     Erc20Nep141Map = 0x9,
+    Nep171Erc721Map = 0xa,
+    Erc721Nep171Map = 0xb,
 }
 
 /// Enum used to differentiate different storage keys used by eth-connector
@@ -31,6 +33,12 @@ pub enum EthConnectorStorageId {
     PausedMask = 0x3,
     StatisticsAuroraAccountsCounter = 0x4,
     FungibleTokenMetadata = 0x5,
+    BridgeFees = 0x6,
+    Roles = 0x7,
+    UsedProof = 0x8,
+    UsedProofRegistry = 0x9,
+    Erc20Locker = 0xa,
+    BridgedErc20 = 0xb,
 }
 
 /// We can't use const generic over Enum, but we can do it over integral type
@@ -50,6 +58,8 @@ impl From<KeyPrefixU8> for KeyPrefix {
             0x7 => Self::Generation,
             0x8 => Self::Nep141Erc20Map,
             0x9 => Self::Erc20Nep141Map,
+            0xa => Self::Nep171Erc721Map,
+            0xb => Self::Erc721Nep171Map,
             _ => unreachable!(),
         }
     }
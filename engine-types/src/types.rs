@@ -20,6 +20,14 @@ pub type WeiU256 = [u8; 32];
 /// Near gas type which wraps an underlying u64.
 pub struct NearGas(u64);
 
+impl Add<NearGas> for NearGas {
+    type Output = NearGas;
+
+    fn add(self, rhs: NearGas) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
 impl Sub<NearGas> for NearGas {
     type Output = NearGas;
 
@@ -160,6 +168,15 @@ impl From<u128> for Fee {
 #[allow(dead_code)]
 pub const ERC20_MINT_SELECTOR: &[u8] = &[64, 193, 15, 25];
 
+/// Selector of `burn(address,uint256)` on the bridged ERC-20 contract.
+pub const ERC20_BURN_SELECTOR: &[u8] = &[157, 194, 159, 172];
+
+/// Selector of `setMetadata(string,string,uint8)` on the bridged ERC-20 contract.
+pub const ERC20_SET_METADATA_SELECTOR: &[u8] = &[55, 210, 194, 244];
+
+/// Selector of `mint(address,string,string)` on the bridged ERC-721 contract.
+pub const ERC721_MINT_SELECTOR: &[u8] = &[153, 7, 17, 144];
+
 #[derive(Debug)]
 pub enum AddressValidationError {
     FailedDecodeHex,
@@ -288,6 +305,20 @@ pub struct InternalMetaCallArgs {
     pub input: Vec<u8>,
 }
 
+/// Second version of meta-call arguments, with a deadline and a signed gas limit.
+pub struct InternalMetaCallArgsV2 {
+    pub sender: Address,
+    pub nonce: U256,
+    /// NEAR block timestamp in seconds after which the meta-call is rejected
+    pub deadline: u64,
+    pub fee_amount: Wei,
+    pub fee_address: Address,
+    pub contract_address: Address,
+    pub value: Wei,
+    pub gas_limit: u64,
+    pub input: Vec<u8>,
+}
+
 pub struct StorageBalanceBounds {
     pub min: Balance,
     pub max: Option<Balance>,
@@ -1,20 +1,27 @@
 use crate::admin_controlled::{AdminControlled, PausedMask};
-use crate::deposit_event::{DepositedEvent, FtTransferMessageData, TokenMessageData};
-use crate::engine::Engine;
+use crate::deposit_event::{
+    DepositedEvent, Erc20LockedEvent, FtTransferMessageData, TokenMessageData,
+};
+use crate::engine::{nep141_erc20_map, ERC20Address, Engine};
 use crate::fungible_token::{self, FungibleToken, FungibleTokenMetadata, FungibleTokenOps};
 use crate::parameters::{
-    BalanceOfCallArgs, BalanceOfEthCallArgs, FinishDepositCallArgs, InitCallArgs,
-    NEP141FtOnTransferArgs, PauseEthConnectorCallArgs, ResolveTransferCallArgs,
-    SetContractDataCallArgs, StorageBalanceOfCallArgs, StorageDepositCallArgs,
-    StorageWithdrawCallArgs, TransferCallArgs, TransferCallCallArgs, WithdrawResult,
+    BalanceOfCallArgs, BalanceOfEthCallArgs, BatchedDeposit, BridgeFees, ConnectorRole,
+    ConnectorRoleCallArgs, DepositBatchCallArgs, DepositBatchStatus, FinishDepositBatchCallArgs,
+    FinishDepositCallArgs, FinishDepositErc20CallArgs, InitCallArgs, MigrateUsedProofsCallArgs,
+    NEP141FtOnTransferArgs, PauseEthConnectorCallArgs, RegisterBridgedErc20CallArgs,
+    ResolveTransferCallArgs, SetBridgeFeesCallArgs, SetContractDataCallArgs,
+    SetErc20LockerCallArgs, SetProofWatermarkCallArgs, StorageBalanceOfCallArgs,
+    StorageDepositCallArgs, StorageWithdrawCallArgs, TransferCallArgs, TransferCallCallArgs,
+    UsedProofsStorage, WithdrawResult,
 };
 use crate::prelude::{
     format, sdk, str, validate_eth_address, AccountId, Address, Balance, BorshDeserialize,
-    BorshSerialize, EthAddress, EthConnectorStorageId, KeyPrefix, NearGas, PromiseResult, ToString,
-    Vec, WithdrawCallArgs, ERR_FAILED_PARSE, H160,
+    BorshSerialize, EthAddress, EthConnectorStorageId, KeyPrefix, NearGas, PromiseResult, String,
+    ToString, TryFrom, Vec, WithdrawCallArgs, ERR_FAILED_PARSE, H160,
 };
 use crate::prelude::{
-    AddressValidationError, PromiseBatchAction, PromiseCreateArgs, PromiseWithCallbackArgs,
+    u256_to_arr, AddressValidationError, PromiseBatchAction, PromiseCreateArgs,
+    PromiseWithCallbackArgs, RawAddress,
 };
 use crate::proof::Proof;
 use aurora_engine_sdk::env::Env;
@@ -28,6 +35,11 @@ pub const GAS_FOR_FINISH_DEPOSIT: NearGas = NearGas::new(50_000_000_000_000);
 /// NEAR Gas for calling `verify_log_entry` promise. Used in the `deposit` logic.
 // Note: Is 40Tgas always enough?
 const GAS_FOR_VERIFY_LOG_ENTRY: NearGas = NearGas::new(40_000_000_000_000);
+/// NEAR Gas used by `deposit_batch` itself, before scheduling the verification calls.
+const GAS_FOR_DEPOSIT_BATCH: NearGas = NearGas::new(10_000_000_000_000);
+
+/// Bytes NEAR charges for each storage record on top of its key and value.
+const STORAGE_BYTES_PER_RECORD: u64 = 40;
 
 /// Admin control flow flag indicates that all control flow unpause (unblocked).
 pub const UNPAUSE_ALL: PausedMask = 0;
@@ -49,6 +61,15 @@ pub struct EthConnectorContract<I: IO> {
     io: I,
 }
 
+/// State of the registry of used proofs
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+struct UsedProofRegistry {
+    /// Proofs of blocks below it are rejected
+    min_block_number: Option<u64>,
+    /// Number of used proofs in the registry
+    entries: u64,
+}
+
 /// Connector specific data. It always should contain `prover account` -
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct EthConnector {
@@ -135,9 +156,98 @@ impl<I: IO + Copy> EthConnectorContract<I> {
 
         sdk::log!("[Deposit tokens]");
 
+        let (verify_call, data) =
+            self.prepare_deposit(raw_proof, &current_account_id, predecessor_account_id)?;
+        let finish_call = PromiseCreateArgs {
+            target_account_id: current_account_id,
+            method: "finish_deposit".to_string(),
+            args: data.try_to_vec().unwrap(),
+            attached_balance: ZERO_ATTACHED_BALANCE,
+            attached_gas: GAS_FOR_FINISH_DEPOSIT.into_u64(),
+        };
+        Ok(PromiseWithCallbackArgs {
+            base: verify_call,
+            callback: finish_call,
+        })
+    }
+
+    /// Deposit several proofs at once. Their `verify_log_entry` calls are joined into the
+    /// `finish_deposit_batch` callback, which finishes every verified deposit. Proofs
+    /// rejected before verification are only reported by the callback.
+    ///
+    /// The batch fails with `ERR_NOT_ENOUGH_GAS_FOR_DEPOSIT_BATCH` unless the prepaid gas
+    /// covers every verification call and the callback, which needs `GAS_FOR_FINISH_DEPOSIT`
+    /// per verified proof. This bounds the size of a batch.
+    pub fn deposit_batch(
+        &self,
+        args: DepositBatchCallArgs,
+        current_account_id: AccountId,
+        predecessor_account_id: AccountId,
+        prepaid_gas: NearGas,
+    ) -> Result<(Vec<PromiseCreateArgs>, PromiseCreateArgs), error::DepositError> {
+        // Check is current account owner
+        let is_owner = current_account_id == predecessor_account_id;
+        // Check is current flow paused. If it's owner account just skip it.
+        self.assert_not_paused(PAUSE_DEPOSIT, is_owner)
+            .map_err(|_| error::DepositError::Paused)?;
+
+        sdk::log!(&format!("[Deposit batch of {} proofs]", args.proofs.len()));
+
+        let mut verify_calls = Vec::with_capacity(args.proofs.len());
+        let mut deposits = Vec::with_capacity(args.proofs.len());
+        for proof in args.proofs {
+            let raw_proof = proof.try_to_vec().unwrap();
+            match self.prepare_deposit(
+                raw_proof,
+                &current_account_id,
+                predecessor_account_id.clone(),
+            ) {
+                Ok((verify_call, data)) => {
+                    verify_calls.push(verify_call);
+                    deposits.push(BatchedDeposit::Verifying(data));
+                }
+                Err(e) => deposits.push(BatchedDeposit::Rejected(
+                    String::from_utf8_lossy(e.as_ref()).to_string(),
+                )),
+            }
+        }
+
+        let finish_gas = GAS_FOR_FINISH_DEPOSIT
+            .into_u64()
+            .saturating_mul(verify_calls.len().max(1) as u64);
+        let required_gas = GAS_FOR_VERIFY_LOG_ENTRY
+            .into_u64()
+            .saturating_mul(verify_calls.len() as u64)
+            .saturating_add(finish_gas)
+            .saturating_add(GAS_FOR_DEPOSIT_BATCH.into_u64());
+        if prepaid_gas.into_u64() < required_gas {
+            return Err(error::DepositError::InsufficientGasForBatch);
+        }
+
+        let finish_call = PromiseCreateArgs {
+            target_account_id: current_account_id,
+            method: "finish_deposit_batch".to_string(),
+            args: FinishDepositBatchCallArgs { deposits }
+                .try_to_vec()
+                .unwrap(),
+            attached_balance: ZERO_ATTACHED_BALANCE,
+            attached_gas: finish_gas,
+        };
+        Ok((verify_calls, finish_call))
+    }
+
+    /// Checks the proof of a deposit and returns the call verifying it, as well as the
+    /// arguments to finish the deposit with once it is verified.
+    fn prepare_deposit(
+        &self,
+        raw_proof: Vec<u8>,
+        current_account_id: &AccountId,
+        predecessor_account_id: AccountId,
+    ) -> Result<(PromiseCreateArgs, FinishDepositCallArgs), error::DepositError> {
         // Get incoming deposit arguments
         let proof: Proof =
             Proof::try_from_slice(&raw_proof).map_err(|_| error::DepositError::ProofParseFailed)?;
+        self.check_proof_watermark(&proof)?;
         // Fetch event data from Proof
         let event = DepositedEvent::from_log_entry_data(&proof.log_entry_data)
             .map_err(error::DepositError::EventParseFailed)?;
@@ -163,25 +273,12 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         if event.fee.into_u128() >= event.amount {
             return Err(error::DepositError::InsufficientAmountForFee);
         }
+        let bridge_fee = self.get_bridge_fees().deposit_fee(event.amount);
+        if event.fee.into_u128().saturating_add(bridge_fee) >= event.amount {
+            return Err(error::DepositError::InsufficientAmountForFee);
+        }
 
-        // Verify proof data with cross-contract call to prover account
-        sdk::log!(&format!(
-            "Deposit verify_log_entry for prover: {}",
-            self.contract.prover_account,
-        ));
-
-        // Do not skip bridge call. This is only used for development and diagnostics.
-        let skip_bridge_call = false.try_to_vec().unwrap();
-        let mut proof_to_verify = raw_proof;
-        proof_to_verify.extend(skip_bridge_call);
-
-        let verify_call = PromiseCreateArgs {
-            target_account_id: self.contract.prover_account.clone(),
-            method: "verify_log_entry".to_string(),
-            args: proof_to_verify,
-            attached_balance: ZERO_ATTACHED_BALANCE,
-            attached_gas: GAS_FOR_VERIFY_LOG_ENTRY.into_u64(),
-        };
+        let verify_call = self.verify_log_entry_call(raw_proof);
 
         // Finalize deposit
         let data = match event.token_message_data {
@@ -193,9 +290,7 @@ impl<I: IO + Copy> EthConnectorContract<I> {
                 relayer_id: predecessor_account_id,
                 fee: event.fee,
                 msg: None,
-            }
-            .try_to_vec()
-            .unwrap(),
+            },
             // Deposit to Eth accounts
             // fee is being minted in the `ft_on_transfer` callback method
             TokenMessageData::Eth {
@@ -222,24 +317,155 @@ impl<I: IO + Copy> EthConnectorContract<I> {
                     fee: event.fee,
                     msg: Some(transfer_data),
                 }
-                .try_to_vec()
-                .unwrap()
             }
         };
 
-        let finish_call = PromiseCreateArgs {
-            target_account_id: current_account_id,
-            method: "finish_deposit".to_string(),
-            args: data,
+        Ok((verify_call, data))
+    }
+
+    /// Rejects the proofs of blocks below the watermark of the used proofs registry
+    fn check_proof_watermark(&self, proof: &Proof) -> Result<(), error::DepositError> {
+        if let Some(min_block_number) = self.get_used_proof_registry().min_block_number {
+            match proof.block_number() {
+                Some(block_number) if block_number >= min_block_number => (),
+                _ => return Err(error::DepositError::ProofTooOld),
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify proof data with cross-contract call to prover account
+    fn verify_log_entry_call(&self, raw_proof: Vec<u8>) -> PromiseCreateArgs {
+        sdk::log!(&format!(
+            "Deposit verify_log_entry for prover: {}",
+            self.contract.prover_account,
+        ));
+
+        // Do not skip bridge call. This is only used for development and diagnostics.
+        let skip_bridge_call = false.try_to_vec().unwrap();
+        let mut proof_to_verify = raw_proof;
+        proof_to_verify.extend(skip_bridge_call);
+
+        PromiseCreateArgs {
+            target_account_id: self.contract.prover_account.clone(),
+            method: "verify_log_entry".to_string(),
+            args: proof_to_verify,
             attached_balance: ZERO_ATTACHED_BALANCE,
-            attached_gas: GAS_FOR_FINISH_DEPOSIT.into_u64(),
+            attached_gas: GAS_FOR_VERIFY_LOG_ENTRY.into_u64(),
+        }
+    }
+
+    /// Deposit ERC-20 tokens locked in the ERC-20 locker on Ethereum. Once the proof of
+    /// the `Locked` event is verified, `finish_deposit_erc20` mints the registered Aurora
+    /// ERC-20 to the recipient. Proofs share the replay protection of ETH deposits.
+    pub fn deposit_erc20(
+        &self,
+        raw_proof: Vec<u8>,
+        current_account_id: AccountId,
+        predecessor_account_id: AccountId,
+    ) -> Result<PromiseWithCallbackArgs, error::DepositError> {
+        // Check is current account owner
+        let is_owner = current_account_id == predecessor_account_id;
+        // Check is current flow paused. If it's owner account just skip it.
+        self.assert_not_paused(PAUSE_DEPOSIT, is_owner)
+            .map_err(|_| error::DepositError::Paused)?;
+
+        sdk::log!("[Deposit ERC-20 tokens]");
+
+        let proof: Proof =
+            Proof::try_from_slice(&raw_proof).map_err(|_| error::DepositError::ProofParseFailed)?;
+        self.check_proof_watermark(&proof)?;
+        let event = Erc20LockedEvent::from_log_entry_data(&proof.log_entry_data)
+            .map_err(error::DepositError::EventParseFailed)?;
+
+        sdk::log!(&format!(
+            "Deposit started: token {} from {} to recipient {} with amount: {}",
+            hex::encode(event.token),
+            hex::encode(event.sender),
+            hex::encode(event.recipient),
+            event.amount,
+        ));
+
+        if self.get_erc20_locker() != Some(event.locker_address) {
+            return Err(error::DepositError::LockerAddressMismatch);
+        }
+        let erc20 = self
+            .get_bridged_erc20(&event.token)
+            .ok_or(error::DepositError::Erc20NotRegistered)?;
+
+        let data = FinishDepositErc20CallArgs {
+            erc20,
+            recipient: event.recipient,
+            amount: u256_to_arr(&event.amount),
+            proof_key: proof.get_key(),
         };
         Ok(PromiseWithCallbackArgs {
-            base: verify_call,
-            callback: finish_call,
+            base: self.verify_log_entry_call(raw_proof),
+            callback: PromiseCreateArgs {
+                target_account_id: current_account_id,
+                method: "finish_deposit_erc20".to_string(),
+                args: data.try_to_vec().unwrap(),
+                attached_balance: ZERO_ATTACHED_BALANCE,
+                attached_gas: GAS_FOR_FINISH_DEPOSIT.into_u64(),
+            },
         })
     }
 
+    /// Finish ERC-20 deposit (private method). Only records the proof: the tokens are
+    /// minted by the engine afterwards, in the same receipt.
+    pub fn finish_deposit_erc20(
+        &mut self,
+        data: &FinishDepositErc20CallArgs,
+    ) -> Result<(), error::ProofUsed> {
+        self.record_proof(&data.proof_key)
+    }
+
+    /// Address of the ERC-20 locker on Ethereum, if ERC-20 deposits are set up
+    pub fn get_erc20_locker(&self) -> Option<EthAddress> {
+        self.io
+            .read_storage(&construct_contract_key(&EthConnectorStorageId::Erc20Locker))
+            .and_then(|data| data.to_value().ok())
+    }
+
+    pub fn set_erc20_locker(&mut self, args: SetErc20LockerCallArgs) {
+        self.io.write_borsh(
+            &construct_contract_key(&EthConnectorStorageId::Erc20Locker),
+            &args.locker_address,
+        );
+    }
+
+    fn bridged_erc20_key(eth_token: &EthAddress) -> Vec<u8> {
+        let mut key = construct_contract_key(&EthConnectorStorageId::BridgedErc20);
+        key.extend_from_slice(eth_token);
+        key
+    }
+
+    /// Aurora ERC-20 minted for deposits of the Ethereum token
+    pub fn get_bridged_erc20(&self, eth_token: &EthAddress) -> Option<RawAddress> {
+        self.io
+            .read_storage(&Self::bridged_erc20_key(eth_token))
+            .and_then(|data| data.to_value().ok())
+    }
+
+    /// Registers the Aurora ERC-20 minted for deposits of the Ethereum token. ERC-20s
+    /// bridged from NEP-141 tokens are rejected, since their supply must stay backed by
+    /// the NEP-141 tokens held by the engine.
+    pub fn register_bridged_erc20(
+        &mut self,
+        args: RegisterBridgedErc20CallArgs,
+    ) -> Result<(), error::RegisterBridgedErc20Error> {
+        let is_bridged_from_nep141 = ERC20Address::try_from(args.erc20.to_vec())
+            .ok()
+            .and_then(|erc20| nep141_erc20_map(self.io).lookup_right(&erc20))
+            .is_some();
+        if is_bridged_from_nep141 {
+            return Err(error::RegisterBridgedErc20Error::BridgedFromNep141);
+        }
+        self.io
+            .write_borsh(&Self::bridged_erc20_key(&args.eth_token), &args.erc20);
+        Ok(())
+    }
+
     /// Finish deposit (private method)
     /// NOTE: we should `record_proof` only after `mint` operation. The reason
     /// is that in this case we only calculate the amount to be credited but
@@ -254,15 +480,18 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     ) -> Result<Option<PromiseWithCallbackArgs>, error::FinishDepositError> {
         sdk::log!(&format!("Finish deposit with the amount: {}", data.amount));
 
+        let bridge_fee = self.deposit_bridge_fee(&data);
         // Mint tokens to recipient minus fee
         if let Some(msg) = data.msg {
             // Mint - calculate new balances
-            self.mint_eth_on_near(data.new_owner_id, data.amount)?;
+            self.mint_eth_on_near(data.new_owner_id, data.amount - bridge_fee)?;
+            self.collect_bridge_fee(bridge_fee)?;
             // Store proof only after `mint` calculations
             self.record_proof(&data.proof_key)?;
             // Save new contract data
             self.save_ft_contract();
-            let transfer_call_args = TransferCallCallArgs::try_from_slice(&msg).unwrap();
+            let mut transfer_call_args = TransferCallCallArgs::try_from_slice(&msg).unwrap();
+            transfer_call_args.amount -= bridge_fee;
             let promise = self.ft_transfer_call(
                 predecessor_account_id,
                 current_account_id,
@@ -274,9 +503,10 @@ impl<I: IO + Copy> EthConnectorContract<I> {
             // Mint - calculate new balances
             self.mint_eth_on_near(
                 data.new_owner_id.clone(),
-                data.amount - data.fee.into_u128(),
+                data.amount - data.fee.into_u128() - bridge_fee,
             )?;
             self.mint_eth_on_near(data.relayer_id, data.fee.into_u128())?;
+            self.collect_bridge_fee(bridge_fee)?;
             // Store proof only after `mint` calculations
             self.record_proof(&data.proof_key)?;
             // Save new contract data
@@ -285,6 +515,121 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         }
     }
 
+    /// Finish the deposits of a batch, given whether each of the verified proofs is valid.
+    /// Deposits are finished independently: one which cannot be finished is reported
+    /// without changing any state, so the other deposits of the batch still go through.
+    pub fn finish_deposit_batch(
+        &mut self,
+        predecessor_account_id: AccountId,
+        current_account_id: AccountId,
+        args: FinishDepositBatchCallArgs,
+        verified: Vec<bool>,
+    ) -> Result<(Vec<DepositBatchStatus>, Vec<PromiseWithCallbackArgs>), error::FinishDepositError>
+    {
+        let mut verified = verified.into_iter();
+        let mut statuses = Vec::with_capacity(args.deposits.len());
+        let mut promises = Vec::new();
+        for deposit in args.deposits {
+            let data = match deposit {
+                BatchedDeposit::Verifying(data) => data,
+                BatchedDeposit::Rejected(e) => {
+                    statuses.push(DepositBatchStatus::Failed(e));
+                    continue;
+                }
+            };
+            if !verified.next().unwrap_or(false) {
+                statuses.push(DepositBatchStatus::Failed("ERR_VERIFY_PROOF".to_string()));
+                continue;
+            }
+            if let Err(e) = self.check_finish_deposit(&data, &current_account_id) {
+                statuses.push(DepositBatchStatus::Failed(
+                    String::from_utf8_lossy(e.as_ref()).to_string(),
+                ));
+                continue;
+            }
+
+            // Cannot fail after the checks above.
+            let promise = self.finish_deposit(
+                predecessor_account_id.clone(),
+                current_account_id.clone(),
+                data,
+                GAS_FOR_FINISH_DEPOSIT,
+            )?;
+            promises.extend(promise);
+            statuses.push(DepositBatchStatus::Finished);
+        }
+        Ok((statuses, promises))
+    }
+
+    /// Checks everything which could make `finish_deposit` fail, without changing any state.
+    fn check_finish_deposit(
+        &self,
+        data: &FinishDepositCallArgs,
+        current_account_id: &AccountId,
+    ) -> Result<(), error::FinishDepositError> {
+        if self.is_used_event(&data.proof_key) {
+            return Err(error::FinishDepositError::ProofUsed);
+        }
+        // Balances are bounded by the total supply so cannot overflow if it does not.
+        if self
+            .ft
+            .total_eth_supply_on_near
+            .checked_add(data.amount)
+            .is_none()
+        {
+            return Err(fungible_token::error::DepositError::TotalSupplyOverflow.into());
+        }
+        if let Some(msg) = &data.msg {
+            let mut transfer_call_args = TransferCallCallArgs::try_from_slice(msg).unwrap();
+            transfer_call_args.amount -= self.deposit_bridge_fee(data);
+            self.check_transfer_call(&transfer_call_args, current_account_id)?;
+        }
+        Ok(())
+    }
+
+    /// Bridge fee of a deposit, which leaves at least the relayer fee to mint.
+    fn deposit_bridge_fee(&self, data: &FinishDepositCallArgs) -> Balance {
+        self.get_bridge_fees()
+            .deposit_fee(data.amount)
+            .min(data.amount.saturating_sub(data.fee.into_u128()))
+    }
+
+    /// Mint the bridge fee to the fee collector
+    fn collect_bridge_fee(
+        &mut self,
+        bridge_fee: Balance,
+    ) -> Result<(), fungible_token::error::DepositError> {
+        match self.get_bridge_fees().fee_collector {
+            Some(fee_collector) if bridge_fee > 0 => {
+                self.mint_eth_on_near(fee_collector, bridge_fee)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the bridge fees set for the eth-connector
+    pub fn get_bridge_fees(&self) -> BridgeFees {
+        self.io
+            .read_storage(&construct_contract_key(&EthConnectorStorageId::BridgeFees))
+            .and_then(|data| data.to_value().ok())
+            .unwrap_or_default()
+    }
+
+    /// Set the bridge fees of the eth-connector
+    pub fn set_bridge_fees(
+        &mut self,
+        args: SetBridgeFeesCallArgs,
+    ) -> Result<(), error::SetBridgeFeesError> {
+        if !args.is_valid() {
+            return Err(error::SetBridgeFeesError::InvalidFeeSchedule);
+        }
+        self.io.write_borsh(
+            &construct_contract_key(&EthConnectorStorageId::BridgeFees),
+            &args,
+        );
+        Ok(())
+    }
+
     /// Internal ETH withdraw ETH logic
     pub(crate) fn internal_remove_eth(
         &mut self,
@@ -364,15 +709,21 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         self.assert_not_paused(PAUSE_WITHDRAW, is_owner)
             .map_err(|_| error::WithdrawError::Paused)?;
 
+        let bridge_fee = self.get_bridge_fees().withdraw_fee(args.amount);
+        if bridge_fee > 0 && bridge_fee >= args.amount {
+            return Err(error::WithdrawError::InsufficientAmountForFee);
+        }
+
         // Burn tokens to recipient
         self.ft
             .internal_withdraw_eth_from_near(predecessor_account_id, args.amount)?;
+        self.collect_bridge_fee(bridge_fee)?;
         // Save new contract data
         self.save_ft_contract();
 
         Ok(WithdrawResult {
             recipient_id: args.recipient_address,
-            amount: args.amount,
+            amount: args.amount - bridge_fee,
             eth_custodian_address: self.contract.eth_custodian_address,
         })
     }
@@ -423,26 +774,65 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         Ok(())
     }
 
-    /// Transfer between NEAR accounts
+    /// Transfer between NEAR accounts. The bridge fee is not charged on transfers made by
+    /// the connector itself (nor by the fee collector), the same as in `ft_transfer_call`.
     pub fn ft_transfer(
         &mut self,
         predecessor_account_id: &AccountId,
+        current_account_id: &AccountId,
         args: TransferCallArgs,
-    ) -> Result<(), fungible_token::error::TransferError> {
+    ) -> Result<(), error::FtTransferCallError> {
+        let mut amount = args.amount;
+        if let Some((fee_collector, bridge_fee)) =
+            self.ft_transfer_bridge_fee(predecessor_account_id, current_account_id, amount)?
+        {
+            self.ft.internal_transfer_eth_on_near(
+                predecessor_account_id,
+                &fee_collector,
+                bridge_fee,
+                &None,
+            )?;
+            amount -= bridge_fee;
+        }
         self.ft.internal_transfer_eth_on_near(
             predecessor_account_id,
             &args.receiver_id,
-            args.amount,
+            amount,
             &args.memo,
         )?;
         self.save_ft_contract();
         sdk::log!(&format!(
             "Transfer amount {} to {} success with memo: {:?}",
-            args.amount, args.receiver_id, args.memo
+            amount, args.receiver_id, args.memo
         ));
         Ok(())
     }
 
+    /// Return the fee collector and the bridge fee of a transfer, if it is charged.
+    /// Neither the fee collector nor the connector itself (e.g. when finishing deposits)
+    /// pay fees.
+    fn ft_transfer_bridge_fee(
+        &self,
+        sender_id: &AccountId,
+        current_account_id: &AccountId,
+        amount: Balance,
+    ) -> Result<Option<(AccountId, Balance)>, error::FtTransferCallError> {
+        if sender_id == current_account_id {
+            return Ok(None);
+        }
+        let bridge_fees = self.get_bridge_fees();
+        let bridge_fee = bridge_fees.ft_transfer_fee(amount);
+        match bridge_fees.fee_collector {
+            Some(fee_collector) if bridge_fee > 0 && &fee_collector != sender_id => {
+                if bridge_fee >= amount {
+                    return Err(error::FtTransferCallError::InsufficientAmountForFee);
+                }
+                Ok(Some((fee_collector, bridge_fee)))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// FT resolve transfer logic
     pub fn ft_resolve_transfer(
         &mut self,
@@ -481,9 +871,43 @@ impl<I: IO + Copy> EthConnectorContract<I> {
             args.receiver_id, args.amount,
         ));
 
-        // Verify message data before `ft_on_transfer` call to avoid verification panics
+        let bridge_fee =
+            self.ft_transfer_bridge_fee(&predecessor_account_id, &current_account_id, args.amount)?;
+        let mut args = args;
+        if let Some((_, bridge_fee)) = &bridge_fee {
+            args.amount -= bridge_fee;
+        }
+        self.check_transfer_call(&args, &current_account_id)?;
+        if let Some((fee_collector, bridge_fee)) = bridge_fee {
+            self.ft.internal_transfer_eth_on_near(
+                &predecessor_account_id,
+                &fee_collector,
+                bridge_fee,
+                &None,
+            )?;
+        }
+
+        self.ft
+            .ft_transfer_call(
+                predecessor_account_id,
+                args.receiver_id,
+                args.amount,
+                &args.memo,
+                args.msg,
+                current_account_id,
+                prepaid_gas,
+            )
+            .map_err(Into::into)
+    }
+
+    /// Verify message data before `ft_on_transfer` call to avoid verification panics
+    fn check_transfer_call(
+        &self,
+        args: &TransferCallCallArgs,
+        current_account_id: &AccountId,
+    ) -> Result<(), error::FtTransferCallError> {
         // It's allowed empty message if `receiver_id =! current_account_id`
-        if args.receiver_id == current_account_id {
+        if &args.receiver_id == current_account_id {
             let message_data = FtTransferMessageData::parse_on_transfer_message(&args.msg)
                 .map_err(error::FtTransferCallError::MessageParseFailed)?;
             // Check is transfer amount > fee
@@ -514,18 +938,7 @@ impl<I: IO + Copy> EthConnectorContract<I> {
                 ));
             }
         }
-
-        self.ft
-            .ft_transfer_call(
-                predecessor_account_id,
-                args.receiver_id,
-                args.amount,
-                &args.memo,
-                args.msg,
-                current_account_id,
-                prepaid_gas,
-            )
-            .map_err(Into::into)
+        Ok(())
     }
 
     /// FT storage deposit logic
@@ -629,8 +1042,15 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         );
     }
 
-    /// Generate key for used events from Proof
+    /// Generate fixed size key for used events from Proof
     fn used_event_key(&self, key: &str) -> Vec<u8> {
+        let mut v = construct_contract_key(&EthConnectorStorageId::UsedProof);
+        v.extend_from_slice(sdk::sha256(key.as_bytes()).as_bytes());
+        v
+    }
+
+    /// Key of a used event proof recorded before the compact registry
+    fn legacy_used_event_key(&self, key: &str) -> Vec<u8> {
         let mut v = construct_contract_key(&EthConnectorStorageId::UsedEvent).to_vec();
         v.extend_from_slice(key.as_bytes());
         v
@@ -639,11 +1059,70 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     /// Save already used event proof as hash key
     fn save_used_event(&mut self, key: &str) {
         self.io.write_borsh(&self.used_event_key(key), &0u8);
+        let mut registry = self.get_used_proof_registry();
+        registry.entries += 1;
+        self.save_used_proof_registry(&registry);
     }
 
     /// Check is event of proof already used
     fn is_used_event(&self, key: &str) -> bool {
         self.io.storage_has_key(&self.used_event_key(key))
+            || self.io.storage_has_key(&self.legacy_used_event_key(key))
+    }
+
+    /// Move used event proofs recorded before the compact registry to it.
+    /// Returns the number of proofs moved.
+    pub fn migrate_used_proofs(&mut self, args: MigrateUsedProofsCallArgs) -> u64 {
+        let mut migrated = 0;
+        for key in args.proof_keys {
+            if self
+                .io
+                .remove_storage(&self.legacy_used_event_key(&key))
+                .is_some()
+            {
+                if !self.io.storage_has_key(&self.used_event_key(&key)) {
+                    self.save_used_event(&key);
+                }
+                migrated += 1;
+            }
+        }
+        migrated
+    }
+
+    /// Reject proofs of blocks below the watermark
+    pub fn set_proof_watermark(&mut self, args: SetProofWatermarkCallArgs) {
+        let mut registry = self.get_used_proof_registry();
+        registry.min_block_number = args.min_block_number;
+        self.save_used_proof_registry(&registry);
+    }
+
+    /// Return the storage occupied by the registry of used proofs, not counting the
+    /// proofs which are still to be migrated. Each entry is charged for its key, its
+    /// value and the per-record overhead of NEAR storage.
+    pub fn get_used_proofs_storage(&self) -> UsedProofsStorage {
+        let entries = self.get_used_proof_registry().entries;
+        let entry_bytes = (self.used_event_key("").len() + 0u8.try_to_vec().unwrap().len()) as u64
+            + STORAGE_BYTES_PER_RECORD;
+        UsedProofsStorage {
+            entries,
+            bytes: entries * entry_bytes,
+        }
+    }
+
+    fn get_used_proof_registry(&self) -> UsedProofRegistry {
+        self.io
+            .read_storage(&construct_contract_key(
+                &EthConnectorStorageId::UsedProofRegistry,
+            ))
+            .and_then(|data| data.to_value().ok())
+            .unwrap_or_default()
+    }
+
+    fn save_used_proof_registry(&mut self, registry: &UsedProofRegistry) {
+        self.io.write_borsh(
+            &construct_contract_key(&EthConnectorStorageId::UsedProofRegistry),
+            registry,
+        );
     }
 
     /// Checks whether the provided proof was already used
@@ -660,6 +1139,77 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     pub fn set_paused_flags(&mut self, args: PauseEthConnectorCallArgs) {
         self.set_paused(args.paused_mask);
     }
+
+    /// Checks the account may change the paused flags to the new mask: pausing a flow
+    /// requires the pause manager role and unpausing one the unpause manager role.
+    pub fn check_paused_flags_roles(
+        &self,
+        paused_mask: PausedMask,
+        account_id: &AccountId,
+        current_account_id: &AccountId,
+    ) -> Result<(), error::AccessError> {
+        let current_mask = self.get_paused();
+        if paused_mask & !current_mask != 0 {
+            self.check_role(ConnectorRole::PauseManager, account_id, current_account_id)?;
+        }
+        if current_mask & !paused_mask != 0 {
+            self.check_role(
+                ConnectorRole::UnpauseManager,
+                account_id,
+                current_account_id,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Checks the account is the owner or was granted the role
+    pub fn check_role(
+        &self,
+        role: ConnectorRole,
+        account_id: &AccountId,
+        current_account_id: &AccountId,
+    ) -> Result<(), error::AccessError> {
+        if account_id == current_account_id || self.has_role(role, account_id) {
+            Ok(())
+        } else {
+            Err(error::AccessError)
+        }
+    }
+
+    pub fn has_role(&self, role: ConnectorRole, account_id: &AccountId) -> bool {
+        self.get_roles()
+            .iter()
+            .any(|(granted_role, grantee)| granted_role == &role && grantee == account_id)
+    }
+
+    /// Return all the roles granted
+    pub fn get_roles(&self) -> Vec<(ConnectorRole, AccountId)> {
+        self.io
+            .read_storage(&construct_contract_key(&EthConnectorStorageId::Roles))
+            .and_then(|data| data.to_value().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn grant_role(&mut self, args: ConnectorRoleCallArgs) {
+        if !self.has_role(args.role, &args.account_id) {
+            let mut roles = self.get_roles();
+            roles.push((args.role, args.account_id));
+            self.save_roles(&roles);
+        }
+    }
+
+    pub fn revoke_role(&mut self, args: ConnectorRoleCallArgs) {
+        let mut roles = self.get_roles();
+        roles.retain(|(role, account_id)| role != &args.role || account_id != &args.account_id);
+        self.save_roles(&roles);
+    }
+
+    fn save_roles(&mut self, roles: &[(ConnectorRole, AccountId)]) {
+        self.io.write_storage(
+            &construct_contract_key(&EthConnectorStorageId::Roles),
+            &roles.try_to_vec().unwrap(),
+        );
+    }
 }
 
 impl<I: IO + Copy> AdminControlled for EthConnectorContract<I> {
@@ -705,12 +1255,17 @@ pub fn set_contract_data<I: IO>(
         &contract_data,
     );
 
+    set_metadata(io, &args.metadata);
+
+    Ok(contract_data)
+}
+
+/// Sets the metadata of the nETH token
+pub fn set_metadata<I: IO>(io: &mut I, metadata: &FungibleTokenMetadata) {
     io.write_borsh(
         &construct_contract_key(&EthConnectorStorageId::FungibleTokenMetadata),
-        &args.metadata,
+        metadata,
     );
-
-    Ok(contract_data)
 }
 
 /// Return metdata
@@ -737,6 +1292,10 @@ pub mod error {
         CustodianAddressMismatch,
         InsufficientAmountForFee,
         InvalidAddress(AddressValidationError),
+        ProofTooOld,
+        LockerAddressMismatch,
+        Erc20NotRegistered,
+        InsufficientGasForBatch,
     }
 
     impl AsRef<[u8]> for DepositError {
@@ -748,6 +1307,24 @@ pub mod error {
                 Self::CustodianAddressMismatch => b"ERR_WRONG_EVENT_ADDRESS",
                 Self::InsufficientAmountForFee => super::ERR_NOT_ENOUGH_BALANCE_FOR_FEE.as_bytes(),
                 Self::InvalidAddress(e) => e.as_ref(),
+                Self::ProofTooOld => b"ERR_PROOF_TOO_OLD",
+                Self::LockerAddressMismatch => b"ERR_WRONG_EVENT_ADDRESS",
+                Self::Erc20NotRegistered => b"ERR_ERC20_NOT_REGISTERED",
+                Self::InsufficientGasForBatch => b"ERR_NOT_ENOUGH_GAS_FOR_DEPOSIT_BATCH",
+            }
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum RegisterBridgedErc20Error {
+        /// The ERC-20 is bridged from a NEP-141 token.
+        BridgedFromNep141,
+    }
+
+    impl AsRef<[u8]> for RegisterBridgedErc20Error {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::BridgedFromNep141 => b"ERR_ERC20_BRIDGED_FROM_NEP141",
             }
         }
     }
@@ -787,7 +1364,15 @@ pub mod error {
 
     pub enum WithdrawError {
         Paused,
+        InsufficientAmountForFee,
         FT(fungible_token::error::WithdrawError),
+        FeeCollection(fungible_token::error::DepositError),
+    }
+
+    impl From<fungible_token::error::DepositError> for WithdrawError {
+        fn from(e: fungible_token::error::DepositError) -> Self {
+            Self::FeeCollection(e)
+        }
     }
 
     impl From<fungible_token::error::WithdrawError> for WithdrawError {
@@ -800,7 +1385,9 @@ pub mod error {
         fn as_ref(&self) -> &[u8] {
             match self {
                 Self::Paused => crate::admin_controlled::ERR_PAUSED.as_bytes(),
+                Self::InsufficientAmountForFee => super::ERR_NOT_ENOUGH_BALANCE_FOR_FEE.as_bytes(),
                 Self::FT(e) => e.as_ref(),
+                Self::FeeCollection(e) => e.as_ref(),
             }
         }
     }
@@ -856,6 +1443,28 @@ pub mod error {
         }
     }
 
+    pub struct AccessError;
+
+    impl AsRef<[u8]> for AccessError {
+        fn as_ref(&self) -> &[u8] {
+            b"ERR_MISSING_ROLE"
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum SetBridgeFeesError {
+        InvalidFeeSchedule,
+    }
+
+    impl AsRef<[u8]> for SetBridgeFeesError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidFeeSchedule => b"ERR_INVALID_FEE_SCHEDULE",
+            }
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
     pub struct ProofUsed;
 
     impl AsRef<[u8]> for ProofUsed {
@@ -9,6 +9,7 @@ use byte_slice_cast::AsByteSlice;
 use ethabi::{Event, EventParam, Hash, Log, ParamType, RawLog};
 
 pub const DEPOSITED_EVENT: &str = "Deposited";
+pub const LOCKED_EVENT: &str = "Locked";
 
 pub type EventParams = Vec<EventParam>;
 
@@ -300,6 +301,83 @@ impl DepositedEvent {
     }
 }
 
+/// Data that was emitted by the Locked event of the ERC-20 locker.
+pub struct Erc20LockedEvent {
+    pub locker_address: EthAddress,
+    pub token: EthAddress,
+    pub sender: EthAddress,
+    pub amount: U256,
+    pub recipient: EthAddress,
+}
+
+impl Erc20LockedEvent {
+    #[allow(dead_code)]
+    pub fn event_params() -> EventParams {
+        vec![
+            EventParam {
+                name: "token".to_string(),
+                kind: ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "sender".to_string(),
+                kind: ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "amount".to_string(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            },
+            EventParam {
+                name: "accountId".to_string(),
+                kind: ParamType::String,
+                indexed: false,
+            },
+        ]
+    }
+
+    /// Parses raw Ethereum logs proof's entry data. The `accountId` of the event is
+    /// the hex address of the recipient on Aurora.
+    pub fn from_log_entry_data(data: &[u8]) -> Result<Self, error::ParseError> {
+        let event = EthEvent::fetch_log_entry_data(LOCKED_EVENT, Self::event_params(), data)
+            .map_err(error::ParseError::LogParseFailed)?;
+        let token = event.log.params[0]
+            .value
+            .clone()
+            .into_address()
+            .ok_or(error::ParseError::InvalidToken)?
+            .0;
+        let sender = event.log.params[1]
+            .value
+            .clone()
+            .into_address()
+            .ok_or(error::ParseError::InvalidSender)?
+            .0;
+        let amount = event.log.params[2]
+            .value
+            .clone()
+            .into_uint()
+            .ok_or(error::ParseError::InvalidAmount)?;
+
+        let account_id: String = event.log.params[3].value.clone().to_string();
+        let address = account_id
+            .strip_prefix("0x")
+            .unwrap_or(&account_id)
+            .to_string();
+        let recipient = validate_eth_address(address)
+            .map_err(ParseEventMessageError::EthAddressValidationError)?;
+
+        Ok(Self {
+            locker_address: event.eth_custodian_address,
+            token,
+            sender,
+            amount,
+            recipient,
+        })
+    }
+}
+
 pub mod error {
     use super::*;
 
@@ -345,6 +423,7 @@ pub mod error {
     #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
     pub enum ParseError {
         LogParseFailed(DecodeError),
+        InvalidToken,
         InvalidSender,
         InvalidAmount,
         InvalidFee,
@@ -355,6 +434,7 @@ pub mod error {
         fn as_ref(&self) -> &[u8] {
             match self {
                 Self::LogParseFailed(e) => e.as_ref(),
+                Self::InvalidToken => b"ERR_INVALID_TOKEN",
                 Self::InvalidSender => b"ERR_INVALID_SENDER",
                 Self::InvalidAmount => b"ERR_INVALID_AMOUNT",
                 Self::InvalidFee => b"ERR_INVALID_FEE",
@@ -385,3 +465,42 @@ pub mod error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_entry::LogEntry;
+    use crate::prelude::{H160, H256};
+
+    #[test]
+    fn test_erc20_locked_event() {
+        let event = Event {
+            name: LOCKED_EVENT.to_string(),
+            inputs: Erc20LockedEvent::event_params(),
+            anonymous: false,
+        };
+        let locker = [1u8; 20];
+        let token = [2u8; 20];
+        let sender = [3u8; 20];
+        let recipient = [4u8; 20];
+        let log_entry = LogEntry {
+            address: H160(locker),
+            topics: vec![
+                H256(event.signature().0),
+                H256::from(H160(token)),
+                H256::from(H160(sender)),
+            ],
+            data: ethabi::encode(&[
+                ethabi::Token::Uint(U256::from(1000)),
+                ethabi::Token::String(["0x", &hex::encode(recipient)].concat()),
+            ]),
+        };
+
+        let parsed = Erc20LockedEvent::from_log_entry_data(&rlp::encode(&log_entry)).unwrap();
+        assert_eq!(parsed.locker_address, locker);
+        assert_eq!(parsed.token, token);
+        assert_eq!(parsed.sender, sender);
+        assert_eq!(parsed.amount, U256::from(1000));
+        assert_eq!(parsed.recipient, recipient);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::parameters::{CallArgs, NEP141FtOnTransferArgs, ResultLog, SubmitResult, ViewCallArgs};
+use crate::parameters::{
+    CallArgs, FtOnTransferMessage, NEP141FtOnTransferArgs, RelayerFee, ResultLog, SubmitResult,
+    ViewCallArgs,
+};
 use core::mem;
 use evm::backend::{Apply, ApplyBackend, Backend, Basic, Log};
 use evm::executor;
@@ -10,16 +13,28 @@ use aurora_engine_sdk::env::Env;
 use aurora_engine_sdk::io::{StorageIntermediate, IO};
 use aurora_engine_sdk::promise::{PromiseHandler, PromiseId};
 
-use crate::parameters::{DeployErc20TokenArgs, NewCallArgs, TransactionStatus};
-use crate::prelude::precompiles::native::{ExitToEthereum, ExitToNear};
+use crate::parameters::{
+    DeployErc20TokenArgs, DeployErc721TokenArgs, Erc20Metadata, NewCallArgs,
+    NftOnTransferCallbackArgs, NftToken, SyncErc20MetadataArgs, TransactionStatus,
+    WrappedNearCallArgs,
+};
+use crate::prelude::precompiles::cross_contract;
+use crate::prelude::precompiles::native::{ExitNftToNear, ExitToEthereum, ExitToNear};
+use crate::prelude::precompiles::near_gas;
+use crate::prelude::precompiles::random;
+use crate::prelude::precompiles::registry;
 use crate::prelude::precompiles::Precompiles;
 use crate::prelude::{
     address_to_key, bytes_to_key, sdk, storage_to_key, u256_to_arr, vec, AccountId, Address,
-    BorshDeserialize, BorshSerialize, KeyPrefix, PromiseArgs, PromiseCreateArgs, ToString, TryFrom,
-    TryInto, Vec, Wei, ERC20_MINT_SELECTOR, H256, U256,
+    BTreeMap, BorshDeserialize, BorshSerialize, DisabledPrecompileMode, EvmCallbackCallArgs,
+    KeyPrefix, NearGas, PromiseAction, PromiseArgs, PromiseBatchAction, PromiseCreateArgs,
+    PromiseResult, PromiseWithCallbackArgs, RefundNftCallArgs, ToString, TryFrom, TryInto, Vec,
+    Wei, XccRouterDeployedArgs, ERC20_BURN_SELECTOR, ERC20_MINT_SELECTOR,
+    ERC20_SET_METADATA_SELECTOR, ERC721_MINT_SELECTOR, H256, U256,
 };
 use crate::transaction::{EthTransactionKind, NormalizedEthTransaction};
 use aurora_engine_precompiles::PrecompileConstructorContext;
+use aurora_engine_types::json::escape_json;
 
 /// Used as the first byte in the concatenation of data used to compute the blockhash.
 /// Could be useful in the future as a version byte, or to distinguish different types of blocks.
@@ -28,6 +43,37 @@ const BLOCK_HASH_PREFIX_SIZE: usize = 1;
 const BLOCK_HEIGHT_SIZE: usize = 8;
 const CHAIN_ID_SIZE: usize = 32;
 
+const GAS_FOR_FT_METADATA: NearGas = NearGas::new(5_000_000_000_000);
+const GAS_FOR_SYNC_ERC20_METADATA_CALLBACK: NearGas = NearGas::new(20_000_000_000_000);
+const GAS_FOR_NFT_TOKEN: NearGas = NearGas::new(5_000_000_000_000);
+const GAS_FOR_NFT_ON_TRANSFER_CALLBACK: NearGas = NearGas::new(30_000_000_000_000);
+const GAS_FOR_NEAR_DEPOSIT: NearGas = NearGas::new(5_000_000_000_000);
+const GAS_FOR_CALL_WITH_NEAR: NearGas = NearGas::new(10_000_000_000_000);
+const GAS_FOR_XCC_ROUTER_INITIALIZE: NearGas = NearGas::new(30_000_000_000_000);
+const GAS_FOR_XCC_ROUTER_DEPLOYED: NearGas = NearGas::new(10_000_000_000_000);
+
+/// yoctoNEAR staked per byte of NEAR storage.
+const STORAGE_PRICE_PER_BYTE: u128 = 10_000_000_000_000_000_000;
+/// Deposit for the router account and state and its wNEAR storage deposit, on top of the
+/// storage of the router code.
+const XCC_ROUTER_BASE_DEPOSIT: u128 = 15_000_000_000_000_000_000_000;
+
+/// Value returned by EIP-1271 `isValidSignature(bytes32,bytes)` for a valid signature,
+/// which is also its selector.
+#[cfg(feature = "meta-call")]
+const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+/// EVM gas available to `isValidSignature` when checking the signature of a meta-call.
+#[cfg(feature = "meta-call")]
+const EIP1271_GAS_LIMIT: u64 = 500_000;
+
+/// Key under which the wNEAR ERC-20 address is kept in the engine config storage.
+const WNEAR_ADDRESS_KEY: &[u8] = b"WNEAR_ADDRESS";
+/// Key under which the EVM log events mode is kept in the engine config storage.
+const EVM_LOG_EVENTS_KEY: &[u8] = b"EVM_LOG_EVENTS";
+/// Prefix of the keys of the allow-listed unprotected transaction hashes in the engine
+/// config storage.
+const UNPROTECTED_TX_ALLOWED_KEY: &[u8] = b"UNPROTECTED_TX_ALLOWED";
+
 pub fn current_address(current_account_id: &AccountId) -> Address {
     aurora_engine_sdk::types::near_account_to_evm_address(current_account_id.as_bytes())
 }
@@ -89,6 +135,8 @@ pub enum EngineErrorKind {
     MaxPriorityGasFeeTooLarge,
     GasPayment(GasPaymentError),
     GasOverflow,
+    /// Transaction without EIP-155 replay protection, rejected by the engine policy.
+    UnprotectedTransaction,
 }
 
 impl EngineErrorKind {
@@ -127,6 +175,7 @@ impl EngineErrorKind {
             MaxPriorityGasFeeTooLarge => b"ERR_MAX_PRIORITY_FEE_GREATER",
             GasPayment(e) => e.as_ref(),
             GasOverflow => b"ERR_GAS_OVERFLOW",
+            UnprotectedTransaction => b"ERR_UNPROTECTED_TX",
         }
     }
 }
@@ -226,6 +275,24 @@ impl AsRef<[u8]> for DeployErc20Error {
     }
 }
 
+#[derive(Debug)]
+pub enum SetErc20MetadataError {
+    State(EngineStateError),
+    Erc20NotFound(GetErc20FromNep141Error),
+    Failed(TransactionStatus),
+    Engine(EngineError),
+}
+impl AsRef<[u8]> for SetErc20MetadataError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::State(e) => e.as_ref(),
+            Self::Erc20NotFound(e) => e.as_ref(),
+            Self::Failed(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
 pub struct ERC20Address(Address);
 
 impl AsRef<[u8]> for ERC20Address {
@@ -314,6 +381,204 @@ impl AsRef<[u8]> for RegisterTokenError {
     }
 }
 
+pub struct ERC721Address(Address);
+
+impl AsRef<[u8]> for ERC721Address {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for ERC721Address {
+    type Error = AddressParseError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        if bytes.len() == 20 {
+            Ok(Self(Address::from_slice(&bytes)))
+        } else {
+            Err(AddressParseError)
+        }
+    }
+}
+
+pub struct NEP171Account(AccountId);
+
+impl AsRef<[u8]> for NEP171Account {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for NEP171Account {
+    type Error = aurora_engine_types::account_id::ParseAccountError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        AccountId::try_from(bytes).map(Self)
+    }
+}
+
+#[derive(Debug)]
+pub enum GetErc721FromNep171Error {
+    Nep171NotFound,
+}
+
+impl AsRef<[u8]> for GetErc721FromNep171Error {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Nep171NotFound => b"ERR_NEP171_NOT_FOUND",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RegisterNftError {
+    NftAlreadyRegistered,
+}
+
+impl AsRef<[u8]> for RegisterNftError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::NftAlreadyRegistered => b"ERR_NEP171_TOKEN_ALREADY_REGISTERED",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DeployErc721Error {
+    State(EngineStateError),
+    Failed(TransactionStatus),
+    Engine(EngineError),
+    Register(RegisterNftError),
+}
+impl AsRef<[u8]> for DeployErc721Error {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::State(e) => e.as_ref(),
+            Self::Failed(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+            Self::Register(e) => e.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MintErc721Error {
+    State(EngineStateError),
+    Erc721NotFound(GetErc721FromNep171Error),
+    Failed(TransactionStatus),
+    Engine(EngineError),
+}
+impl AsRef<[u8]> for MintErc721Error {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::State(e) => e.as_ref(),
+            Self::Erc721NotFound(e) => e.as_ref(),
+            Self::Failed(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MintErc20Error {
+    State(EngineStateError),
+    Failed(TransactionStatus),
+    Engine(EngineError),
+}
+impl AsRef<[u8]> for MintErc20Error {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::State(e) => e.as_ref(),
+            Self::Failed(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SenderSignatureError {
+    Invalid,
+    Engine(EngineErrorKind),
+}
+impl AsRef<[u8]> for SenderSignatureError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Invalid => b"ERR_META_TX_INVALID_SIGNATURE",
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
+#[cfg(feature = "meta-call")]
+#[derive(Debug)]
+pub enum MetaCallV2Error {
+    Parse,
+    Expired,
+    SenderSignature(SenderSignatureError),
+    Nonce(EngineErrorKind),
+    FeePayment(GasPaymentError),
+    State(EngineStateError),
+    Engine(EngineError),
+}
+#[cfg(feature = "meta-call")]
+impl AsRef<[u8]> for MetaCallV2Error {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Parse => b"ERR_META_TX_PARSE",
+            Self::Expired => b"ERR_META_TX_EXPIRED",
+            Self::SenderSignature(e) => e.as_ref(),
+            Self::Nonce(e) => e.as_ref(),
+            Self::FeePayment(e) => e.as_ref(),
+            Self::State(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CallWithNearError {
+    /// NEAR is attached but no wNEAR ERC-20 is configured to wrap it into.
+    WnearNotSet,
+    /// The configured wNEAR ERC-20 is not bridged from a NEP-141.
+    WnearNotFound,
+    /// The prepaid gas does not cover the wrapping and the callback making the call.
+    NotEnoughGas,
+    Failed(TransactionStatus),
+    Engine(EngineError),
+}
+impl AsRef<[u8]> for CallWithNearError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::WnearNotSet => b"ERR_WNEAR_NOT_SET",
+            Self::WnearNotFound => b"ERR_WNEAR_NOT_FOUND",
+            Self::NotEnoughGas => b"ERR_NOT_ENOUGH_GAS_FOR_CALL_WITH_NEAR",
+            Self::Failed(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DeployXccRouterError {
+    /// The owner has not set the router code.
+    CodeNotSet,
+    AlreadyDeployed,
+    InvalidAccountId,
+    /// The attached NEAR does not cover the storage of the router account.
+    InsufficientDeposit,
+}
+
+impl AsRef<[u8]> for DeployXccRouterError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::CodeNotSet => b"ERR_XCC_ROUTER_CODE_NOT_SET",
+            Self::AlreadyDeployed => b"ERR_XCC_ROUTER_EXISTS",
+            Self::InvalidAccountId => b"ERR_XCC_ROUTER_ACCOUNT_ID",
+            Self::InsufficientDeposit => b"ERR_XCC_ROUTER_DEPOSIT",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum EngineStateError {
     NotFound,
@@ -329,30 +594,46 @@ impl AsRef<[u8]> for EngineStateError {
     }
 }
 
-struct StackExecutorParams {
-    precompiles: Precompiles,
+struct StackExecutorParams<'a> {
+    precompiles: Precompiles<'a>,
     gas_limit: u64,
 }
 
-impl StackExecutorParams {
-    fn new(gas_limit: u64, current_account_id: AccountId, random_seed: H256) -> Self {
+impl<'a> StackExecutorParams<'a> {
+    fn new<I: IO + Copy + 'a, E: Env>(
+        gas_limit: u64,
+        current_account_id: AccountId,
+        execution_id: &'a random::ExecutionId<I>,
+        io: &I,
+        env: &E,
+        precompile_config: &PrecompileConfig,
+    ) -> Self {
+        // The BLS12-381 precompiles of `new_prague` are enabled by a later engine upgrade
+        let mut precompiles = Precompiles::new_london(PrecompileConstructorContext {
+            current_account_id,
+            predecessor_account_id: env.predecessor_account_id(),
+            signer_account_id: env.signer_account_id(),
+            random_seed: env.random_seed(),
+            execution_id,
+            eth_gas_per_tgas: precompile_config.eth_gas_per_tgas,
+            io: *io,
+        });
+        precompiles.disable(&precompile_config.disabled);
+
         Self {
-            precompiles: Precompiles::new_london(PrecompileConstructorContext {
-                current_account_id,
-                random_seed,
-            }),
+            precompiles,
             gas_limit,
         }
     }
 
-    fn make_executor<'a, 'env, I: IO + Copy, E: Env>(
-        &'a self,
-        engine: &'a Engine<'env, I, E>,
+    fn make_executor<'b, 'env, I: IO + Copy, E: Env>(
+        &'b self,
+        engine: &'b Engine<'env, I, E>,
     ) -> executor::StackExecutor<
         'static,
-        'a,
+        'b,
         executor::MemoryStackState<Engine<'env, I, E>>,
-        Precompiles,
+        Precompiles<'a>,
     > {
         let metadata = executor::StackSubstateMetadata::new(self.gas_limit, CONFIG);
         let state = executor::MemoryStackState::new(metadata, engine);
@@ -381,6 +662,8 @@ pub struct EngineState {
     pub bridge_prover_id: AccountId,
     /// How many blocks after staging upgrade can deploy it.
     pub upgrade_delay_blocks: u64,
+    /// Whether transactions without EIP-155 replay protection are accepted.
+    pub unprotected_transactions_policy: UnprotectedTransactionsPolicy,
 }
 
 impl From<NewCallArgs> for EngineState {
@@ -390,15 +673,74 @@ impl From<NewCallArgs> for EngineState {
             owner_id: args.owner_id,
             bridge_prover_id: args.bridge_prover_id,
             upgrade_delay_blocks: args.upgrade_delay_blocks,
+            unprotected_transactions_policy: UnprotectedTransactionsPolicy::default(),
+        }
+    }
+}
+
+/// Policy for legacy transactions signed without a chain ID (pre-EIP-155), which can be
+/// replayed from any other chain.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnprotectedTransactionsPolicy {
+    /// Unprotected transactions are accepted.
+    Allow,
+    /// Unprotected transactions are rejected, unless their hash is allow-listed by the
+    /// owner (e.g. keyless deployments such as the deterministic CREATE2 factory).
+    Reject,
+}
+
+impl Default for UnprotectedTransactionsPolicy {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+/// Layout of the engine state before `unprotected_transactions_policy` was added.
+#[derive(BorshDeserialize)]
+struct LegacyEngineState {
+    chain_id: [u8; 32],
+    owner_id: AccountId,
+    bridge_prover_id: AccountId,
+    upgrade_delay_blocks: u64,
+}
+
+impl From<LegacyEngineState> for EngineState {
+    fn from(state: LegacyEngineState) -> Self {
+        EngineState {
+            chain_id: state.chain_id,
+            owner_id: state.owner_id,
+            bridge_prover_id: state.bridge_prover_id,
+            upgrade_delay_blocks: state.upgrade_delay_blocks,
+            unprotected_transactions_policy: UnprotectedTransactionsPolicy::default(),
+        }
+    }
+}
+
+/// Owner configuration of the precompiles, read once per engine rather than per EVM execution.
+struct PrecompileConfig {
+    /// EVM gas charged per Tgas of NEAR gas used by the promise-creating precompiles.
+    eth_gas_per_tgas: u64,
+    /// Precompiles disabled by the owner.
+    disabled: BTreeMap<Address, DisabledPrecompileMode>,
+}
+
+impl PrecompileConfig {
+    fn load<I: IO>(io: &I) -> Self {
+        Self {
+            eth_gas_per_tgas: near_gas::get_eth_gas_per_tgas(io),
+            disabled: registry::get_disabled(io),
         }
     }
 }
 
 pub struct Engine<'env, I: IO, E: Env> {
     state: EngineState,
+    precompile_config: PrecompileConfig,
     origin: Address,
     gas_price: U256,
     current_account_id: AccountId,
+    /// Hash of the signed Ethereum transaction being executed, zero otherwise.
+    transaction_hash: H256,
     io: I,
     env: &'env E,
 }
@@ -427,9 +769,11 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
     ) -> Self {
         Self {
             state,
+            precompile_config: PrecompileConfig::load(&io),
             origin,
             gas_price: U256::zero(),
             current_account_id,
+            transaction_hash: H256::zero(),
             io,
             env,
         }
@@ -488,10 +832,18 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         access_list: Vec<(Address, Vec<H256>)>, // See EIP-2930
         handler: &mut P,
     ) -> EngineResult<SubmitResult> {
+        let execution_id = random::ExecutionId::new(
+            self.io,
+            self.transaction_hash,
+            self.env.predecessor_account_id(),
+        );
         let executor_params = StackExecutorParams::new(
             gas_limit,
             self.current_account_id.clone(),
-            self.env.random_seed(),
+            &execution_id,
+            &self.io,
+            self.env,
+            &self.precompile_config,
         );
         let mut executor = executor_params.make_executor(self);
         let address = executor.create_address(CreateScheme::Legacy { caller: origin });
@@ -511,12 +863,158 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
 
         let (values, logs) = executor.into_state().deconstruct();
         let logs = filter_promises_from_logs(handler, logs);
+        self.emit_evm_log_events(&execution_id, &logs);
 
         self.apply(values, Vec::<Log>::new(), true);
 
         Ok(SubmitResult::new(status, used_gas, logs))
     }
 
+    /// Wraps the NEAR attached to a call into wNEAR credited to the origin before making
+    /// the call.
+    ///
+    /// The NEAR is first deposited to the wNEAR NEP-141, and the `call_with_wrapped_near`
+    /// callback then mints the wNEAR backed by it and makes the call, so no wNEAR exists
+    /// before the NEAR backing it. The returned promise resolves to the result of the call.
+    pub fn call_with_attached_near<P: PromiseHandler>(
+        &mut self,
+        args: CallArgs,
+        wnear: Address,
+        amount: u128,
+        handler: &mut P,
+    ) -> Result<PromiseId, CallWithNearError> {
+        let wnear_nep141 = nep141_erc20_map(self.io)
+            .lookup_right(&ERC20Address(wnear))
+            .ok_or(CallWithNearError::WnearNotFound)?;
+
+        let required_gas = GAS_FOR_NEAR_DEPOSIT + GAS_FOR_CALL_WITH_NEAR;
+        let prepaid_gas = self.env.prepaid_gas();
+        if prepaid_gas.into_u64() <= required_gas.into_u64() {
+            return Err(CallWithNearError::NotEnoughGas);
+        }
+
+        let callback_args = WrappedNearCallArgs {
+            wnear: wnear.0,
+            origin: self.origin.0,
+            payer: self.env.predecessor_account_id(),
+            amount,
+            call: args,
+        };
+        let promise_id = handler.promise_crate_with_callback(&PromiseWithCallbackArgs {
+            base: PromiseCreateArgs {
+                target_account_id: wnear_nep141.0,
+                method: "near_deposit".to_string(),
+                args: b"{}".to_vec(),
+                attached_balance: amount,
+                attached_gas: GAS_FOR_NEAR_DEPOSIT.into_u64(),
+            },
+            callback: PromiseCreateArgs {
+                target_account_id: self.current_account_id.clone(),
+                method: "call_with_wrapped_near".to_string(),
+                args: callback_args.try_to_vec().unwrap(),
+                attached_balance: 0,
+                attached_gas: (prepaid_gas - required_gas).into_u64(),
+            },
+        });
+
+        Ok(promise_id)
+    }
+
+    /// Handles the result of the `near_deposit` promise created by `call_with_attached_near`.
+    ///
+    /// If the NEAR was wrapped, the wNEAR backed by it is minted to the origin and the call is
+    /// made; the wNEAR is kept even if the call fails, so the call result is returned as is.
+    /// Otherwise the NEAR, which the failed deposit returned to the engine, is refunded to the
+    /// payer and `None` is returned.
+    pub fn call_with_wrapped_near<P: PromiseHandler>(
+        &mut self,
+        args: WrappedNearCallArgs,
+        promise_result: PromiseResult,
+        handler: &mut P,
+    ) -> Result<Option<EngineResult<SubmitResult>>, CallWithNearError> {
+        if !matches!(promise_result, PromiseResult::Successful(_)) {
+            handler.promise_create_batch(&PromiseBatchAction {
+                target_account_id: args.payer,
+                actions: vec![PromiseAction::Transfer {
+                    amount: args.amount,
+                }],
+            });
+            return Ok(None);
+        }
+
+        let tail = ethabi::encode(&[
+            ethabi::Token::Address(Address(args.origin)),
+            ethabi::Token::Uint(args.amount.into()),
+        ]);
+        let admin_address = current_address(&self.current_account_id);
+        let mint_result = self
+            .call(
+                admin_address,
+                Address(args.wnear),
+                Wei::zero(),
+                [ERC20_MINT_SELECTOR, tail.as_slice()].concat(),
+                u64::MAX,
+                Vec::new(),
+                handler,
+            )
+            .map_err(CallWithNearError::Engine)?;
+        if !mint_result.status.is_ok() {
+            return Err(CallWithNearError::Failed(mint_result.status));
+        }
+
+        Ok(Some(self.call_with_args(args.call, handler)))
+    }
+
+    /// Makes the given `(origin, contract, value, input)` calls in a single EVM execution. The
+    /// state changes are only applied if all the calls succeed; otherwise the status of
+    /// the first failed call is returned.
+    fn call_all<P: PromiseHandler>(
+        &mut self,
+        calls: Vec<(Address, Address, Wei, Vec<u8>)>,
+        handler: &mut P,
+    ) -> EngineResult<SubmitResult> {
+        let execution_id = random::ExecutionId::new(
+            self.io,
+            self.transaction_hash,
+            self.env.predecessor_account_id(),
+        );
+        let executor_params = StackExecutorParams::new(
+            u64::MAX,
+            self.current_account_id.clone(),
+            &execution_id,
+            &self.io,
+            self.env,
+            &self.precompile_config,
+        );
+        let mut executor = executor_params.make_executor(self);
+        let mut status = TransactionStatus::Succeed(Vec::new());
+        for (origin, contract, value, input) in calls {
+            let (exit_reason, result) =
+                executor.transact_call(origin, contract, value.raw(), input, u64::MAX, Vec::new());
+            status = exit_reason
+                .into_result(result)
+                .map_err(|e| e.with_gas_used(executor.used_gas()))?;
+            if !status.is_ok() {
+                return Ok(SubmitResult::new(status, executor.used_gas(), Vec::new()));
+            }
+        }
+
+        let used_gas = executor.used_gas();
+        let (values, logs) = executor.into_state().deconstruct();
+        let logs = filter_promises_from_logs(handler, logs);
+        self.emit_evm_log_events(&execution_id, &logs);
+        self.apply(values, Vec::<Log>::new(), true);
+
+        Ok(SubmitResult::new(status, used_gas, logs))
+    }
+
+    /// Writes the logs to the NEAR logs as NEP-297 events, if enabled by the owner.
+    fn emit_evm_log_events(&self, execution_id: &random::ExecutionId<I>, logs: &[ResultLog]) {
+        if !logs.is_empty() && get_evm_log_events(&self.io) {
+            crate::events::emit_evm_log_events(execution_id.get(), logs);
+        }
+    }
+
     /// Call the EVM contract with arguments
     pub fn call_with_args<P: PromiseHandler>(
         &mut self,
@@ -567,10 +1065,18 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         access_list: Vec<(Address, Vec<H256>)>, // See EIP-2930
         handler: &mut P,
     ) -> EngineResult<SubmitResult> {
+        let execution_id = random::ExecutionId::new(
+            self.io,
+            self.transaction_hash,
+            self.env.predecessor_account_id(),
+        );
         let executor_params = StackExecutorParams::new(
             gas_limit,
             self.current_account_id.clone(),
-            self.env.random_seed(),
+            &execution_id,
+            &self.io,
+            self.env,
+            &self.precompile_config,
         );
         let mut executor = executor_params.make_executor(self);
         let (exit_reason, result) =
@@ -587,9 +1093,10 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
 
         let (values, logs) = executor.into_state().deconstruct();
         let logs = filter_promises_from_logs(handler, logs);
+        // The logs can't be passed to the NEAR log method as they are, since it only
+        // allows UTF-8 strings. They are written as NEP-297 events instead if enabled.
+        self.emit_evm_log_events(&execution_id, &logs);
 
-        // There is no way to return the logs to the NEAR log method as it only
-        // allows a return of UTF-8 strings.
         self.apply(values, Vec::<Log>::new(), true);
 
         Ok(SubmitResult::new(status, used_gas, logs))
@@ -610,10 +1117,18 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         input: Vec<u8>,
         gas_limit: u64,
     ) -> Result<TransactionStatus, EngineErrorKind> {
+        let execution_id = random::ExecutionId::new_view(
+            self.io,
+            self.transaction_hash,
+            self.env.predecessor_account_id(),
+        );
         let executor_params = StackExecutorParams::new(
             gas_limit,
             self.current_account_id.clone(),
-            self.env.random_seed(),
+            &execution_id,
+            &self.io,
+            self.env,
+            &self.precompile_config,
         );
         let mut executor = executor_params.make_executor(self);
         let (status, result) =
@@ -621,6 +1136,75 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         status.into_result(result)
     }
 
+    /// Checks that the explicit sender of a meta-call signed it: a contract sender through
+    /// EIP-1271 `isValidSignature`, which must return the magic value, and any other
+    /// sender by being the ECDSA signer.
+    #[cfg(feature = "meta-call")]
+    pub fn verify_sender_signature(
+        &self,
+        sender: Address,
+        signature: crate::meta_parsing::SenderSignature,
+    ) -> Result<(), SenderSignatureError> {
+        if get_code_size(&self.io, &sender) == 0 {
+            return match crate::meta_parsing::recover_signer(
+                &signature.message,
+                &signature.signature,
+            ) {
+                Ok(signer) if signer == sender => Ok(()),
+                _ => Err(SenderSignatureError::Invalid),
+            };
+        }
+
+        let tail = ethabi::encode(&[
+            ethabi::Token::FixedBytes(signature.message.to_vec()),
+            ethabi::Token::Bytes(signature.signature),
+        ]);
+        let status = self
+            .view(
+                current_address(&self.current_account_id),
+                sender,
+                Wei::zero(),
+                [&EIP1271_MAGIC_VALUE[..], tail.as_slice()].concat(),
+                EIP1271_GAS_LIMIT,
+            )
+            .map_err(SenderSignatureError::Engine)?;
+        match status {
+            TransactionStatus::Succeed(output)
+                if output.get(..4) == Some(&EIP1271_MAGIC_VALUE[..]) =>
+            {
+                Ok(())
+            }
+            _ => Err(SenderSignatureError::Invalid),
+        }
+    }
+
+    /// Delivers the result of a promise created by the cross-contract call precompile to
+    /// the EVM contract which created it. The selected method is called with
+    /// `(bool success, bytes result)` as arguments and the precompile address as the caller,
+    /// so the contract can tell genuine callbacks apart.
+    pub fn receive_promise_result<P: PromiseHandler>(
+        &mut self,
+        args: EvmCallbackCallArgs,
+        promise_result: PromiseResult,
+        handler: &mut P,
+    ) -> EngineResult<SubmitResult> {
+        let (success, result) = match promise_result {
+            PromiseResult::Successful(bytes) => (true, bytes),
+            PromiseResult::Failed | PromiseResult::NotReady => (false, Vec::new()),
+        };
+        let tail = ethabi::encode(&[ethabi::Token::Bool(success), ethabi::Token::Bytes(result)]);
+
+        self.call(
+            cross_contract::ADDRESS,
+            Address(args.contract),
+            Wei::zero(),
+            [&args.selector[..], tail.as_slice()].concat(),
+            args.gas_limit,
+            Vec::new(),
+            handler,
+        )
+    }
+
     fn relayer_key(account_id: &[u8]) -> Vec<u8> {
         bytes_to_key(KeyPrefix::RelayerEvmAddressMap, account_id)
     }
@@ -656,6 +1240,21 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         Ok(())
     }
 
+    pub fn register_nft(
+        &mut self,
+        erc721_token: Address,
+        nep171_token: AccountId,
+    ) -> Result<(), RegisterNftError> {
+        if get_erc721_from_nep171(&self.io, &nep171_token).is_ok() {
+            return Err(RegisterNftError::NftAlreadyRegistered);
+        }
+
+        let erc721_token = ERC721Address(erc721_token);
+        let nep171_token = NEP171Account(nep171_token);
+        nep171_erc721_map(self.io).insert(&nep171_token, &erc721_token);
+        Ok(())
+    }
+
     /// Transfers an amount from a given sender to a receiver, provided that
     /// the have enough in their balance.
     ///
@@ -701,32 +1300,19 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         let str_amount = crate::prelude::format!("\"{}\"", args.amount);
         let output_on_fail = str_amount.as_bytes();
 
-        // Parse message to determine recipient and fee
-        let (recipient, fee) = {
-            // Message format:
-            //      Recipient of the transaction - 40 characters (Address in hex)
-            //      Fee to be paid in ETH (Optional) - 64 characters (Encoded in big endian / hex)
-            let mut message = args.msg.as_bytes();
-            assert_or_finish!(message.len() >= 40, output_on_fail, self.io);
-
-            let recipient = Address(unwrap_res_or_finish!(
-                hex::decode(&message[..40]).unwrap().as_slice().try_into(),
-                output_on_fail,
-                self.io
-            ));
-            message = &message[40..];
-
-            let fee = if message.is_empty() {
-                U256::from(0)
-            } else {
-                assert_or_finish!(message.len() == 64, output_on_fail, self.io);
-                U256::from_big_endian(
-                    unwrap_res_or_finish!(hex::decode(message), output_on_fail, self.io).as_slice(),
-                )
-            };
-
-            (recipient, fee)
+        let message = unwrap_res_or_finish!(
+            FtOnTransferMessage::parse(&args.msg),
+            output_on_fail,
+            self.io
+        );
+        let recipient = Address(message.recipient);
+        let amount = U256::from(args.amount);
+        // The legacy fee is paid in ETH by the recipient, the JSON one out of the bridged tokens
+        let token_fee = match message.fee {
+            RelayerFee::Eth(_) => U256::zero(),
+            RelayerFee::Tokens(fee) => fee,
         };
+        assert_or_finish!(token_fee <= amount, output_on_fail, self.io);
 
         let erc20_token = Address(unwrap_res_or_finish!(
             unwrap_res_or_finish!(
@@ -740,44 +1326,59 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
             self.io
         ));
 
-        if fee != U256::from(0) {
+        let erc20_admin_address = current_address(current_account_id);
+        let mint = |account: Address, amount: U256| {
+            let tail =
+                ethabi::encode(&[ethabi::Token::Address(account), ethabi::Token::Uint(amount)]);
+            (
+                erc20_admin_address,
+                erc20_token,
+                Wei::zero(),
+                [ERC20_MINT_SELECTOR, tail.as_slice()].concat(),
+            )
+        };
+
+        let mut calls = Vec::new();
+        let is_fee_paid = match message.fee {
+            RelayerFee::Eth(fee) => !fee.is_zero(),
+            RelayerFee::Tokens(fee) => !fee.is_zero(),
+        };
+        if is_fee_paid {
             let relayer_address = unwrap_res_or_finish!(
                 self.get_relayer(relayer_account_id.as_bytes()).ok_or(()),
                 output_on_fail,
                 self.io
             );
-
-            unwrap_res_or_finish!(
-                self.transfer(
-                    recipient,
-                    relayer_address,
-                    Wei::new_u64(fee.as_u64()),
-                    u64::MAX,
-                    handler,
-                ),
+            match message.fee {
+                RelayerFee::Eth(fee) => {
+                    calls.push((recipient, relayer_address, fee, Vec::new()));
+                    calls.push(mint(recipient, amount));
+                }
+                RelayerFee::Tokens(fee) => {
+                    calls.push(mint(recipient, amount - fee));
+                    calls.push(mint(relayer_address, fee));
+                }
+            }
+        } else {
+            calls.push(mint(recipient, amount));
+        }
+        // The call is made by the recipient, so it must be the address of the NEAR account
+        // which sent the tokens.
+        if let Some(call) = message.call {
+            assert_or_finish!(
+                recipient
+                    == aurora_engine_sdk::types::near_account_to_evm_address(
+                        args.sender_id.as_bytes()
+                    ),
                 output_on_fail,
                 self.io
             );
+            calls.push((recipient, Address(call.contract), Wei::zero(), call.input));
         }
-
-        let selector = ERC20_MINT_SELECTOR;
-        let tail = ethabi::encode(&[
-            ethabi::Token::Address(recipient),
-            ethabi::Token::Uint(args.amount.into()),
-        ]);
-
-        let erc20_admin_address = current_address(current_account_id);
+        // Minting, the relayer fee and the call either all happen or none does, in which
+        // case all the tokens are returned to the sender.
         unwrap_res_or_finish!(
-            self.call(
-                erc20_admin_address,
-                erc20_token,
-                Wei::zero(),
-                [selector, tail.as_slice()].concat(),
-                u64::MAX,
-                Vec::new(), // TODO: are there values we should put here?
-                handler,
-            )
-            .and_then(|submit_result| {
+            self.call_all(calls, handler).and_then(|submit_result| {
                 match submit_result.status {
                     TransactionStatus::Succeed(_) => Ok(()),
                     TransactionStatus::Revert(bytes) => {
@@ -840,6 +1441,15 @@ pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
         }
     }
 
+    // Reject transactions without replay protection, unless the policy or the allow-list
+    // accepts them:
+    if transaction.chain_id.is_none()
+        && state.unprotected_transactions_policy == UnprotectedTransactionsPolicy::Reject
+        && !is_unprotected_transaction_allowed(&io, &sdk::keccak(transaction_bytes))
+    {
+        return Err(EngineErrorKind::UnprotectedTransaction.into());
+    }
+
     // Retrieve the signer of the transaction:
     let sender = transaction
         .address
@@ -866,6 +1476,7 @@ pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
     }
 
     let mut engine = Engine::new_with_state(state, sender, current_account_id, io, env);
+    engine.transaction_hash = sdk::keccak(transaction_bytes);
     let prepaid_amount = match engine.charge_gas(&sender, &transaction) {
         Ok(gas_result) => gas_result,
         Err(GasPaymentError::OutOfFund) => {
@@ -926,6 +1537,59 @@ pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
     result
 }
 
+/// Executes a meta-call signed according to EIP-712 with Aurora's domain, see
+/// `meta_parsing::prepare_meta_call_v2_args`. The fee is paid to the fee address before
+/// the call and stays paid if the call reverts, while an error aborts the whole meta-call.
+#[cfg(feature = "meta-call")]
+pub fn meta_call_v2<I: IO + Copy, E: Env, P: PromiseHandler>(
+    mut io: I,
+    env: &E,
+    input: Vec<u8>,
+    handler: &mut P,
+) -> Result<SubmitResult, MetaCallV2Error> {
+    let state = get_state(&io).map_err(MetaCallV2Error::State)?;
+    let current_account_id = env.current_account_id();
+    let domain_separator = crate::meta_parsing::aurora_eip712_domain(
+        U256::from(state.chain_id),
+        current_address(&current_account_id),
+    );
+    let (meta_call_args, sender_signature) =
+        crate::meta_parsing::parse_meta_call_v2(&domain_separator, input)
+            .map_err(|_| MetaCallV2Error::Parse)?;
+    if env.block_timestamp().secs() > meta_call_args.deadline {
+        return Err(MetaCallV2Error::Expired);
+    }
+
+    let mut engine =
+        Engine::new_with_state(state, meta_call_args.sender, current_account_id, io, env);
+    if let Some(signature) = sender_signature {
+        engine
+            .verify_sender_signature(meta_call_args.sender, signature)
+            .map_err(MetaCallV2Error::SenderSignature)?;
+    }
+    check_nonce(&io, &meta_call_args.sender, &meta_call_args.nonce)
+        .map_err(MetaCallV2Error::Nonce)?;
+    pay_meta_call_fee(
+        &mut io,
+        &meta_call_args.sender,
+        &meta_call_args.fee_address,
+        meta_call_args.fee_amount,
+    )
+    .map_err(MetaCallV2Error::FeePayment)?;
+
+    engine
+        .call(
+            meta_call_args.sender,
+            meta_call_args.contract_address,
+            meta_call_args.value,
+            meta_call_args.input,
+            meta_call_args.gas_limit,
+            Vec::new(),
+            handler,
+        )
+        .map_err(MetaCallV2Error::Engine)
+}
+
 /// There is one Aurora block per NEAR block height (note: when heights in NEAR are skipped
 /// they are interpreted as empty blocks on Aurora). The blockhash is derived from the height
 /// according to
@@ -968,6 +1632,22 @@ pub fn set_state<I: IO>(io: &mut I, state: EngineState) {
     );
 }
 
+/// Rewrites a state saved by an engine prior to `unprotected_transactions_policy` in the
+/// current layout. A state already in the current layout is left as is.
+pub fn migrate_state<I: IO>(io: &mut I) -> Result<(), EngineStateError> {
+    let bytes = match io.read_storage(&bytes_to_key(KeyPrefix::Config, STATE_KEY)) {
+        None => return Err(EngineStateError::NotFound),
+        Some(bytes) => bytes.to_vec(),
+    };
+    if EngineState::try_from_slice(&bytes).is_ok() {
+        return Ok(());
+    }
+    let state = LegacyEngineState::try_from_slice(&bytes)
+        .map_err(|_| EngineStateError::DeserializationFailed)?;
+    set_state(io, state.into());
+    Ok(())
+}
+
 pub fn refund_unused_gas<I: IO>(
     io: &mut I,
     sender: &Address,
@@ -1045,12 +1725,259 @@ pub fn deploy_erc20_token<I: IO + Copy, E: Env, P: PromiseHandler>(
 
     sdk::log!(crate::prelude::format!("Deployed ERC-20 in Aurora at: {:#?}", address).as_str());
     engine
-        .register_token(address, args.nep141)
+        .register_token(address, args.nep141.clone())
         .map_err(DeployErc20Error::Register)?;
 
+    handler.promise_crate_with_callback(&sync_erc20_metadata_promise(
+        args.nep141,
+        env.current_account_id(),
+    ));
+
     Ok(address)
 }
 
+/// Fetches `ft_metadata` from the NEP-141 token and passes the result to
+/// `sync_erc20_metadata_callback`, which applies it to the bridged ERC-20.
+pub fn sync_erc20_metadata_promise(
+    nep141: AccountId,
+    current_account_id: AccountId,
+) -> PromiseWithCallbackArgs {
+    let callback_args = SyncErc20MetadataArgs {
+        nep141: nep141.clone(),
+    };
+    PromiseWithCallbackArgs {
+        base: PromiseCreateArgs {
+            target_account_id: nep141,
+            method: "ft_metadata".to_string(),
+            args: Vec::new(),
+            attached_balance: 0,
+            attached_gas: GAS_FOR_FT_METADATA.into_u64(),
+        },
+        callback: PromiseCreateArgs {
+            target_account_id: current_account_id,
+            method: "sync_erc20_metadata_callback".to_string(),
+            args: callback_args.try_to_vec().unwrap(),
+            attached_balance: 0,
+            attached_gas: GAS_FOR_SYNC_ERC20_METADATA_CALLBACK.into_u64(),
+        },
+    }
+}
+
+/// Sets the name, symbol and decimals of the ERC-20 bridged from the given NEP-141 token.
+/// The engine is the admin of the bridged ERC-20 contracts, so the call is made from its address.
+pub fn set_erc20_metadata<I: IO + Copy, E: Env, P: PromiseHandler>(
+    nep141: &AccountId,
+    metadata: Erc20Metadata,
+    io: I,
+    env: &E,
+    handler: &mut P,
+) -> Result<SubmitResult, SetErc20MetadataError> {
+    let current_account_id = env.current_account_id();
+    let erc20_admin_address = current_address(&current_account_id);
+    let erc20_token = get_erc20_from_nep141(&io, nep141)
+        .map_err(SetErc20MetadataError::Erc20NotFound)
+        .map(|address| Address::from_slice(&address))?;
+    let mut engine = Engine::new(erc20_admin_address, current_account_id, io, env)
+        .map_err(SetErc20MetadataError::State)?;
+
+    let tail = ethabi::encode(&[
+        ethabi::Token::String(metadata.name),
+        ethabi::Token::String(metadata.symbol),
+        ethabi::Token::Uint(ethabi::Uint::from(metadata.decimals)),
+    ]);
+    let result = engine
+        .call(
+            erc20_admin_address,
+            erc20_token,
+            Wei::zero(),
+            [ERC20_SET_METADATA_SELECTOR, tail.as_slice()].concat(),
+            u64::MAX,
+            Vec::new(),
+            handler,
+        )
+        .map_err(SetErc20MetadataError::Engine)?;
+    match result.status {
+        TransactionStatus::Succeed(_) => Ok(result),
+        other => Err(SetErc20MetadataError::Failed(other)),
+    }
+}
+
+/// Used to bridge NEP-171 tokens from NEAR to Aurora. On Aurora the NEP-171 becomes an ERC-721.
+pub fn deploy_erc721_token<I: IO + Copy, E: Env, P: PromiseHandler>(
+    args: DeployErc721TokenArgs,
+    io: I,
+    env: &E,
+    handler: &mut P,
+) -> Result<Address, DeployErc721Error> {
+    let current_account_id = env.current_account_id();
+    let erc721_admin_address = current_address(&current_account_id);
+    let mut engine = Engine::new(
+        aurora_engine_sdk::types::near_account_to_evm_address(
+            env.predecessor_account_id().as_bytes(),
+        ),
+        current_account_id,
+        io,
+        env,
+    )
+    .map_err(DeployErc721Error::State)?;
+
+    let erc721_contract = include_bytes!("../../etc/eth-contracts/res/EvmErc721.bin");
+    let deploy_args = ethabi::encode(&[
+        ethabi::Token::String("Empty".to_string()),
+        ethabi::Token::String("EMPTY".to_string()),
+        ethabi::Token::Address(erc721_admin_address),
+    ]);
+
+    let address = match Engine::deploy_code_with_input(
+        &mut engine,
+        (&[erc721_contract, deploy_args.as_slice()].concat()).to_vec(),
+        handler,
+    ) {
+        Ok(result) => match result.status {
+            TransactionStatus::Succeed(ret) => Address(ret.as_slice().try_into().unwrap()),
+            other => return Err(DeployErc721Error::Failed(other)),
+        },
+        Err(e) => return Err(DeployErc721Error::Engine(e)),
+    };
+
+    sdk::log!(crate::prelude::format!("Deployed ERC-721 in Aurora at: {:#?}", address).as_str());
+    engine
+        .register_nft(address, args.nep171)
+        .map_err(DeployErc721Error::Register)?;
+
+    Ok(address)
+}
+
+/// Fetches the token received by `nft_on_transfer` with `nft_token` and passes it to
+/// `nft_on_transfer_callback`, which mints the ERC-721 token with the token URI.
+pub fn nft_on_transfer_promise(
+    args: NftOnTransferCallbackArgs,
+    current_account_id: AccountId,
+) -> PromiseWithCallbackArgs {
+    let nft_token_args = crate::prelude::format!(
+        r#"{{"token_id": "{}"}}"#,
+        // Escape token id to avoid json injection attacks
+        escape_json(&args.token_id)
+    );
+    PromiseWithCallbackArgs {
+        base: PromiseCreateArgs {
+            target_account_id: args.nep171.clone(),
+            method: "nft_token".to_string(),
+            args: nft_token_args.into_bytes(),
+            attached_balance: 0,
+            attached_gas: GAS_FOR_NFT_TOKEN.into_u64(),
+        },
+        callback: PromiseCreateArgs {
+            target_account_id: current_account_id,
+            method: "nft_on_transfer_callback".to_string(),
+            args: args.try_to_vec().unwrap(),
+            attached_balance: 0,
+            attached_gas: GAS_FOR_NFT_ON_TRANSFER_CALLBACK.into_u64(),
+        },
+    }
+}
+
+/// Mints the ERC-721 token bridged from the given NEP-171 token to the recipient.
+pub fn mint_erc721<I: IO + Copy, E: Env, P: PromiseHandler>(
+    nep171: &AccountId,
+    token: NftToken,
+    recipient: Address,
+    io: I,
+    env: &E,
+    handler: &mut P,
+) -> Result<SubmitResult, MintErc721Error> {
+    let current_account_id = env.current_account_id();
+    let erc721_admin_address = current_address(&current_account_id);
+    let erc721_token = get_erc721_from_nep171(&io, nep171)
+        .map_err(MintErc721Error::Erc721NotFound)
+        .map(|address| Address::from_slice(&address))?;
+    let mut engine = Engine::new(erc721_admin_address, current_account_id, io, env)
+        .map_err(MintErc721Error::State)?;
+
+    let tail = ethabi::encode(&[
+        ethabi::Token::Address(recipient),
+        ethabi::Token::String(token.token_id),
+        ethabi::Token::String(token.token_uri),
+    ]);
+    let result = engine
+        .call(
+            erc721_admin_address,
+            erc721_token,
+            Wei::zero(),
+            [ERC721_MINT_SELECTOR, tail.as_slice()].concat(),
+            u64::MAX,
+            Vec::new(),
+            handler,
+        )
+        .map_err(MintErc721Error::Engine)?;
+    match result.status {
+        TransactionStatus::Succeed(_) => Ok(result),
+        other => Err(MintErc721Error::Failed(other)),
+    }
+}
+
+/// Handles the result of the `nft_transfer` promise created by the exit NFT to NEAR
+/// precompile: the burned ERC-721 token is minted again to its owner if the transfer failed.
+pub fn refund_nft_on_error<I: IO + Copy, E: Env, P: PromiseHandler>(
+    args: RefundNftCallArgs,
+    promise_result: PromiseResult,
+    io: I,
+    env: &E,
+    handler: &mut P,
+) -> Result<Option<SubmitResult>, MintErc721Error> {
+    if let PromiseResult::Successful(_) = promise_result {
+        return Ok(None);
+    }
+    let token = NftToken {
+        token_id: args.token_id,
+        token_uri: args.token_uri,
+    };
+    mint_erc721(
+        &args.nep171,
+        token,
+        Address(args.recipient_address),
+        io,
+        env,
+        handler,
+    )
+    .map(Some)
+}
+
+/// Mints tokens of an ERC-20 whose admin is the engine to the recipient.
+pub fn mint_erc20<I: IO + Copy, E: Env, P: PromiseHandler>(
+    erc20_token: Address,
+    recipient: Address,
+    amount: U256,
+    io: I,
+    env: &E,
+    handler: &mut P,
+) -> Result<SubmitResult, MintErc20Error> {
+    let current_account_id = env.current_account_id();
+    let erc20_admin_address = current_address(&current_account_id);
+    let mut engine = Engine::new(erc20_admin_address, current_account_id, io, env)
+        .map_err(MintErc20Error::State)?;
+
+    let tail = ethabi::encode(&[
+        ethabi::Token::Address(recipient),
+        ethabi::Token::Uint(amount),
+    ]);
+    let result = engine
+        .call(
+            erc20_admin_address,
+            erc20_token,
+            Wei::zero(),
+            [ERC20_MINT_SELECTOR, tail.as_slice()].concat(),
+            u64::MAX,
+            Vec::new(),
+            handler,
+        )
+        .map_err(MintErc20Error::Engine)?;
+    match result.status {
+        TransactionStatus::Succeed(_) => Ok(result),
+        other => Err(MintErc20Error::Failed(other)),
+    }
+}
+
 pub fn set_code<I: IO>(io: &mut I, address: &Address, code: &[u8]) {
     io.write_storage(&address_to_key(KeyPrefix::Code, address), code);
 }
@@ -1123,6 +2050,164 @@ pub fn get_erc20_from_nep141<I: IO>(
         .ok_or(GetErc20FromNep141Error::Nep141NotFound)
 }
 
+pub fn nep171_erc721_map<I: IO>(io: I) -> BijectionMap<NEP171Account, ERC721Address, I> {
+    BijectionMap::new(KeyPrefix::Nep171Erc721Map, KeyPrefix::Erc721Nep171Map, io)
+}
+
+/// Returns the wNEAR ERC-20 which NEAR attached to `call` is wrapped into, if any.
+pub fn get_wnear_address<I: IO>(io: &I) -> Option<Address> {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, WNEAR_ADDRESS_KEY))
+        .and_then(|value| ERC20Address::try_from(value.to_vec()).ok())
+        .map(|address| address.0)
+}
+
+/// Sets the wNEAR ERC-20 address. Attached NEAR is no longer wrapped when unset.
+pub fn set_wnear_address<I: IO>(io: &mut I, address: Option<Address>) {
+    let key = bytes_to_key(KeyPrefix::Config, WNEAR_ADDRESS_KEY);
+    match address {
+        Some(address) => io.write_storage(&key, address.as_bytes()),
+        None => {
+            io.remove_storage(&key);
+        }
+    }
+}
+
+/// Creates the router account which executes the cross-contract calls of the given EVM
+/// address (see `cross_contract::router_account_id`), funded with `deposit`, and deploys the
+/// router code to it. The callback refunds the payer if the router could not be created.
+pub fn deploy_xcc_router_promise<I: IO + Copy>(
+    io: I,
+    address: Address,
+    payer: AccountId,
+    deposit: u128,
+    current_account_id: &AccountId,
+) -> Result<(PromiseBatchAction, PromiseCreateArgs), DeployXccRouterError> {
+    if cross_contract::is_router_deployed(&io, &address) {
+        return Err(DeployXccRouterError::AlreadyDeployed);
+    }
+    let code = cross_contract::get_router_code(&io).ok_or(DeployXccRouterError::CodeNotSet)?;
+    let required_deposit = (code.len() as u128)
+        .saturating_mul(STORAGE_PRICE_PER_BYTE)
+        .saturating_add(XCC_ROUTER_BASE_DEPOSIT);
+    if deposit < required_deposit {
+        return Err(DeployXccRouterError::InsufficientDeposit);
+    }
+    let router_account_id = cross_contract::router_account_id(&address, current_account_id)
+        .map_err(|_| DeployXccRouterError::InvalidAccountId)?;
+
+    // The router unwraps wNEAR to pay the NEAR attached to cross-contract calls.
+    let wnear_account_id = get_wnear_address(&io)
+        .and_then(|wnear| nep141_erc20_map(io).lookup_right(&ERC20Address(wnear)))
+        .map(|nep141| crate::prelude::format!(r#""{}""#, nep141.0))
+        .unwrap_or_else(|| "null".to_string());
+    // There is no way to inject json, given the encoding of the account id.
+    let initialize_args =
+        crate::prelude::format!(r#"{{"wnear_account_id": {}}}"#, wnear_account_id);
+
+    let batch = PromiseBatchAction {
+        target_account_id: router_account_id,
+        actions: vec![
+            PromiseAction::CreateAccount,
+            PromiseAction::Transfer { amount: deposit },
+            PromiseAction::DeployConotract { code },
+            PromiseAction::FunctionCall {
+                name: "initialize".to_string(),
+                args: initialize_args.into_bytes(),
+                attached_yocto: 0,
+                gas: GAS_FOR_XCC_ROUTER_INITIALIZE.into_u64(),
+            },
+        ],
+    };
+    let callback = PromiseCreateArgs {
+        target_account_id: current_account_id.clone(),
+        method: "xcc_router_deployed".to_string(),
+        args: XccRouterDeployedArgs {
+            address: address.0,
+            payer,
+            amount: deposit,
+        }
+        .try_to_vec()
+        .unwrap(),
+        attached_balance: 0,
+        attached_gas: GAS_FOR_XCC_ROUTER_DEPLOYED.into_u64(),
+    };
+    Ok((batch, callback))
+}
+
+/// Handles the result of the router deployment of `deploy_xcc_router_promise`: the router is
+/// recorded if it was created, otherwise the deposit, which the failed batch returned to the
+/// engine, is refunded to the payer.
+pub fn xcc_router_deployed<I: IO, P: PromiseHandler>(
+    args: XccRouterDeployedArgs,
+    promise_result: PromiseResult,
+    io: &mut I,
+    handler: &mut P,
+) {
+    match promise_result {
+        PromiseResult::Successful(_) => {
+            cross_contract::set_router_deployed(io, &Address(args.address));
+        }
+        _ => {
+            handler.promise_create_batch(&PromiseBatchAction {
+                target_account_id: args.payer,
+                actions: vec![PromiseAction::Transfer {
+                    amount: args.amount,
+                }],
+            });
+        }
+    }
+}
+
+/// Returns whether EVM logs are also written to the NEAR logs as NEP-297 events.
+pub fn get_evm_log_events<I: IO>(io: &I) -> bool {
+    io.read_storage(&bytes_to_key(KeyPrefix::Config, EVM_LOG_EVENTS_KEY))
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(false)
+}
+
+pub fn set_evm_log_events<I: IO>(io: &mut I, enabled: bool) {
+    io.write_borsh(
+        &bytes_to_key(KeyPrefix::Config, EVM_LOG_EVENTS_KEY),
+        &enabled,
+    );
+}
+
+fn unprotected_transaction_key(transaction_hash: &H256) -> Vec<u8> {
+    bytes_to_key(
+        KeyPrefix::Config,
+        &[UNPROTECTED_TX_ALLOWED_KEY, transaction_hash.as_bytes()].concat(),
+    )
+}
+
+/// Whether the unprotected transaction with the given hash is accepted even when the
+/// policy rejects unprotected transactions.
+pub fn is_unprotected_transaction_allowed<I: IO>(io: &I, transaction_hash: &H256) -> bool {
+    io.storage_has_key(&unprotected_transaction_key(transaction_hash))
+}
+
+pub fn set_unprotected_transaction_allowed<I: IO>(
+    io: &mut I,
+    transaction_hash: &H256,
+    allowed: bool,
+) {
+    let key = unprotected_transaction_key(transaction_hash);
+    if allowed {
+        io.write_borsh(&key, &0u8);
+    } else {
+        io.remove_storage(&key);
+    }
+}
+
+pub fn get_erc721_from_nep171<I: IO>(
+    io: &I,
+    nep171_account_id: &AccountId,
+) -> Result<Vec<u8>, GetErc721FromNep171Error> {
+    let key = bytes_to_key(KeyPrefix::Nep171Erc721Map, nep171_account_id.as_bytes());
+    io.read_storage(&key)
+        .map(|v| v.to_vec())
+        .ok_or(GetErc721FromNep171Error::Nep171NotFound)
+}
+
 pub fn add_balance<I: IO>(
     io: &mut I,
     address: &Address,
@@ -1134,6 +2219,21 @@ pub fn add_balance<I: IO>(
     Ok(())
 }
 
+/// Moves the fee of a meta-call from its sender to the fee address.
+pub fn pay_meta_call_fee<I: IO>(
+    io: &mut I,
+    sender: &Address,
+    fee_address: &Address,
+    fee_amount: Wei,
+) -> Result<(), GasPaymentError> {
+    let sender_balance = get_balance(io, sender)
+        .checked_sub(fee_amount)
+        .ok_or(GasPaymentError::OutOfFund)?;
+    set_balance(io, sender, &sender_balance);
+    add_balance(io, fee_address, fee_amount)?;
+    Ok(())
+}
+
 pub fn set_balance<I: IO>(io: &mut I, address: &Address, balance: &Wei) {
     io.write_storage(
         &address_to_key(KeyPrefix::Balance, address),
@@ -1242,7 +2342,11 @@ where
 {
     logs.into_iter()
         .filter_map(|log| {
-            if log.address == ExitToNear::ADDRESS || log.address == ExitToEthereum::ADDRESS {
+            if log.address == ExitToNear::ADDRESS
+                || log.address == ExitToEthereum::ADDRESS
+                || log.address == ExitNftToNear::ADDRESS
+                || log.address == cross_contract::ADDRESS
+            {
                 if log.topics.is_empty() {
                     if let Ok(promise) = PromiseArgs::try_from_slice(&log.data) {
                         match promise {
@@ -0,0 +1,406 @@
+//! NEP-297 event logs, which indexers can parse from the NEAR logs of a receipt.
+
+use crate::json::JsonValue;
+use crate::parameters::ResultLog;
+use crate::prelude::{
+    format, sdk, vec, BTreeMap, Balance, EthAddress, String, ToString, Vec, H256,
+};
+use aurora_engine_types::json::escape_json;
+
+/// Prefix of the NEAR logs holding a NEP-297 event
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+const NEP141_STANDARD: &str = "nep141";
+const NEP141_VERSION: &str = "1.0.0";
+const AURORA_STANDARD: &str = "aurora";
+const AURORA_VERSION: &str = "1.0.0";
+
+/// Maximum length of the NEAR log of an EVM log event. NEAR allows 16 KiB of logs in
+/// total per call, so EVM log events are kept well within it.
+pub const MAX_EVM_LOG_EVENT_LENGTH: usize = 1024;
+/// Maximum number of EVM log events emitted per EVM execution. Further logs are only
+/// available in the `SubmitResult`: their number is given by a final `evm_logs_dropped`
+/// event, so that indexers know the events of the execution are incomplete.
+pub const MAX_EVM_LOG_EVENTS: usize = 8;
+
+/// Event in the NEP-297 format
+pub struct Nep297Event {
+    pub standard: &'static str,
+    pub version: &'static str,
+    pub event: &'static str,
+    pub data: JsonValue,
+}
+
+impl Nep297Event {
+    /// Returns the NEAR log of the event
+    pub fn to_log(&self) -> String {
+        format!(
+            "{}{{\"standard\": {}, \"version\": {}, \"event\": {}, \"data\": {}}}",
+            EVENT_JSON_PREFIX,
+            json_string(self.standard),
+            json_string(self.version),
+            json_string(self.event),
+            self.data,
+        )
+    }
+
+    /// Writes the event to the NEAR logs. Unlike `sdk::log!`, events are written
+    /// whether the `log` feature is enabled or not.
+    pub fn emit(&self) {
+        sdk::log(&self.to_log());
+    }
+}
+
+/// Events of the NEP-141 events standard, emitted for nETH
+pub enum FtEvent<'a> {
+    Mint {
+        owner_id: &'a str,
+        amount: Balance,
+        memo: Option<&'a str>,
+    },
+    Burn {
+        owner_id: &'a str,
+        amount: Balance,
+        memo: Option<&'a str>,
+    },
+    Transfer {
+        old_owner_id: &'a str,
+        new_owner_id: &'a str,
+        amount: Balance,
+        memo: Option<&'a str>,
+    },
+}
+
+impl<'a> FtEvent<'a> {
+    pub fn emit(&self) {
+        Nep297Event::from(self).emit();
+    }
+}
+
+impl<'a> From<&FtEvent<'a>> for Nep297Event {
+    fn from(event: &FtEvent<'a>) -> Self {
+        let mut kvs = BTreeMap::new();
+        let (name, amount, memo) = match event {
+            FtEvent::Mint {
+                owner_id,
+                amount,
+                memo,
+            } => {
+                kvs.insert("owner_id".to_string(), json_string(owner_id));
+                ("ft_mint", amount, memo)
+            }
+            FtEvent::Burn {
+                owner_id,
+                amount,
+                memo,
+            } => {
+                kvs.insert("owner_id".to_string(), json_string(owner_id));
+                ("ft_burn", amount, memo)
+            }
+            FtEvent::Transfer {
+                old_owner_id,
+                new_owner_id,
+                amount,
+                memo,
+            } => {
+                kvs.insert("old_owner_id".to_string(), json_string(old_owner_id));
+                kvs.insert("new_owner_id".to_string(), json_string(new_owner_id));
+                ("ft_transfer", amount, memo)
+            }
+        };
+        kvs.insert("amount".to_string(), json_string(&amount.to_string()));
+        if let Some(memo) = memo {
+            kvs.insert("memo".to_string(), json_string(memo));
+        }
+
+        Self {
+            standard: NEP141_STANDARD,
+            version: NEP141_VERSION,
+            event: name,
+            data: JsonValue::Array(vec![JsonValue::Object(kvs)]),
+        }
+    }
+}
+
+/// Mints and burns of ETH on Aurora. They are not NEP-141 events, as their owners are
+/// Aurora addresses rather than NEAR accounts, so they are emitted under the `aurora`
+/// standard with the owner as a `0x`-prefixed hex `address`.
+pub enum EthOnAuroraEvent {
+    Mint {
+        address: EthAddress,
+        amount: Balance,
+    },
+    Burn {
+        address: EthAddress,
+        amount: Balance,
+    },
+}
+
+impl EthOnAuroraEvent {
+    pub fn emit(&self) {
+        Nep297Event::from(self).emit();
+    }
+}
+
+impl From<&EthOnAuroraEvent> for Nep297Event {
+    fn from(event: &EthOnAuroraEvent) -> Self {
+        let (name, address, amount) = match event {
+            EthOnAuroraEvent::Mint { address, amount } => ("eth_mint", address, amount),
+            EthOnAuroraEvent::Burn { address, amount } => ("eth_burn", address, amount),
+        };
+        let mut kvs = BTreeMap::new();
+        kvs.insert("address".to_string(), hex_string(address));
+        kvs.insert("amount".to_string(), json_string(&amount.to_string()));
+
+        Self {
+            standard: AURORA_STANDARD,
+            version: AURORA_VERSION,
+            event: name,
+            data: JsonValue::Array(vec![JsonValue::Object(kvs)]),
+        }
+    }
+}
+
+/// Event of a log emitted by an EVM execution
+pub struct EvmLogEvent<'a> {
+    pub log: &'a ResultLog,
+    /// Identifies the EVM execution: the transaction hash for `submit`, see
+    /// `random::ExecutionId` for the other executions.
+    pub execution_id: H256,
+    /// Position of the log within the logs of the transaction
+    pub log_index: usize,
+}
+
+impl<'a> EvmLogEvent<'a> {
+    /// Returns the NEAR log of the event, at most `MAX_EVM_LOG_EVENT_LENGTH` long.
+    ///
+    /// If the event does not fit, the log data is truncated to its longest prefix which
+    /// fits, and the event gets `"truncated": true` and the length of the full data in
+    /// bytes as `"data_length"`. All other fields are always complete.
+    pub fn to_log(&self) -> String {
+        let log = self.to_event(&self.log.data, false).to_log();
+        if log.len() <= MAX_EVM_LOG_EVENT_LENGTH {
+            return log;
+        }
+        let empty_log_length = self.to_event(&[], true).to_log().len();
+        // Each byte of data takes two hex digits
+        let data_length = (MAX_EVM_LOG_EVENT_LENGTH.saturating_sub(empty_log_length) / 2)
+            .min(self.log.data.len());
+        self.to_event(&self.log.data[..data_length], true).to_log()
+    }
+
+    pub fn emit(&self) {
+        sdk::log(&self.to_log());
+    }
+
+    fn to_event(&self, data: &[u8], truncated: bool) -> Nep297Event {
+        let mut kvs = BTreeMap::new();
+        kvs.insert("address".to_string(), hex_string(&self.log.address));
+        kvs.insert(
+            "topics".to_string(),
+            JsonValue::Array(
+                self.log
+                    .topics
+                    .iter()
+                    .map(|topic| hex_string(topic))
+                    .collect::<Vec<_>>(),
+            ),
+        );
+        kvs.insert("data".to_string(), hex_string(data));
+        kvs.insert(
+            "execution_id".to_string(),
+            hex_string(self.execution_id.as_bytes()),
+        );
+        kvs.insert(
+            "log_index".to_string(),
+            JsonValue::U64(self.log_index as u64),
+        );
+        if truncated {
+            kvs.insert("truncated".to_string(), JsonValue::Bool(true));
+            kvs.insert(
+                "data_length".to_string(),
+                JsonValue::U64(self.log.data.len() as u64),
+            );
+        }
+
+        Nep297Event {
+            standard: AURORA_STANDARD,
+            version: AURORA_VERSION,
+            event: "evm_log",
+            data: JsonValue::Array(vec![JsonValue::Object(kvs)]),
+        }
+    }
+}
+
+/// Emits the events of the logs of an EVM execution, up to `MAX_EVM_LOG_EVENTS`,
+/// followed by an `evm_logs_dropped` event if there are more logs.
+pub fn emit_evm_log_events(execution_id: H256, logs: &[ResultLog]) {
+    for (log_index, log) in logs.iter().enumerate().take(MAX_EVM_LOG_EVENTS) {
+        EvmLogEvent {
+            log,
+            execution_id,
+            log_index,
+        }
+        .emit();
+    }
+    if logs.len() > MAX_EVM_LOG_EVENTS {
+        evm_logs_dropped_event(execution_id, logs.len() - MAX_EVM_LOG_EVENTS).emit();
+    }
+}
+
+/// Event giving the number of logs of an EVM execution which have no `evm_log` event
+fn evm_logs_dropped_event(execution_id: H256, dropped: usize) -> Nep297Event {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "execution_id".to_string(),
+        hex_string(execution_id.as_bytes()),
+    );
+    kvs.insert("dropped".to_string(), JsonValue::U64(dropped as u64));
+
+    Nep297Event {
+        standard: AURORA_STANDARD,
+        version: AURORA_VERSION,
+        event: "evm_logs_dropped",
+        data: JsonValue::Array(vec![JsonValue::Object(kvs)]),
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> JsonValue {
+    JsonValue::String(["0x", &hex::encode(bytes)].concat())
+}
+
+/// JSON string with its special characters escaped, as `JsonValue` writes strings as is.
+pub fn json_string(value: &str) -> JsonValue {
+    JsonValue::String(escape_json(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::parse_json;
+
+    #[test]
+    fn test_ft_event_log() {
+        let event = FtEvent::Transfer {
+            old_owner_id: "alice.near",
+            new_owner_id: "bob.near",
+            amount: 42,
+            memo: Some("say \"hi\""),
+        };
+        let log = Nep297Event::from(&event).to_log();
+        assert_eq!(
+            log,
+            "EVENT_JSON:{\"standard\": \"nep141\", \"version\": \"1.0.0\", \"event\": \"ft_transfer\", \
+             \"data\": [{\"amount\": \"42\", \"memo\": \"say \\\"hi\\\"\", \
+             \"new_owner_id\": \"bob.near\", \"old_owner_id\": \"alice.near\"}]}"
+        );
+        assert!(parse_json(log.strip_prefix(EVENT_JSON_PREFIX).unwrap().as_bytes()).is_some());
+
+        // Control characters are escaped as well
+        let event = FtEvent::Burn {
+            owner_id: "alice.near",
+            amount: 1,
+            memo: Some("line\nbreak\u{1}"),
+        };
+        let log = Nep297Event::from(&event).to_log();
+        assert!(log.contains(r#""memo": "line\nbreak\u0001""#));
+        assert!(parse_json(log.strip_prefix(EVENT_JSON_PREFIX).unwrap().as_bytes()).is_some());
+
+        let event = FtEvent::Mint {
+            owner_id: "alice.near",
+            amount: 7,
+            memo: None,
+        };
+        assert_eq!(
+            Nep297Event::from(&event).to_log(),
+            "EVENT_JSON:{\"standard\": \"nep141\", \"version\": \"1.0.0\", \"event\": \"ft_mint\", \
+             \"data\": [{\"amount\": \"7\", \"owner_id\": \"alice.near\"}]}"
+        );
+    }
+
+    #[test]
+    fn test_eth_on_aurora_event_log() {
+        let event = EthOnAuroraEvent::Mint {
+            address: [1; 20],
+            amount: 7,
+        };
+        assert_eq!(
+            Nep297Event::from(&event).to_log(),
+            format!(
+                "EVENT_JSON:{{\"standard\": \"aurora\", \"version\": \"1.0.0\", \
+                 \"event\": \"eth_mint\", \"data\": [{{\"address\": \"0x{}\", \
+                 \"amount\": \"7\"}}]}}",
+                "01".repeat(20),
+            )
+        );
+
+        let event = EthOnAuroraEvent::Burn {
+            address: [1; 20],
+            amount: 7,
+        };
+        let log = Nep297Event::from(&event).to_log();
+        assert!(log.contains("\"event\": \"eth_burn\""));
+        assert!(parse_json(log.strip_prefix(EVENT_JSON_PREFIX).unwrap().as_bytes()).is_some());
+    }
+
+    #[test]
+    fn test_evm_log_event_log() {
+        let log = ResultLog {
+            address: [1; 20],
+            topics: vec![[2; 32]],
+            data: vec![0xab; 4],
+        };
+        let event = EvmLogEvent {
+            log: &log,
+            execution_id: H256::repeat_byte(3),
+            log_index: 5,
+        };
+        assert_eq!(
+            event.to_log(),
+            format!(
+                "EVENT_JSON:{{\"standard\": \"aurora\", \"version\": \"1.0.0\", \
+                 \"event\": \"evm_log\", \"data\": [{{\"address\": \"0x{}\", \
+                 \"data\": \"0xabababab\", \"execution_id\": \"0x{}\", \"log_index\": 5, \
+                 \"topics\": [\"0x{}\"]}}]}}",
+                "01".repeat(20),
+                "03".repeat(32),
+                "02".repeat(32),
+            )
+        );
+
+        let log = ResultLog {
+            data: vec![0xab; MAX_EVM_LOG_EVENT_LENGTH],
+            ..log
+        };
+        let event = EvmLogEvent {
+            log: &log,
+            execution_id: H256::repeat_byte(3),
+            log_index: 5,
+        };
+        let truncated = event.to_log();
+        assert!(truncated.len() <= MAX_EVM_LOG_EVENT_LENGTH);
+        assert!(truncated.len() > MAX_EVM_LOG_EVENT_LENGTH - 2);
+        assert!(truncated.contains("\"truncated\": true"));
+        assert!(truncated.contains(&format!("\"data_length\": {}", MAX_EVM_LOG_EVENT_LENGTH)));
+        assert!(parse_json(
+            truncated
+                .strip_prefix(EVENT_JSON_PREFIX)
+                .unwrap()
+                .as_bytes()
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn test_evm_logs_dropped_event_log() {
+        assert_eq!(
+            evm_logs_dropped_event(H256::repeat_byte(3), 2).to_log(),
+            format!(
+                "EVENT_JSON:{{\"standard\": \"aurora\", \"version\": \"1.0.0\", \
+                 \"event\": \"evm_logs_dropped\", \"data\": [{{\"dropped\": 2, \
+                 \"execution_id\": \"0x{}\"}}]}}",
+                "03".repeat(32),
+            )
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::connector::ZERO_ATTACHED_BALANCE;
 use crate::engine;
+use crate::events::{EthOnAuroraEvent, FtEvent};
 use crate::json::{parse_json, JsonValue};
 use crate::parameters::{NEP141FtOnTransferArgs, ResolveTransferCallArgs, StorageBalance};
 use crate::prelude::account_id::AccountId;
@@ -157,6 +158,21 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         &mut self,
         account_id: &AccountId,
         amount: Balance,
+    ) -> Result<(), error::DepositError> {
+        self.deposit_eth_to_near(account_id, amount)?;
+        FtEvent::Mint {
+            owner_id: account_id.as_ref(),
+            amount,
+            memo: None,
+        }
+        .emit();
+        Ok(())
+    }
+
+    fn deposit_eth_to_near(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance,
     ) -> Result<(), error::DepositError> {
         let balance = self.get_account_eth_balance(account_id).unwrap_or(0);
         let new_balance = balance
@@ -191,6 +207,7 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
             .total_eth_supply_on_aurora
             .checked_add(amount)
             .ok_or(error::DepositError::TotalSupplyOverflow)?;
+        EthOnAuroraEvent::Mint { address, amount }.emit();
         Ok(())
     }
 
@@ -199,6 +216,21 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         &mut self,
         account_id: &AccountId,
         amount: Balance,
+    ) -> Result<(), error::WithdrawError> {
+        self.withdraw_eth_from_near(account_id, amount)?;
+        FtEvent::Burn {
+            owner_id: account_id.as_ref(),
+            amount,
+            memo: None,
+        }
+        .emit();
+        Ok(())
+    }
+
+    fn withdraw_eth_from_near(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance,
     ) -> Result<(), error::WithdrawError> {
         let balance = self.get_account_eth_balance(account_id).unwrap_or(0);
         let new_balance = balance
@@ -233,6 +265,7 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
             .total_eth_supply_on_aurora
             .checked_sub(amount)
             .ok_or(error::WithdrawError::TotalSupplyUnderflow)?;
+        EthOnAuroraEvent::Burn { address, amount }.emit();
         Ok(())
     }
 
@@ -242,7 +275,7 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         sender_id: &AccountId,
         receiver_id: &AccountId,
         amount: Balance,
-        #[allow(unused_variables)] memo: &Option<String>,
+        memo: &Option<String>,
     ) -> Result<(), error::TransferError> {
         if sender_id == receiver_id {
             return Err(error::TransferError::SelfTransfer);
@@ -258,8 +291,15 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
             // if it does not exist.
             self.internal_register_account(receiver_id)
         }
-        self.internal_withdraw_eth_from_near(sender_id, amount)?;
-        self.internal_deposit_eth_to_near(receiver_id, amount)?;
+        self.withdraw_eth_from_near(sender_id, amount)?;
+        self.deposit_eth_to_near(receiver_id, amount)?;
+        FtEvent::Transfer {
+            old_owner_id: sender_id.as_ref(),
+            new_owner_id: receiver_id.as_ref(),
+            amount,
+            memo: memo.as_deref(),
+        }
+        .emit();
         sdk::log!(&crate::prelude::format!(
             "Transfer {} from {} to {}",
             amount,
@@ -389,6 +429,13 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
 
                 return if let Some(sender_balance) = self.get_account_eth_balance(sender_id) {
                     self.accounts_insert(sender_id, sender_balance + refund_amount);
+                    FtEvent::Transfer {
+                        old_owner_id: receiver_id.as_ref(),
+                        new_owner_id: sender_id.as_ref(),
+                        amount: refund_amount,
+                        memo: Some("refund"),
+                    }
+                    .emit();
                     sdk::log!(&crate::prelude::format!(
                         "Refund amount {} from {} to {}",
                         refund_amount,
@@ -399,6 +446,12 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
                 } else {
                     // Sender's account was deleted, so we need to burn tokens.
                     self.total_eth_supply_on_near -= refund_amount;
+                    FtEvent::Burn {
+                        owner_id: receiver_id.as_ref(),
+                        amount: refund_amount,
+                        memo: Some("refund"),
+                    }
+                    .emit();
                     sdk::log!("The account of the sender was deleted");
                     (amount, refund_amount)
                 };
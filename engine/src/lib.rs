@@ -22,6 +22,7 @@ pub mod admin_controlled;
 pub mod connector;
 pub mod deposit_event;
 pub mod engine;
+pub mod events;
 pub mod fungible_token;
 pub mod json;
 pub mod log_entry;
@@ -67,13 +68,16 @@ mod contract {
     use borsh::{BorshDeserialize, BorshSerialize};
 
     use crate::connector::{self, EthConnectorContract};
-    use crate::engine::{self, current_address, Engine, EngineState};
+    use crate::engine::{self, CallWithNearError, Engine, EngineState};
     use crate::fungible_token::FungibleTokenMetadata;
     use crate::parameters::{
-        self, CallArgs, DeployErc20TokenArgs, GetErc20FromNep141CallArgs, GetStorageAtArgs,
-        InitCallArgs, IsUsedProofCallArgs, NEP141FtOnTransferArgs, NewCallArgs,
+        self, CallArgs, ConnectorRole, ConnectorRoleCallArgs, DeployErc20TokenArgs,
+        DeployErc721TokenArgs, Erc20Metadata, GetErc20FromNep141CallArgs,
+        GetErc721FromNep171CallArgs, GetStorageAtArgs, InitCallArgs, IsUsedProofCallArgs,
+        NEP141FtOnTransferArgs, NEP171NftOnTransferArgs, NewCallArgs, NftToken,
         PauseEthConnectorCallArgs, ResolveTransferCallArgs, SetContractDataCallArgs,
-        StorageDepositCallArgs, StorageWithdrawCallArgs, TransferCallCallArgs, ViewCallArgs,
+        SetMetadataCallArgs, SetWnearAddressArgs, StorageDepositCallArgs, StorageWithdrawCallArgs,
+        SyncErc20MetadataArgs, TransferCallCallArgs, ViewCallArgs, WrappedNearCallArgs,
     };
     #[cfg(feature = "evm_bully")]
     use crate::parameters::{BeginBlockArgs, BeginChainArgs};
@@ -84,7 +88,10 @@ mod contract {
     use aurora_engine_types::account_id::AccountId;
 
     use crate::json::parse_json;
-    use crate::prelude::parameters::RefundCallArgs;
+    use crate::prelude::parameters::{
+        CrossContractCallPolicy, DeployXccRouterArgs, EvmCallbackCallArgs, RefundCallArgs,
+        RefundNftCallArgs, SetPrecompileStatusArgs, XccRouterDeployedArgs,
+    };
     use crate::prelude::sdk::types::{
         near_account_to_evm_address, SdkExpect, SdkProcess, SdkUnwrap,
     };
@@ -187,12 +194,58 @@ mod contract {
         Runtime::self_deploy(&bytes_to_key(KeyPrefix::Config, CODE_KEY));
     }
 
+    /// Sets the limits on promises created by the cross-contract call precompile.
+    #[no_mangle]
+    pub extern "C" fn set_xcc_policy() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let policy: CrossContractCallPolicy = io.read_input_borsh().sdk_unwrap();
+        aurora_engine_precompiles::cross_contract::set_policy(&mut io, &policy);
+    }
+
+    /// Sets the code of the router contract deployed by `deploy_xcc_router`. Input is the
+    /// contract code. Routers which are already deployed keep their code.
+    #[no_mangle]
+    pub extern "C" fn set_xcc_router_code() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let code = io.read_input().to_vec();
+        aurora_engine_precompiles::cross_contract::set_router_code(&mut io, &code);
+    }
+
+    /// Sets the EVM gas charged per Tgas of NEAR gas attached to or burnt by the
+    /// promise-creating precompiles. Input is the Borsh-encoded `u64` ratio, which must not
+    /// be zero.
+    #[no_mangle]
+    pub extern "C" fn set_near_gas_ratio() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let eth_gas_per_tgas: u64 = io.read_input_borsh().sdk_unwrap();
+        aurora_engine_precompiles::near_gas::set_eth_gas_per_tgas(&mut io, eth_gas_per_tgas)
+            .sdk_unwrap();
+    }
+
+    /// Enables or disables a precompile. A disabled precompile either behaves like an account
+    /// without code or reverts, depending on the mode it was disabled with.
+    #[no_mangle]
+    pub extern "C" fn set_precompile_status() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let args: SetPrecompileStatusArgs = io.read_input_borsh().sdk_unwrap();
+        aurora_engine_precompiles::registry::set_status(&mut io, &args);
+    }
+
     /// Called as part of the upgrade process (see `engine-sdk::self_deploy`). This function is meant
     /// to make any necessary changes to the state such that it aligns with the newly deployed
     /// code.
     #[no_mangle]
     pub extern "C" fn state_migration() {
-        // TODO: currently we don't have migrations
+        let mut io = Runtime;
+        engine::migrate_state(&mut io).sdk_unwrap();
     }
 
     ///
@@ -221,7 +274,7 @@ mod contract {
     /// Call method on the EVM contract.
     #[no_mangle]
     pub extern "C" fn call() {
-        let io = Runtime;
+        let mut io = Runtime;
         let bytes = io.read_input().to_vec();
         let args = CallArgs::deserialize(&bytes).sdk_expect("ERR_BORSH_DESERIALIZE");
         let current_account_id = io.current_account_id();
@@ -232,9 +285,22 @@ mod contract {
             &io,
         )
         .sdk_unwrap();
-        Engine::call_with_args(&mut engine, args, &mut Runtime)
-            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
-            .sdk_process();
+        let attached_deposit = io.attached_deposit();
+        if attached_deposit == 0 {
+            Engine::call_with_args(&mut engine, args, &mut Runtime)
+                .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+                .sdk_process();
+        } else {
+            // Attached NEAR is only accepted if it can be wrapped into wNEAR for the caller,
+            // which the `call_with_wrapped_near` callback does before making the call.
+            let wnear = engine::get_wnear_address(&io)
+                .ok_or(CallWithNearError::WnearNotSet)
+                .sdk_unwrap();
+            let promise_id = engine
+                .call_with_attached_near(args, wnear, attached_deposit, &mut Runtime)
+                .sdk_unwrap();
+            io.promise_return(promise_id);
+        }
         // TODO: charge for storage
     }
 
@@ -295,6 +361,21 @@ mod contract {
             .sdk_process();
     }
 
+    /// Meta-call signed according to EIP-712 with Aurora's domain, see
+    /// `meta_parsing::prepare_meta_call_v2_args`. It is made from the ECDSA signer, or
+    /// from an explicit sender which signed it, possibly as an EIP-1271 contract. The fee
+    /// is paid to the fee address before the call and stays paid if the call reverts,
+    /// while an engine error aborts the whole meta-call.
+    #[cfg(feature = "meta-call")]
+    #[no_mangle]
+    pub extern "C" fn meta_call_v2() {
+        let io = Runtime;
+        let input = io.read_input().to_vec();
+        engine::meta_call_v2(io, &io, input, &mut Runtime)
+            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+            .sdk_process();
+    }
+
     #[no_mangle]
     pub extern "C" fn register_relayer() {
         let io = Runtime;
@@ -312,6 +393,54 @@ mod contract {
         engine.register_relayer(predecessor_account_id.as_bytes(), Address(relayer_address));
     }
 
+    /// Sets the wNEAR ERC-20 which NEAR attached to `call` is wrapped into.
+    /// Attached NEAR is not wrapped while unset.
+    #[no_mangle]
+    pub extern "C" fn set_wnear_address() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let args: SetWnearAddressArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_wnear_address(&mut io, args.address.map(Address));
+    }
+
+    /// Enables or disables writing EVM logs to the NEAR logs as NEP-297 events.
+    #[no_mangle]
+    pub extern "C" fn set_evm_log_events() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let args: parameters::SetEvmLogEventsArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_evm_log_events(&mut io, args.enabled);
+    }
+
+    /// Sets whether transactions without EIP-155 replay protection are accepted.
+    /// Input is the Borsh-encoded `UnprotectedTransactionsPolicy`.
+    #[no_mangle]
+    pub extern "C" fn set_unprotected_transactions_policy() {
+        let mut io = Runtime;
+        let mut state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        state.unprotected_transactions_policy = io.read_input_borsh().sdk_unwrap();
+        engine::set_state(&mut io, state);
+    }
+
+    /// Adds or removes an unprotected transaction, by hash, from the transactions accepted
+    /// when unprotected transactions are rejected.
+    #[no_mangle]
+    pub extern "C" fn set_unprotected_transaction_allowed() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let args: parameters::SetUnprotectedTransactionAllowedArgs =
+            io.read_input_borsh().sdk_unwrap();
+        engine::set_unprotected_transaction_allowed(
+            &mut io,
+            &H256(args.transaction_hash),
+            args.allowed,
+        );
+    }
+
     /// Allow receiving NEP141 tokens to the EVM contract.
     ///
     /// This function returns the amount of tokens to return to the sender.
@@ -365,8 +494,190 @@ mod contract {
         // TODO: charge for storage
     }
 
+    /// Deploy ERC721 token mapped to a NEP171
+    #[no_mangle]
+    pub extern "C" fn deploy_erc721_token() {
+        let mut io = Runtime;
+        // Id of the NEP171 token in Near
+        let args: DeployErc721TokenArgs = io.read_input_borsh().sdk_unwrap();
+
+        let address = engine::deploy_erc721_token(args, io, &io, &mut Runtime).sdk_unwrap();
+
+        io.return_output(&address.as_bytes().try_to_vec().sdk_expect("ERR_SERIALIZE"));
+
+        // TODO: charge for storage
+    }
+
+    /// Allow receiving NEP171 tokens to the EVM contract.
+    ///
+    /// The message is the recipient address in hex (40 characters). The token is
+    /// returned to its previous owner if the message is invalid, the NEP171 has no
+    /// ERC721 deployed, or minting the ERC721 token fails.
+    #[no_mangle]
+    pub extern "C" fn nft_on_transfer() {
+        let mut io = Runtime;
+        let nep171 = io.predecessor_account_id();
+        let args: NEP171NftOnTransferArgs = parse_json(io.read_input().to_vec().as_slice())
+            .sdk_unwrap()
+            .try_into()
+            .sdk_unwrap();
+
+        let recipient = match parse_nft_recipient(&args.msg) {
+            Some(recipient) => recipient,
+            None => {
+                sdk::log!("ERR_INVALID_NFT_RECIPIENT");
+                io.return_output(b"true");
+                return;
+            }
+        };
+        if engine::get_erc721_from_nep171(&io, &nep171).is_err() {
+            sdk::log!("ERR_NEP171_NOT_FOUND");
+            io.return_output(b"true");
+            return;
+        }
+
+        let promise = engine::nft_on_transfer_promise(
+            parameters::NftOnTransferCallbackArgs {
+                nep171,
+                token_id: args.token_id,
+                recipient,
+            },
+            io.current_account_id(),
+        );
+        let promise_id = io.promise_crate_with_callback(&promise);
+        io.promise_return(promise_id);
+    }
+
+    /// Mints the ERC721 token for a received NEP171 token once its metadata is fetched.
+    ///
+    /// Returns `false` so the NEP171 token is kept by the engine; failing here returns
+    /// the token to its previous owner.
+    #[no_mangle]
+    pub extern "C" fn nft_on_transfer_callback() {
+        let mut io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+
+        let args: parameters::NftOnTransferCallbackArgs = io.read_input_borsh().sdk_unwrap();
+        let token = match io.promise_result(0) {
+            Some(PromiseResult::Successful(bytes)) => {
+                NftToken::try_from(parse_json(&bytes).sdk_unwrap()).sdk_unwrap()
+            }
+            _ => sdk::panic_utf8(b"ERR_PROMISE_FAILED"),
+        };
+        if token.token_id != args.token_id {
+            sdk::panic_utf8(b"ERR_NFT_TOKEN_MISMATCH");
+        }
+
+        engine::mint_erc721(
+            &args.nep171,
+            token,
+            Address(args.recipient),
+            io,
+            &io,
+            &mut Runtime,
+        )
+        .sdk_unwrap();
+        io.return_output(b"false");
+    }
+
+    /// Fetches the metadata of a bridged NEP-141 token again and applies it to its ERC-20.
+    #[no_mangle]
+    pub extern "C" fn sync_erc20_metadata() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let args: SyncErc20MetadataArgs = io.read_input_borsh().sdk_unwrap();
+        // Fail early for tokens which were never bridged.
+        engine::get_erc20_from_nep141(&io, &args.nep141).sdk_unwrap();
+
+        let promise_args =
+            engine::sync_erc20_metadata_promise(args.nep141, io.current_account_id());
+        let promise_id = io.promise_crate_with_callback(&promise_args);
+        io.promise_return(promise_id);
+    }
+
+    /// Callback of the `ft_metadata` promise created by `deploy_erc20_token` and
+    /// `sync_erc20_metadata`.
+    #[no_mangle]
+    pub extern "C" fn sync_erc20_metadata_callback() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+
+        let args: SyncErc20MetadataArgs = io.read_input_borsh().sdk_unwrap();
+        let metadata = match io.promise_result(0) {
+            Some(PromiseResult::Successful(bytes)) => {
+                Erc20Metadata::try_from(parse_json(&bytes).sdk_unwrap()).sdk_unwrap()
+            }
+            _ => sdk::panic_utf8(b"ERR_PROMISE_FAILED"),
+        };
+        engine::set_erc20_metadata(&args.nep141, metadata, io, &io, &mut Runtime).sdk_unwrap();
+    }
+
+    /// Callback invoked by the exit NFT to NEAR precompile, which mints the ERC-721 token
+    /// again to its owner if `nft_transfer` failed.
+    #[no_mangle]
+    pub extern "C" fn refund_nft_on_error() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+
+        let args: RefundNftCallArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_result = io.promise_result(0).unwrap_or(PromiseResult::Failed);
+        engine::refund_nft_on_error(args, promise_result, io, &io, &mut Runtime).sdk_unwrap();
+    }
+
+    /// Callback of the `near_deposit` promise created by `call` with attached NEAR, which
+    /// mints the wrapped NEAR and makes the call, or refunds the NEAR if it was not wrapped.
+    #[no_mangle]
+    pub extern "C" fn call_with_wrapped_near() {
+        let mut io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+
+        let args: WrappedNearCallArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_result = io.promise_result(0).unwrap_or(PromiseResult::Failed);
+        let current_account_id = io.current_account_id();
+        let mut engine =
+            Engine::new(Address(args.origin), current_account_id, io, &io).sdk_unwrap();
+        let result = engine
+            .call_with_wrapped_near(args, promise_result, &mut Runtime)
+            .sdk_unwrap();
+        match result {
+            Some(Ok(result)) => io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE")),
+            // The minted wNEAR is backed by the wrapped NEAR, so it is kept when the call fails
+            Some(Err(_e)) => {
+                #[cfg(feature = "log")]
+                sdk::log(crate::prelude::format!("{:?}", _e).as_str());
+            }
+            None => (),
+        }
+    }
+
     /// Callback invoked by exit to NEAR precompile to handle potential
-    /// errors in the exit call.
+    /// errors in the exit call. Also refunds the amount `ft_transfer_call`
+    /// returned unused, if any.
     #[no_mangle]
     pub extern "C" fn refund_on_error() {
         let io = Runtime;
@@ -378,66 +689,137 @@ mod contract {
             sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
         }
 
-        if let Some(PromiseResult::Successful(_)) = io.promise_result(0) {
-            // Promise succeeded -- nothing to do
-        } else {
-            // Exit call failed; need to refund tokens
-
-            let current_account_id = io.current_account_id();
-            let args: RefundCallArgs = io.read_input_borsh().sdk_unwrap();
-            let refund_result = match args.erc20_address {
-                // ERC-20 exit; re-mint burned tokens
-                Some(erc20_address) => {
-                    let erc20_admin_address = current_address(&current_account_id);
-                    let mut engine =
-                        Engine::new(erc20_admin_address, current_account_id, io, &io).sdk_unwrap();
-                    let erc20_address = Address(erc20_address);
-                    let refund_address = Address(args.recipient_address);
-                    let amount = U256::from_big_endian(&args.amount);
-
-                    let selector = ERC20_MINT_SELECTOR;
-                    let mint_args = ethabi::encode(&[
-                        ethabi::Token::Address(refund_address),
-                        ethabi::Token::Uint(amount),
-                    ]);
-
-                    engine
-                        .call(
-                            erc20_admin_address,
-                            erc20_address,
-                            Wei::zero(),
-                            [selector, mint_args.as_slice()].concat(),
-                            u64::MAX,
-                            Vec::new(),
-                            &mut Runtime,
-                        )
-                        .sdk_unwrap()
-                }
-                // ETH exit; transfer ETH back from precompile address
-                None => {
-                    let exit_address = aurora_engine_precompiles::native::ExitToNear::ADDRESS;
-                    let mut engine =
-                        Engine::new(exit_address, current_account_id, io, &io).sdk_unwrap();
-                    let refund_address = Address(args.recipient_address);
-                    let amount = Wei::new(U256::from_big_endian(&args.amount));
-                    engine
-                        .call(
-                            exit_address,
-                            refund_address,
-                            amount,
-                            Vec::new(),
-                            u64::MAX,
-                            vec![(exit_address, Vec::new()), (refund_address, Vec::new())],
-                            &mut Runtime,
-                        )
-                        .sdk_unwrap()
+        let args: RefundCallArgs = io.read_input_borsh().sdk_unwrap();
+        let exit_amount = U256::from_big_endian(&args.amount);
+        let amount = match io.promise_result(0) {
+            // `ft_transfer` returns nothing, while `ft_transfer_call` returns the
+            // amount used by the receiver. The rest was returned to this account.
+            Some(PromiseResult::Successful(bytes)) => {
+                match parse_json(&bytes).and_then(|x| u128::try_from(&x).ok()) {
+                    Some(used_amount) => exit_amount.saturating_sub(U256::from(used_amount)),
+                    None => U256::zero(),
                 }
-            };
+            }
+            // Exit call failed; need to refund tokens
+            _ => exit_amount,
+        };
+        if amount.is_zero() {
+            return;
+        }
 
-            if !refund_result.status.is_ok() {
-                sdk::panic_utf8(b"ERR_REFUND_FAILURE");
+        let current_account_id = io.current_account_id();
+        let refund_result = match args.erc20_address {
+            // ERC-20 exit; re-mint burned tokens
+            Some(erc20_address) => {
+                let erc20_admin_address = current_address(&current_account_id);
+                let mut engine =
+                    Engine::new(erc20_admin_address, current_account_id, io, &io).sdk_unwrap();
+                let erc20_address = Address(erc20_address);
+                let refund_address = Address(args.recipient_address);
+
+                let selector = ERC20_MINT_SELECTOR;
+                let mint_args = ethabi::encode(&[
+                    ethabi::Token::Address(refund_address),
+                    ethabi::Token::Uint(amount),
+                ]);
+
+                engine
+                    .call(
+                        erc20_admin_address,
+                        erc20_address,
+                        Wei::zero(),
+                        [selector, mint_args.as_slice()].concat(),
+                        u64::MAX,
+                        Vec::new(),
+                        &mut Runtime,
+                    )
+                    .sdk_unwrap()
+            }
+            // ETH exit; transfer ETH back from precompile address
+            None => {
+                let exit_address = aurora_engine_precompiles::native::ExitToNear::ADDRESS;
+                let mut engine =
+                    Engine::new(exit_address, current_account_id, io, &io).sdk_unwrap();
+                let refund_address = Address(args.recipient_address);
+                let amount = Wei::new(amount);
+                engine
+                    .call(
+                        exit_address,
+                        refund_address,
+                        amount,
+                        Vec::new(),
+                        u64::MAX,
+                        vec![(exit_address, Vec::new()), (refund_address, Vec::new())],
+                        &mut Runtime,
+                    )
+                    .sdk_unwrap()
             }
+        };
+
+        if !refund_result.status.is_ok() {
+            sdk::panic_utf8(b"ERR_REFUND_FAILURE");
+        }
+    }
+
+    /// Creates the router account which executes the cross-contract calls of an EVM address.
+    /// The attached NEAR funds the router and must cover the storage of the router code.
+    #[no_mangle]
+    pub extern "C" fn deploy_xcc_router() {
+        let mut io = Runtime;
+        let args: DeployXccRouterArgs = io.read_input_borsh().sdk_unwrap();
+        let (batch, callback) = engine::deploy_xcc_router_promise(
+            io,
+            Address(args.address),
+            io.predecessor_account_id(),
+            io.attached_deposit(),
+            &io.current_account_id(),
+        )
+        .sdk_unwrap();
+        let base_id = io.promise_create_batch(&batch);
+        let promise_id = io.promise_attach_callback(base_id, &callback);
+        io.promise_return(promise_id);
+    }
+
+    /// Callback of `deploy_xcc_router`. Records the router, or refunds the payer if the
+    /// router account could not be created.
+    #[no_mangle]
+    pub extern "C" fn xcc_router_deployed() {
+        let mut io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
         }
+
+        let args: XccRouterDeployedArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_result = io.promise_result(0).unwrap_or(PromiseResult::Failed);
+        engine::xcc_router_deployed(args, promise_result, &mut io, &mut Runtime);
+    }
+
+    /// Callback invoked by the cross-contract call precompile to deliver the
+    /// result of a NEAR promise to the EVM contract which created it.
+    #[no_mangle]
+    pub extern "C" fn evm_callback() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+
+        let args: EvmCallbackCallArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_result = io.promise_result(0).sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let callback_address = aurora_engine_precompiles::cross_contract::ADDRESS;
+        let mut engine = Engine::new(callback_address, current_account_id, io, &io).sdk_unwrap();
+        engine
+            .receive_promise_result(args, promise_result, &mut Runtime)
+            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+            .sdk_process();
     }
 
     ///
@@ -459,6 +841,65 @@ mod contract {
         io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
+    #[no_mangle]
+    pub extern "C" fn get_xcc_policy() {
+        let mut io = Runtime;
+        let policy = aurora_engine_precompiles::cross_contract::get_policy(&io);
+        io.return_output(&policy.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_near_gas_ratio() {
+        let mut io = Runtime;
+        let eth_gas_per_tgas = aurora_engine_precompiles::near_gas::get_eth_gas_per_tgas(&io);
+        io.return_output(&eth_gas_per_tgas.to_le_bytes());
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_wnear_address() {
+        let mut io = Runtime;
+        let address = engine::get_wnear_address(&io).map(|address| address.0);
+        io.return_output(&address.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_unprotected_transactions_policy() {
+        let mut io = Runtime;
+        let state = engine::get_state(&io).sdk_unwrap();
+        io.return_output(
+            &state
+                .unprotected_transactions_policy
+                .try_to_vec()
+                .sdk_expect("ERR_SERIALIZE"),
+        );
+    }
+
+    /// Whether the unprotected transaction with the given 32-byte hash is allow-listed.
+    #[no_mangle]
+    pub extern "C" fn is_unprotected_transaction_allowed() {
+        let mut io = Runtime;
+        let input = io.read_input().to_vec();
+        if input.len() != 32 {
+            sdk::panic_utf8(b"ERR_INVALID_TX_HASH");
+        }
+        let allowed = engine::is_unprotected_transaction_allowed(&io, &H256::from_slice(&input));
+        io.return_output(&allowed.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_evm_log_events() {
+        let mut io = Runtime;
+        let enabled = engine::get_evm_log_events(&io);
+        io.return_output(&enabled.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_disabled_precompiles() {
+        let mut io = Runtime;
+        let disabled = aurora_engine_precompiles::registry::read_disabled(&io);
+        io.return_output(&disabled.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn get_block_hash() {
         let mut io = Runtime;
@@ -555,13 +996,36 @@ mod contract {
     #[no_mangle]
     pub extern "C" fn set_eth_connector_contract_data() {
         let mut io = Runtime;
-        // Only the owner can set the EthConnector contract data
-        io.assert_private_call().sdk_unwrap();
+        // Only the owner or a contract data manager can set the EthConnector contract data, as
+        // it includes the prover account and the custodian address
+        EthConnectorContract::init_instance(io)
+            .check_role(
+                ConnectorRole::ContractDataManager,
+                &io.predecessor_account_id(),
+                &io.current_account_id(),
+            )
+            .sdk_unwrap();
 
         let args: SetContractDataCallArgs = io.read_input_borsh().sdk_unwrap();
         connector::set_contract_data(&mut io, args).sdk_unwrap();
     }
 
+    #[no_mangle]
+    pub extern "C" fn set_eth_connector_metadata() {
+        let mut io = Runtime;
+        // Only the owner or a metadata manager can set the nETH metadata
+        EthConnectorContract::init_instance(io)
+            .check_role(
+                ConnectorRole::MetadataManager,
+                &io.predecessor_account_id(),
+                &io.current_account_id(),
+            )
+            .sdk_unwrap();
+
+        let args: SetMetadataCallArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_metadata(&mut io, &args);
+    }
+
     #[no_mangle]
     pub extern "C" fn withdraw() {
         let mut io = Runtime;
@@ -589,6 +1053,72 @@ mod contract {
         io.promise_return(promise_id);
     }
 
+    /// Deposit several proofs at once, see `EthConnectorContract::deposit_batch`.
+    /// Returns the borsh-encoded `Vec<DepositBatchStatus>` in the order of the proofs.
+    #[no_mangle]
+    pub extern "C" fn deposit_batch() {
+        let mut io = Runtime;
+        let args: parameters::DepositBatchCallArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let predecessor_account_id = io.predecessor_account_id();
+        let (verify_calls, finish_call) = EthConnectorContract::init_instance(io)
+            .deposit_batch(
+                args,
+                current_account_id,
+                predecessor_account_id,
+                io.prepaid_gas(),
+            )
+            .sdk_unwrap();
+
+        let verify_ids: Vec<_> = verify_calls
+            .iter()
+            .map(|verify_call| io.promise_create_call(verify_call))
+            .collect();
+        let promise_id = if verify_ids.is_empty() {
+            io.promise_create_call(&finish_call)
+        } else {
+            let verified = io.promise_and(&verify_ids);
+            io.promise_attach_callback(verified, &finish_call)
+        };
+        io.promise_return(promise_id);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn finish_deposit_batch() {
+        let mut io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        let args: parameters::FinishDepositBatchCallArgs = io.read_input_borsh().sdk_unwrap();
+        // Check results from proof verification calls
+        let verifying = args
+            .deposits
+            .iter()
+            .filter(|deposit| matches!(deposit, parameters::BatchedDeposit::Verifying(_)))
+            .count() as u64;
+        if io.promise_results_count() != verifying {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+        let verified = (0..verifying)
+            .map(|index| match io.promise_result(index) {
+                Some(PromiseResult::Successful(bytes)) => {
+                    bool::try_from_slice(&bytes).unwrap_or(false)
+                }
+                _ => false,
+            })
+            .collect();
+
+        let current_account_id = io.current_account_id();
+        let predecessor_account_id = io.predecessor_account_id();
+        let (statuses, promises) = EthConnectorContract::init_instance(io)
+            .finish_deposit_batch(predecessor_account_id, current_account_id, args, verified)
+            .sdk_unwrap();
+
+        for promise_args in promises.iter() {
+            io.promise_crate_with_callback(promise_args);
+        }
+        io.return_output(&statuses.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn finish_deposit() {
         let mut io = Runtime;
@@ -626,6 +1156,85 @@ mod contract {
         }
     }
 
+    /// Deposit ERC-20 tokens locked on Ethereum, see `EthConnectorContract::deposit_erc20`.
+    #[no_mangle]
+    pub extern "C" fn deposit_erc20() {
+        let mut io = Runtime;
+        let raw_proof = io.read_input().to_vec();
+        let current_account_id = io.current_account_id();
+        let predecessor_account_id = io.predecessor_account_id();
+        let promise_args = EthConnectorContract::init_instance(io)
+            .deposit_erc20(raw_proof, current_account_id, predecessor_account_id)
+            .sdk_unwrap();
+        let promise_id = io.promise_crate_with_callback(&promise_args);
+        io.promise_return(promise_id);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn finish_deposit_erc20() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        // Check result from proof verification call
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+        let promise_result = match io.promise_result(0) {
+            Some(PromiseResult::Successful(bytes)) => {
+                bool::try_from_slice(&bytes).sdk_expect("ERR_PROMISE_ENCODING")
+            }
+            _ => sdk::panic_utf8(b"ERR_PROMISE_FAILED"),
+        };
+        if !promise_result {
+            sdk::panic_utf8(b"ERR_VERIFY_PROOF");
+        }
+
+        let data: parameters::FinishDepositErc20CallArgs = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io)
+            .finish_deposit_erc20(&data)
+            .sdk_unwrap();
+        // A failed mint aborts the receipt, so the proof is not recorded either.
+        engine::mint_erc20(
+            Address(data.erc20),
+            Address(data.recipient),
+            U256::from_big_endian(&data.amount),
+            io,
+            &io,
+            &mut Runtime,
+        )
+        .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_erc20_locker() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        let args: parameters::SetErc20LockerCallArgs = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io).set_erc20_locker(args);
+    }
+
+    /// Register the Aurora ERC-20 minted for deposits of an Ethereum token. The ERC-20
+    /// must have the engine as its admin, as for the ERC-20s bridged from NEP-141 tokens.
+    #[no_mangle]
+    pub extern "C" fn register_bridged_erc20() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        let args: parameters::RegisterBridgedErc20CallArgs = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io)
+            .register_bridged_erc20(args)
+            .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_bridged_erc20() {
+        let mut io = Runtime;
+        let eth_token = io.read_input_arr20().sdk_unwrap();
+        let erc20 = EthConnectorContract::init_instance(io).get_bridged_erc20(&eth_token);
+        io.return_output(&erc20.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn is_used_proof() {
         let mut io = Runtime;
@@ -636,6 +1245,34 @@ mod contract {
         io.return_output(&res[..]);
     }
 
+    /// Move used proofs recorded under the legacy keys to the compact registry.
+    /// The legacy keys are passed in, as they can't be listed on chain.
+    #[no_mangle]
+    pub extern "C" fn migrate_used_proofs() {
+        let mut io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        let args: parameters::MigrateUsedProofsCallArgs = io.read_input_borsh().sdk_unwrap();
+        let migrated = EthConnectorContract::init_instance(io).migrate_used_proofs(args);
+        io.return_output(&migrated.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_proof_watermark() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        let args: parameters::SetProofWatermarkCallArgs = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io).set_proof_watermark(args);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_used_proofs_storage() {
+        let mut io = Runtime;
+        let storage = EthConnectorContract::init_instance(io).get_used_proofs_storage();
+        io.return_output(&storage.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn ft_total_supply() {
         let io = Runtime;
@@ -678,12 +1315,13 @@ mod contract {
         let io = Runtime;
         io.assert_one_yocto().sdk_unwrap();
         let predecessor_account_id = io.predecessor_account_id();
+        let current_account_id = io.current_account_id();
         let args = parameters::TransferCallArgs::try_from(
             parse_json(&io.read_input().to_vec()).sdk_unwrap(),
         )
         .sdk_unwrap();
         EthConnectorContract::init_instance(io)
-            .ft_transfer(&predecessor_account_id, args)
+            .ft_transfer(&predecessor_account_id, &current_account_id, args)
             .sdk_unwrap();
     }
 
@@ -787,11 +1425,66 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn set_paused_flags() {
+        let io = Runtime;
+        let args: PauseEthConnectorCallArgs = io.read_input_borsh().sdk_unwrap();
+        let mut connector = EthConnectorContract::init_instance(io);
+        connector
+            .check_paused_flags_roles(
+                args.paused_mask,
+                &io.predecessor_account_id(),
+                &io.current_account_id(),
+            )
+            .sdk_unwrap();
+        connector.set_paused_flags(args);
+    }
+
+    /// Grant a role of the eth-connector. Only the owner can grant roles.
+    #[no_mangle]
+    pub extern "C" fn grant_role() {
         let io = Runtime;
         io.assert_private_call().sdk_unwrap();
 
-        let args: PauseEthConnectorCallArgs = io.read_input_borsh().sdk_unwrap();
-        EthConnectorContract::init_instance(io).set_paused_flags(args);
+        let args: ConnectorRoleCallArgs = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io).grant_role(args);
+    }
+
+    /// Revoke a role of the eth-connector. Only the owner can revoke roles.
+    #[no_mangle]
+    pub extern "C" fn revoke_role() {
+        let io = Runtime;
+        io.assert_private_call().sdk_unwrap();
+
+        let args: ConnectorRoleCallArgs = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io).revoke_role(args);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_roles() {
+        let mut io = Runtime;
+        let roles = EthConnectorContract::init_instance(io).get_roles();
+        io.return_output(&roles.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_bridge_fees() {
+        let mut io = Runtime;
+        let bridge_fees = EthConnectorContract::init_instance(io).get_bridge_fees();
+        io.return_output(&bridge_fees.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_bridge_fees() {
+        let io = Runtime;
+        let args: parameters::SetBridgeFeesCallArgs = io.read_input_borsh().sdk_unwrap();
+        let mut connector = EthConnectorContract::init_instance(io);
+        connector
+            .check_role(
+                ConnectorRole::FeeManager,
+                &io.predecessor_account_id(),
+                &io.current_account_id(),
+            )
+            .sdk_unwrap();
+        connector.set_bridge_fees(args).sdk_unwrap();
     }
 
     #[no_mangle]
@@ -825,6 +1518,31 @@ mod contract {
         );
     }
 
+    #[no_mangle]
+    pub extern "C" fn get_erc721_from_nep171() {
+        let mut io = Runtime;
+        let args: GetErc721FromNep171CallArgs = io.read_input_borsh().sdk_unwrap();
+
+        io.return_output(
+            engine::get_erc721_from_nep171(&io, &args.nep171)
+                .sdk_unwrap()
+                .as_slice(),
+        );
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_nep171_from_erc721() {
+        let mut io = Runtime;
+        let erc721_address: crate::engine::ERC721Address =
+            io.read_input().to_vec().try_into().sdk_unwrap();
+        io.return_output(
+            engine::nep171_erc721_map(io)
+                .lookup_right(&erc721_address)
+                .sdk_expect("ERC721_NOT_FOUND")
+                .as_ref(),
+        );
+    }
+
     #[no_mangle]
     pub extern "C" fn ft_metadata() {
         let mut io = Runtime;
@@ -919,6 +1637,14 @@ mod contract {
     fn predecessor_address(predecessor_account_id: &AccountId) -> Address {
         near_account_to_evm_address(predecessor_account_id.as_bytes())
     }
+
+    /// Parses the recipient of a bridged NFT, given as 40 hex characters.
+    fn parse_nft_recipient(msg: &str) -> Option<[u8; 20]> {
+        if msg.len() != 40 {
+            return None;
+        }
+        hex::decode(msg).ok()?.as_slice().try_into().ok()
+    }
 }
 
 pub trait AuroraState {
@@ -1,8 +1,9 @@
-use crate::parameters::MetaCallArgs;
+use crate::parameters::{MetaCallArgs, MetaCallArgsV2};
 use crate::prelude::precompiles::secp256k1::ecrecover;
 use crate::prelude::{
-    keccak, u256_to_arr, vec, Address, BorshDeserialize, Box, HashMap, InternalMetaCallArgs,
-    RawU256, String, ToOwned, ToString, Vec, Wei, H256, U256,
+    format, keccak, u256_to_arr, vec, Address, BTreeMap, BorshDeserialize, Box, HashMap,
+    InternalMetaCallArgs, InternalMetaCallArgsV2, RawU256, String, ToOwned, ToString, Vec, Wei,
+    H256, U256,
 };
 use ethabi::{encode, Token as ABIToken};
 use logos::Logos;
@@ -62,12 +63,12 @@ mod type_lexer {
         let slice = lex.slice();
 
         if slice == prefix {
-            // the default int size is 32
-            return 32;
+            // the default int size is 256
+            return 256;
         }
 
         let n = slice[prefix.len()..].parse();
-        n.unwrap_or(32)
+        n.unwrap_or(256)
     }
 
     fn reference_type_size(lex: &mut Lexer<Token>) -> Option<u64> {
@@ -86,8 +87,10 @@ mod type_lexer {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArgType {
     Address,
-    Uint,
-    Int,
+    /// Unsigned integer of the given size in bits
+    Uint(usize),
+    /// Signed integer of the given size in bits
+    Int(usize),
     String,
     Bool,
     Bytes,
@@ -99,6 +102,17 @@ pub enum ArgType {
     },
 }
 
+impl ArgType {
+    /// Name of the struct type used by this type, directly or as an array element
+    fn struct_name(&self) -> Option<&str> {
+        match self {
+            ArgType::Custom(name) => Some(name.as_str()),
+            ArgType::Array { inner, .. } => inner.struct_name(),
+            _ => None,
+        }
+    }
+}
+
 /// the type string is being validated before it's parsed.
 /// field_type: A single evm function arg type in string, without the argument name
 /// e.g. "bytes" "uint256[][3]" "CustomStructName"
@@ -116,8 +130,8 @@ pub fn parse_type(field_type: &str) -> ParsingResult<ArgType> {
             Some(type_lexer::Token::Bytes) => ArgType::Bytes,
             Some(type_lexer::Token::Identifier) => ArgType::Custom(lexer.slice().to_owned()),
             Some(type_lexer::Token::FixedBytes(size)) => ArgType::Byte(size),
-            Some(type_lexer::Token::Int(_)) => ArgType::Int,
-            Some(type_lexer::Token::Uint(_)) => ArgType::Uint,
+            Some(type_lexer::Token::Int(size)) => ArgType::Int(size),
+            Some(type_lexer::Token::Uint(size)) => ArgType::Uint(size),
             Some(type_lexer::Token::ReferenceType(length)) => match inner_type {
                 None => return Err(ParsingError::ArgumentParseError),
                 Some(t) => ArgType::Array {
@@ -151,7 +165,25 @@ pub fn near_erc712_domain(chain_id: U256) -> RawU256 {
     keccak(&bytes).into()
 }
 
-/// method_sig: format like "adopt(uint256,PetObj)" (no additional PetObj definition)
+/// Aurora's domainSeparator for the second version of meta-calls, which also binds
+/// signatures to the engine through `verifyingContract`.
+pub fn aurora_eip712_domain(chain_id: U256, verifying_contract: Address) -> RawU256 {
+    let mut bytes = Vec::with_capacity(160);
+    bytes.extend_from_slice(
+        keccak(
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+                .as_bytes(),
+        )
+        .as_bytes(),
+    );
+    bytes.extend_from_slice(keccak(b"Aurora").as_bytes());
+    bytes.extend_from_slice(keccak(b"2").as_bytes());
+    bytes.extend_from_slice(&u256_to_arr(&chain_id));
+    bytes.extend_from_slice(&encode_address(verifying_contract));
+    keccak(&bytes).into()
+}
+
+/// method_sig: format like "adopt(uint256,(string,address))", with structs as tuples
 pub fn method_sig_to_abi(method_sig: &str) -> [u8; 4] {
     let mut result = [0u8; 4];
     result.copy_from_slice(&keccak(method_sig.as_bytes())[..4]);
@@ -346,6 +378,103 @@ fn method_signature(method_and_type: &MethodAndTypes) -> String {
     result
 }
 
+/// Return the ETH-ABI signature of the method, with struct arguments as tuples
+/// E.g. abi_method_signature(Methods before parse: "adopt(uint256 petId,PetObj petobj)PetObj(string name)")
+/// -> "adopt(uint256,(string))"
+fn abi_method_signature(method_and_type: &MethodAndTypes) -> ParsingResult<String> {
+    let mut result = method_and_type.method.name.clone();
+    result.push('(');
+    for (i, arg) in method_and_type.method.args.iter().enumerate() {
+        if i > 0 {
+            result.push(',');
+        }
+        abi_type(&arg.t, &method_and_type.types, &mut vec![], &mut result)?;
+    }
+    result.push(')');
+    Ok(result)
+}
+
+/// Append the canonical ETH-ABI type of ty to result. Structs are expanded to tuples,
+/// so a struct which contains itself, which the ABI cannot encode, is an error.
+fn abi_type<'a>(
+    ty: &'a ArgType,
+    types: &'a HashMap<String, Method>,
+    parents: &mut Vec<&'a str>,
+    result: &mut String,
+) -> ParsingResult<()> {
+    match ty {
+        ArgType::Address => result.push_str("address"),
+        ArgType::Uint(bits) => result.push_str(&format!("uint{}", bits)),
+        ArgType::Int(bits) => result.push_str(&format!("int{}", bits)),
+        ArgType::String => result.push_str("string"),
+        ArgType::Bool => result.push_str("bool"),
+        ArgType::Bytes => result.push_str("bytes"),
+        ArgType::Byte(size) => result.push_str(&format!("bytes{}", size)),
+        ArgType::Array { length, inner } => {
+            abi_type(inner, types, parents, result)?;
+            match length {
+                Some(length) => result.push_str(&format!("[{}]", length)),
+                None => result.push_str("[]"),
+            }
+        }
+        ArgType::Custom(type_name) => {
+            if parents.contains(&type_name.as_str()) {
+                return Err(ParsingError::InvalidMetaTransactionMethodName);
+            }
+            let struct_type = types
+                .get(type_name)
+                .ok_or(ParsingError::InvalidMetaTransactionMethodName)?;
+            parents.push(type_name);
+            result.push('(');
+            for (i, arg) in struct_type.args.iter().enumerate() {
+                if i > 0 {
+                    result.push(',');
+                }
+                abi_type(&arg.t, types, parents, result)?;
+            }
+            result.push(')');
+            parents.pop();
+        }
+    }
+    Ok(())
+}
+
+/// Collect the definitions of the struct types referenced by args, recursively, by name.
+fn collect_referenced_types<'a>(
+    args: &'a [Arg],
+    types: &'a HashMap<String, Method>,
+    referenced: &mut BTreeMap<&'a str, &'a str>,
+) -> ParsingResult<()> {
+    for arg in args {
+        if let Some(type_name) = arg.t.struct_name() {
+            let struct_type = types
+                .get(type_name)
+                .ok_or(ParsingError::InvalidMetaTransactionMethodName)?;
+            if referenced
+                .insert(&struct_type.name, &struct_type.raw)
+                .is_none()
+            {
+                collect_referenced_types(&struct_type.args, types, referenced)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// EIP-712 encodeType of a struct type: its definition followed by the definitions of the
+/// struct types it references, recursively, sorted by name.
+/// See https://eips.ethereum.org/EIPS/eip-712#definition-of-encodetype
+fn encode_type(struct_type: &Method, types: &HashMap<String, Method>) -> ParsingResult<String> {
+    let mut referenced = BTreeMap::new();
+    collect_referenced_types(&struct_type.args, types, &mut referenced)?;
+    referenced.remove(struct_type.name.as_str());
+    let mut result = struct_type.raw.clone();
+    for raw in referenced.values() {
+        result.push_str(raw);
+    }
+    Ok(result)
+}
+
 /// Decode rlp-encoded args into vector of Values
 fn rlp_decode(args: &[u8]) -> ParsingResult<Vec<RlpValue>> {
     let rlp = Rlp::new(args);
@@ -353,47 +482,151 @@ fn rlp_decode(args: &[u8]) -> ParsingResult<Vec<RlpValue>> {
     res.map_err(|_| ParsingError::InvalidMetaTransactionFunctionArg)
 }
 
+/// Decode an integer of the given size in bits from its big-endian bytes. Signed integers
+/// are given in two's complement, either on `bits` bits or already sign extended to 256
+/// bits, and are returned sign extended to 256 bits.
+fn decode_int(bytes: &[u8], bits: usize, signed: bool) -> ParsingResult<U256> {
+    if bytes.len() > 32 {
+        return Err(ParsingError::InvalidMetaTransactionFunctionArg);
+    }
+    let value = U256::from_big_endian(bytes);
+    if bits >= 256 {
+        return Ok(value);
+    }
+    let low_bits = value & ((U256::one() << bits) - 1);
+    let extended = if signed && low_bits.bit(bits - 1) {
+        low_bits | (U256::max_value() << bits)
+    } else {
+        low_bits
+    };
+    if value != low_bits && value != extended {
+        return Err(ParsingError::InvalidMetaTransactionFunctionArg);
+    }
+    Ok(extended)
+}
+
+/// Decode an address, which must be exactly 20 bytes
+fn decode_address(bytes: &[u8]) -> ParsingResult<Address> {
+    if bytes.len() != 20 {
+        return Err(ParsingError::InvalidMetaTransactionFunctionArg);
+    }
+    Ok(Address::from_slice(bytes))
+}
+
+/// Check a `bytesN` value is exactly `size` bytes
+fn check_fixed_bytes(bytes: &[u8], size: u8) -> ParsingResult<()> {
+    if bytes.len() != size as usize {
+        return Err(ParsingError::InvalidMetaTransactionFunctionArg);
+    }
+    Ok(())
+}
+
+/// Check a fixed-size array has the length of its type
+fn check_array_length(elements: &[RlpValue], length: &Option<u64>) -> ParsingResult<()> {
+    match length {
+        Some(length) if elements.len() as u64 != *length => {
+            Err(ParsingError::InvalidMetaTransactionFunctionArg)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Get the definition of a struct type and check the number of its fields in value
+fn get_struct_type<'a>(
+    type_name: &str,
+    fields: &[RlpValue],
+    types: &'a HashMap<String, Method>,
+) -> ParsingResult<&'a Method> {
+    let struct_type = types
+        .get(type_name)
+        .ok_or(ParsingError::InvalidMetaTransactionFunctionArg)?;
+    if struct_type.args.len() != fields.len() {
+        return Err(ParsingError::InvalidMetaTransactionFunctionArg);
+    }
+    Ok(struct_type)
+}
+
+/// How a meta-call is encoded to be signed and called
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TypeEncoding {
+    /// Encoding of the first version of meta-calls, kept as it is so that signed v1 meta-calls
+    /// stay valid: the typeHash of a struct is the hash of its own definition, `bytesN` and
+    /// `intN` values are hashed as they are given, without padding nor sign extension, and
+    /// struct arguments appear by name in the function selector.
+    Legacy,
+    /// EIP-712 encoding, with struct arguments as tuples in the function selector
+    Eip712,
+}
+
+/// EIP-712 hashStruct of a value of struct_type, whose fields are values.
+/// The number of values must have been checked against the fields of struct_type.
+/// Definition of all types is in `types`.
+/// See https://eips.ethereum.org/EIPS/eip-712#definition-of-hashstruct
+fn eip_712_hash_struct(
+    struct_type: &Method,
+    values: &[RlpValue],
+    types: &HashMap<String, Method>,
+    encoding: TypeEncoding,
+) -> ParsingResult<RawU256> {
+    let type_hash = match encoding {
+        TypeEncoding::Legacy => keccak(struct_type.raw.as_bytes()),
+        TypeEncoding::Eip712 => keccak(encode_type(struct_type, types)?.as_bytes()),
+    };
+    let mut bytes = type_hash.as_bytes().to_vec();
+    for (arg, value) in struct_type.args.iter().zip(values) {
+        bytes.extend_from_slice(&eip_712_hash_argument(&arg.t, value, types, encoding)?);
+    }
+    Ok(keccak(&bytes).into())
+}
+
 /// eip-712 hash a single argument, whose type is ty, and value is value.
 /// Definition of all types is in `types`.
+/// See https://eips.ethereum.org/EIPS/eip-712#definition-of-encodedata
 fn eip_712_hash_argument(
     ty: &ArgType,
     value: &RlpValue,
     types: &HashMap<String, Method>,
+    encoding: TypeEncoding,
 ) -> ParsingResult<Vec<u8>> {
     match ty {
         ArgType::String | ArgType::Bytes => {
             eip_712_rlp_value(value, |b| Ok(keccak(b).as_bytes().to_vec()))
         }
-        ArgType::Byte(_) => eip_712_rlp_value(value, |b| Ok(b.clone())),
-        // TODO: ensure rlp int is encoded as sign extended uint256, otherwise this is wrong
-        ArgType::Uint | ArgType::Int | ArgType::Bool => eip_712_rlp_value(value, |b| {
-            Ok(u256_to_arr(&U256::from_big_endian(b)).to_vec())
+        ArgType::Byte(size) => eip_712_rlp_value(value, |b| {
+            check_fixed_bytes(b, *size)?;
+            let mut r = b.clone();
+            if encoding == TypeEncoding::Eip712 {
+                // bytesN are right padded to 32 bytes
+                r.resize(32, 0);
+            }
+            Ok(r)
         }),
-        ArgType::Address => {
-            eip_712_rlp_value(value, |b| Ok(encode_address(Address::from_slice(b))))
-        }
-        ArgType::Array { inner, .. } => eip_712_rlp_list(value, |l| {
+        ArgType::Uint(bits) => eip_712_rlp_value(value, |b| {
+            Ok(u256_to_arr(&decode_int(b, *bits, false)?).to_vec())
+        }),
+        ArgType::Int(bits) => eip_712_rlp_value(value, |b| {
+            let value = decode_int(b, *bits, true)?;
+            let value = match encoding {
+                TypeEncoding::Legacy => U256::from_big_endian(b),
+                TypeEncoding::Eip712 => value,
+            };
+            Ok(u256_to_arr(&value).to_vec())
+        }),
+        ArgType::Bool => eip_712_rlp_value(value, |b| {
+            Ok(u256_to_arr(&decode_int(b, 1, false)?).to_vec())
+        }),
+        ArgType::Address => eip_712_rlp_value(value, |b| Ok(encode_address(decode_address(b)?))),
+        ArgType::Array { inner, length } => eip_712_rlp_list(value, |l| {
+            check_array_length(l, length)?;
             let mut r = vec![];
             for element in l {
-                r.extend_from_slice(&eip_712_hash_argument(inner, element, types)?);
+                r.extend_from_slice(&eip_712_hash_argument(inner, element, types, encoding)?);
             }
             Ok(keccak(&r).as_bytes().to_vec())
         }),
         ArgType::Custom(type_name) => eip_712_rlp_list(value, |l| {
-            let struct_type = types
-                .get(type_name)
-                .ok_or(ParsingError::InvalidMetaTransactionFunctionArg)?;
-            // struct_type.raw is with struct type with argument names (a "method_def"), so it follows
-            // EIP-712 typeHash.
-            let mut r = keccak(struct_type.raw.as_bytes()).as_bytes().to_vec();
-            for (i, element) in l.iter().enumerate() {
-                r.extend_from_slice(&eip_712_hash_argument(
-                    &struct_type.args[i].t,
-                    element,
-                    types,
-                )?);
-            }
-            Ok(keccak(&r).as_bytes().to_vec())
+            let struct_type = get_struct_type(type_name, l, types)?;
+            Ok(eip_712_hash_struct(struct_type, l, types, encoding)?.to_vec())
         }),
     }
 }
@@ -442,41 +675,34 @@ fn arg_to_abi_token(
         ArgType::String | ArgType::Bytes => {
             value_to_abi_token(arg, |b| Ok(ABIToken::Bytes(b.clone())))
         }
-        ArgType::Byte(_) => value_to_abi_token(arg, |b| Ok(ABIToken::FixedBytes(b.clone()))),
-        ArgType::Uint | ArgType::Int | ArgType::Bool => {
-            value_to_abi_token(arg, |b| Ok(ABIToken::Uint(U256::from_big_endian(b))))
+        ArgType::Byte(size) => value_to_abi_token(arg, |b| {
+            check_fixed_bytes(b, *size)?;
+            Ok(ABIToken::FixedBytes(b.clone()))
+        }),
+        ArgType::Uint(bits) => {
+            value_to_abi_token(arg, |b| Ok(ABIToken::Uint(decode_int(b, *bits, false)?)))
         }
-        ArgType::Address => {
-            value_to_abi_token(arg, |b| Ok(ABIToken::Address(Address::from_slice(b))))
+        ArgType::Int(bits) => {
+            value_to_abi_token(arg, |b| Ok(ABIToken::Int(decode_int(b, *bits, true)?)))
         }
-        ArgType::Array {
-            inner,
-            length: None,
-        } => list_to_abi_token(arg, |l| {
+        ArgType::Bool => value_to_abi_token(arg, |b| Ok(ABIToken::Uint(decode_int(b, 1, false)?))),
+        ArgType::Address => value_to_abi_token(arg, |b| Ok(ABIToken::Address(decode_address(b)?))),
+        ArgType::Array { inner, length } => list_to_abi_token(arg, |l| {
+            check_array_length(l, length)?;
             let mut tokens = vec![];
             for arg in l {
                 tokens.push(arg_to_abi_token(inner, arg, methods)?);
             }
-            Ok(ABIToken::Array(tokens))
-        }),
-        ArgType::Array {
-            inner,
-            length: Some(_),
-        } => list_to_abi_token(arg, |l| {
-            let mut tokens = vec![];
-            for arg in l {
-                tokens.push(arg_to_abi_token(inner, arg, methods)?);
+            match length {
+                None => Ok(ABIToken::Array(tokens)),
+                Some(_) => Ok(ABIToken::FixedArray(tokens)),
             }
-            Ok(ABIToken::FixedArray(tokens))
         }),
         ArgType::Custom(type_name) => list_to_abi_token(arg, |l| {
-            let struct_type = methods
-                .types
-                .get(type_name)
-                .ok_or(ParsingError::InvalidMetaTransactionFunctionArg)?;
+            let struct_type = get_struct_type(type_name, l, &methods.types)?;
             let mut tokens = vec![];
-            for (i, element) in l.iter().enumerate() {
-                tokens.push(arg_to_abi_token(&struct_type.args[i].t, element, methods)?);
+            for (element_type, element) in struct_type.args.iter().zip(l) {
+                tokens.push(arg_to_abi_token(&element_type.t, element, methods)?);
             }
             Ok(ABIToken::Tuple(tokens))
         }),
@@ -503,68 +729,167 @@ where
     }
 }
 
-/// eip-712 hash struct of entire meta txn and abi-encode function args to evm input
-pub fn prepare_meta_call_args(
-    domain_separator: &RawU256,
-    account_id: &[u8],
-    method_def: String,
-    input: &InternalMetaCallArgs,
-) -> ParsingResult<(RawU256, Vec<u8>)> {
-    let mut bytes = Vec::new();
-    let method_arg_start = match method_def.find('(') {
-        Some(index) => index,
-        None => return Err(ParsingError::InvalidMetaTransactionMethodName),
-    };
-    let arguments = "Arguments".to_string() + &method_def[method_arg_start..];
+/// The method called by a meta-call, EIP-712 encoded and ABI-encoded
+struct MetaCallMethod {
+    /// Definitions of the Arguments type and of the struct types it references, as they follow
+    /// the meta-call type in its encodeType
+    referenced_types: String,
+    /// Method signature without argument names, e.g. "adopt(uint256,PetObj)"
+    signature: String,
+    /// EIP-712 hashStruct of the arguments
+    arguments_hash: RawU256,
+    /// ABI-encoded input of the call
+    input: Vec<u8>,
+}
+
+const ARGUMENTS_TYPE_NAME: &str = "Arguments";
+
+fn encode_meta_call_method(
+    method_def: &str,
+    args: &[u8],
+    encoding: TypeEncoding,
+) -> ParsingResult<MetaCallMethod> {
     // Note: method_def is like "adopt(uint256 petId,PetObj petObj)PetObj(string name,address owner)",
     // MUST have no space after `,`. EIP-712 requires hashStruct start by packing the typeHash,
     // See "Rationale for typeHash" in https://eips.ethereum.org/EIPS/eip-712#definition-of-hashstruct
-    // method_def is used here for typeHash
-    let types = "NearTx(string evmId,uint256 nonce,uint256 feeAmount,address feeAddress,address contractAddress,uint256 value,string contractMethod,Arguments arguments)".to_string() + &arguments;
-    bytes.extend_from_slice(keccak(types.as_bytes()).as_bytes());
-    bytes.extend_from_slice(keccak(account_id).as_bytes());
-    bytes.extend_from_slice(&u256_to_arr(&input.nonce));
-    bytes.extend_from_slice(&input.fee_amount.to_bytes());
-    bytes.extend_from_slice(&encode_address(input.fee_address));
-    bytes.extend_from_slice(&encode_address(input.contract_address));
-    bytes.extend_from_slice(&input.value.to_bytes());
-
-    let methods = MethodAndTypes::parse(&method_def)?;
-    let method_sig = method_signature(&methods);
-    bytes.extend_from_slice(keccak(method_sig.as_bytes()).as_bytes());
-
-    let mut arg_bytes = Vec::new();
-    arg_bytes.extend_from_slice(keccak(arguments.as_bytes()).as_bytes());
-    let args_decoded: Vec<RlpValue> = rlp_decode(&input.input)?;
+    // The arguments are hashed as an "Arguments" struct with the fields of the method.
+    let methods = MethodAndTypes::parse(method_def)?;
+    if methods.types.contains_key(ARGUMENTS_TYPE_NAME) {
+        return Err(ParsingError::InvalidMetaTransactionMethodName);
+    }
+    let args_decoded: Vec<RlpValue> = rlp_decode(args)?;
     if methods.method.args.len() != args_decoded.len() {
         return Err(ParsingError::ArgsLengthMismatch);
     }
-    for (i, arg) in args_decoded.iter().enumerate() {
-        arg_bytes.extend_from_slice(&eip_712_hash_argument(
-            &methods.method.args[i].t,
-            arg,
-            &methods.types,
-        )?);
-    }
 
     // ETH-ABI require function selector to use method_sig, instead of method_name,
     // See https://docs.soliditylang.org/en/v0.7.5/abi-spec.html#function-selector
     // Above spec is not completely clear, this implementation shows signature is the one without
     // argument name:
     // https://github.com/rust-ethereum/ethabi/blob/69285cf6b6202d9faa19c7d0239df6a2bd79d55f/ethabi/src/signature.rs#L28
-    let method_selector = method_sig_to_abi(&method_sig);
+    // With the EIP-712 encoding, struct arguments are part of the selector as tuples of their
+    // fields' types.
+    let signature = method_signature(&methods);
+    let method_selector = match encoding {
+        TypeEncoding::Legacy => method_sig_to_abi(&signature),
+        TypeEncoding::Eip712 => method_sig_to_abi(&abi_method_signature(&methods)?),
+    };
     let args_eth_abi = eth_abi_encode_args(&args_decoded, &methods)?;
     let input = [method_selector.to_vec(), args_eth_abi.to_vec()].concat();
 
-    let arg_bytes_hash: RawU256 = keccak(&arg_bytes).into();
-    bytes.extend_from_slice(&arg_bytes_hash);
+    let MethodAndTypes { method, types, .. } = methods;
+    let (arguments, referenced_types) = match encoding {
+        // The struct types follow the Arguments type as they are given in method_def
+        TypeEncoding::Legacy => {
+            let raw = ARGUMENTS_TYPE_NAME.to_string() + &method_def[method.name.len()..];
+            let arguments = Method {
+                name: ARGUMENTS_TYPE_NAME.to_string(),
+                raw: raw.clone(),
+                args: method.args,
+            };
+            (arguments, raw)
+        }
+        TypeEncoding::Eip712 => {
+            let arguments = Method {
+                name: ARGUMENTS_TYPE_NAME.to_string(),
+                raw: ARGUMENTS_TYPE_NAME.to_string() + &method.raw[method.name.len()..],
+                args: method.args,
+            };
+            let mut referenced = BTreeMap::new();
+            collect_referenced_types(&arguments.args, &types, &mut referenced)?;
+            referenced.insert(arguments.name.as_str(), arguments.raw.as_str());
+            let referenced_types = referenced.values().copied().collect();
+            (arguments, referenced_types)
+        }
+    };
+    let arguments_hash = eip_712_hash_struct(&arguments, &args_decoded, &types, encoding)?;
+
+    Ok(MetaCallMethod {
+        referenced_types,
+        signature,
+        arguments_hash,
+        input,
+    })
+}
 
-    let message: RawU256 = keccak(&bytes).into();
+/// EIP-712 message to sign, given the domainSeparator and the hashStruct of the message
+fn eip_712_message(domain_separator: &RawU256, struct_hash: &[u8]) -> RawU256 {
+    let message: RawU256 = keccak(struct_hash).into();
     let mut bytes = Vec::with_capacity(2 + 32 + 32);
     bytes.extend_from_slice(&[0x19, 0x01]);
     bytes.extend_from_slice(domain_separator);
     bytes.extend_from_slice(&message);
-    Ok((keccak(&bytes).into(), input))
+    keccak(&bytes).into()
+}
+
+/// eip-712 hash struct of entire meta txn and abi-encode function args to evm input
+pub fn prepare_meta_call_args(
+    domain_separator: &RawU256,
+    account_id: &[u8],
+    method_def: String,
+    input: &InternalMetaCallArgs,
+) -> ParsingResult<(RawU256, Vec<u8>)> {
+    let method = encode_meta_call_method(&method_def, &input.input, TypeEncoding::Legacy)?;
+
+    let mut bytes = Vec::new();
+    let types = "NearTx(string evmId,uint256 nonce,uint256 feeAmount,address feeAddress,address contractAddress,uint256 value,string contractMethod,Arguments arguments)".to_string() + &method.referenced_types;
+    bytes.extend_from_slice(keccak(types.as_bytes()).as_bytes());
+    bytes.extend_from_slice(keccak(account_id).as_bytes());
+    bytes.extend_from_slice(&u256_to_arr(&input.nonce));
+    bytes.extend_from_slice(&input.fee_amount.to_bytes());
+    bytes.extend_from_slice(&encode_address(input.fee_address));
+    bytes.extend_from_slice(&encode_address(input.contract_address));
+    bytes.extend_from_slice(&input.value.to_bytes());
+    bytes.extend_from_slice(keccak(method.signature.as_bytes()).as_bytes());
+    bytes.extend_from_slice(&method.arguments_hash);
+
+    Ok((eip_712_message(domain_separator, &bytes), method.input))
+}
+
+/// eip-712 hash struct of entire second version meta txn and abi-encode function args to evm input.
+/// The sender is signed so that a signature accepted by several EIP-1271 wallets can't be
+/// replayed from another wallet. It is the zero address for a meta-call made from the
+/// ECDSA signer, whose address is only known once recovered from the signature.
+pub fn prepare_meta_call_v2_args(
+    domain_separator: &RawU256,
+    method_def: &str,
+    input: &InternalMetaCallArgsV2,
+) -> ParsingResult<(RawU256, Vec<u8>)> {
+    let method = encode_meta_call_method(method_def, &input.input, TypeEncoding::Eip712)?;
+
+    let mut bytes = Vec::new();
+    let types = "MetaCall(address sender,uint256 nonce,uint256 deadline,uint256 feeAmount,address feeAddress,address contractAddress,uint256 value,uint256 gasLimit,string contractMethod,Arguments arguments)".to_string() + &method.referenced_types;
+    bytes.extend_from_slice(keccak(types.as_bytes()).as_bytes());
+    bytes.extend_from_slice(&encode_address(input.sender));
+    bytes.extend_from_slice(&u256_to_arr(&input.nonce));
+    bytes.extend_from_slice(&u256_to_arr(&U256::from(input.deadline)));
+    bytes.extend_from_slice(&input.fee_amount.to_bytes());
+    bytes.extend_from_slice(&encode_address(input.fee_address));
+    bytes.extend_from_slice(&encode_address(input.contract_address));
+    bytes.extend_from_slice(&input.value.to_bytes());
+    bytes.extend_from_slice(&u256_to_arr(&U256::from(input.gas_limit)));
+    bytes.extend_from_slice(keccak(method.signature.as_bytes()).as_bytes());
+    bytes.extend_from_slice(&method.arguments_hash);
+
+    Ok((eip_712_message(domain_separator, &bytes), method.input))
+}
+
+/// Recover the address which signed the message, given the `r || s || v` signature
+pub fn recover_signer(msg: &RawU256, signature: &[u8]) -> ParsingResult<Address> {
+    let mut full_signature: [u8; 65] = [0; 65];
+    if signature.len() != full_signature.len() {
+        return Err(ParsingError::InvalidEcRecoverSignature);
+    }
+    full_signature.copy_from_slice(signature);
+    ecrecover(H256::from_slice(msg), &full_signature)
+        .map_err(|_| ParsingError::InvalidEcRecoverSignature)
+}
+
+/// Signature of a meta-call made from an explicit sender. It can only be verified
+/// against the EVM state, as the sender may be a contract.
+pub struct SenderSignature {
+    pub message: RawU256,
+    pub signature: Vec<u8>,
 }
 
 /// Parse encoded `MetaCallArgs`, validate with given domain and account and recover the sender's address from the signature.
@@ -593,22 +918,59 @@ pub fn parse_meta_call(
     };
     let (msg, input) =
         prepare_meta_call_args(domain_separator, account_id, meta_tx.method_def, &result)?;
-    let mut signature: [u8; 65] = [0; 65];
-    signature[64] = meta_tx.v;
-    signature[..64].copy_from_slice(&meta_tx.signature);
-    match ecrecover(H256::from_slice(&msg), &signature) {
-        Ok(sender) => {
-            result.sender = sender;
-            result.input = input;
-            Ok(result)
+    let mut signature = meta_tx.signature.to_vec();
+    signature.push(meta_tx.v);
+    result.sender = recover_signer(&msg, &signature)?;
+    result.input = input;
+    Ok(result)
+}
+
+/// Parse encoded `MetaCallArgsV2`, validate with given domain and recover the sender's address from the signature.
+/// Returns error if method definition or arguments are wrong, invalid signature or EC recovery failed.
+/// The deadline and the nonce are left to the caller to check, as well as the returned
+/// signature of an explicit sender.
+pub fn parse_meta_call_v2(
+    domain_separator: &RawU256,
+    args: Vec<u8>,
+) -> ParsingResult<(InternalMetaCallArgsV2, Option<SenderSignature>)> {
+    let meta_tx =
+        MetaCallArgsV2::try_from_slice(&args).map_err(|_| ParsingError::ArgumentParseError)?;
+
+    let mut result = InternalMetaCallArgsV2 {
+        sender: meta_tx
+            .sender
+            .map(Address::from)
+            .unwrap_or_else(Address::zero),
+        nonce: U256::from(meta_tx.nonce),
+        deadline: meta_tx.deadline,
+        fee_amount: Wei::new(U256::from(meta_tx.fee_amount)),
+        fee_address: Address::from(meta_tx.fee_address),
+        contract_address: Address::from(meta_tx.contract_address),
+        value: Wei::new(U256::from(meta_tx.value)),
+        gas_limit: meta_tx.gas_limit,
+        input: meta_tx.args,
+    };
+    let (msg, input) = prepare_meta_call_v2_args(domain_separator, &meta_tx.method_def, &result)?;
+    result.input = input;
+    match meta_tx.sender {
+        Some(_) => {
+            let signature = SenderSignature {
+                message: msg,
+                signature: meta_tx.signature,
+            };
+            Ok((result, Some(signature)))
+        }
+        None => {
+            result.sender = recover_signer(&msg, &meta_tx.signature)?;
+            Ok((result, None))
         }
-        Err(_) => Err(ParsingError::InvalidEcRecoverSignature),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ArgType;
+    use super::{ArgType, MethodAndTypes, RlpValue, TypeEncoding};
+    use crate::prelude::{keccak, u256_to_arr, U256};
     use rand::Rng;
 
     #[test]
@@ -625,16 +987,16 @@ mod tests {
         // ## uintN
         for n in 1..=32 {
             let s = format!("uint{}", 8 * n);
-            assert_arg_type(&s, ArgType::Uint);
+            assert_arg_type(&s, ArgType::Uint(8 * n));
         }
-        assert_arg_type("uint", ArgType::Uint);
+        assert_arg_type("uint", ArgType::Uint(256));
 
         // ## intN
         for n in 1..=32 {
             let s = format!("int{}", 8 * n);
-            assert_arg_type(&s, ArgType::Int);
+            assert_arg_type(&s, ArgType::Int(8 * n));
         }
-        assert_arg_type("int", ArgType::Int);
+        assert_arg_type("int", ArgType::Int(256));
 
         // ## bool
         assert_arg_type("bool", ArgType::Bool);
@@ -700,6 +1062,249 @@ mod tests {
         super::parse_type("*AThing*").unwrap_err();
     }
 
+    // Vectors of https://eips.ethereum.org/EIPS/eip-712#specification-of-the-eth_signtypeddata-json-rpc
+    #[test]
+    fn test_eip_712_spec_example() {
+        let methods = MethodAndTypes::parse(
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+        )
+        .ok()
+        .unwrap();
+        let encoded_type = super::encode_type(&methods.method, &methods.types)
+            .ok()
+            .unwrap();
+        assert_eq!(
+            encoded_type,
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(keccak(encoded_type.as_bytes())),
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+        );
+
+        let person = |name: &str, wallet: &str| {
+            RlpValue::List(vec![
+                bytes(name.as_bytes()),
+                bytes(&hex::decode(wallet).unwrap()),
+            ])
+        };
+        let mail = vec![
+            person("Cow", "CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+            person("Bob", "bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+            bytes(b"Hello, Bob!"),
+        ];
+        let mail_hash = super::eip_712_hash_struct(
+            &methods.method,
+            &mail,
+            &methods.types,
+            TypeEncoding::Eip712,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(
+            hex::encode(mail_hash),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+
+        let domain_methods = MethodAndTypes::parse(
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+        )
+        .ok()
+        .unwrap();
+        let domain = vec![
+            bytes(b"Ether Mail"),
+            bytes(b"1"),
+            bytes(&[1]),
+            bytes(&hex::decode("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap()),
+        ];
+        let domain_separator = super::eip_712_hash_struct(
+            &domain_methods.method,
+            &domain,
+            &domain_methods.types,
+            TypeEncoding::Eip712,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(
+            hex::encode(domain_separator),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        let message = keccak(&[&[0x19, 0x01][..], &domain_separator[..], &mail_hash[..]].concat());
+        assert_eq!(
+            hex::encode(message),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    // Expected values are computed with an independent implementation of the EIP-712
+    // specification, which also gives the vectors of `test_eip_712_spec_example`.
+    #[test]
+    fn test_eip_712_nested_types() {
+        let methods = MethodAndTypes::parse(
+            "Order(Item[2] items,int8[] deltas,bytes4[] tags,uint16[][] matrix,Person owner)\
+             Person(string name,address wallet)\
+             Item(Person seller,bytes32 id,bool active,int256 price)",
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(
+            super::encode_type(&methods.method, &methods.types)
+                .ok()
+                .unwrap(),
+            "Order(Item[2] items,int8[] deltas,bytes4[] tags,uint16[][] matrix,Person owner)\
+             Item(Person seller,bytes32 id,bool active,int256 price)\
+             Person(string name,address wallet)"
+        );
+
+        let person = |name: &str, wallet: u8| {
+            RlpValue::List(vec![bytes(name.as_bytes()), bytes(&[wallet; 20])])
+        };
+        let item = |seller: RlpValue, id: u8, active: &[u8], price: &[u8]| {
+            RlpValue::List(vec![seller, bytes(&[id; 32]), bytes(active), bytes(price)])
+        };
+        let mut minus_five = [0xff; 32];
+        minus_five[31] = 0xfb;
+        let items = RlpValue::List(vec![
+            item(person("Alice", 0x11), 0x01, &[1], &minus_five[..]),
+            item(person("Bob", 0x22), 0x02, &[], &[7]),
+        ]);
+        let deltas = RlpValue::List(vec![bytes(&[0xff]), bytes(&[5]), bytes(&[0x80])]);
+        let tags = RlpValue::List(vec![bytes(&[0xde, 0xad, 0xbe, 0xef]), bytes(&[0, 0, 0, 1])]);
+        let matrix = RlpValue::List(vec![
+            RlpValue::List(vec![bytes(&[1]), bytes(&[2])]),
+            RlpValue::List(vec![]),
+            RlpValue::List(vec![bytes(&[0xff, 0xff])]),
+        ]);
+        let mut order = vec![items, deltas, tags, matrix, person("Carol", 0x33)];
+        let hash = |order: &[RlpValue]| {
+            super::eip_712_hash_struct(&methods.method, order, &methods.types, TypeEncoding::Eip712)
+                .ok()
+        };
+        assert_eq!(
+            hex::encode(hash(&order).unwrap()),
+            "84ead12455438336dce35ce7a64d59c7d87c096396d27296f9e31b25494f4280"
+        );
+
+        // bytes4 of another size
+        order[2] = RlpValue::List(vec![bytes(&[0xde, 0xad, 0xbe])]);
+        assert!(hash(&order).is_none());
+        // int8 out of range
+        order[2] = RlpValue::List(vec![]);
+        order[1] = RlpValue::List(vec![bytes(&[0x01, 0x00])]);
+        assert!(hash(&order).is_none());
+        // Item[2] of another length
+        order[1] = RlpValue::List(vec![]);
+        order[0] = RlpValue::List(vec![item(person("Alice", 0x11), 0x01, &[1], &[])]);
+        assert!(hash(&order).is_none());
+        // struct with a missing field
+        order[0] = RlpValue::List(vec![]);
+        order[4] = RlpValue::List(vec![bytes(b"Carol")]);
+        assert!(hash(&order).is_none());
+    }
+
+    #[test]
+    fn test_decode_int() {
+        let max = U256::max_value();
+        assert_eq!(super::decode_int(&[0xff], 8, true).ok(), Some(max));
+        assert_eq!(super::decode_int(&[0xff; 32], 8, true).ok(), Some(max));
+        assert_eq!(
+            super::decode_int(&[0x7f], 8, true).ok(),
+            Some(U256::from(0x7f))
+        );
+        assert_eq!(
+            super::decode_int(&[0xff], 8, false).ok(),
+            Some(U256::from(0xff))
+        );
+        assert_eq!(super::decode_int(&[0xff; 32], 256, true).ok(), Some(max));
+        assert!(super::decode_int(&[0x01, 0x00], 8, false).is_err());
+        assert!(super::decode_int(&[0xff; 32], 8, false).is_err());
+        assert!(super::decode_int(&[0xff; 33], 256, false).is_err());
+        assert!(super::decode_int(&[2], 1, false).is_err());
+    }
+
+    #[test]
+    fn test_encode_meta_call_method() {
+        let method = super::encode_meta_call_method(
+            "f(Zoo z,Apple a)Zoo(uint8 x)Apple(Zoo[] zs)",
+            &hex::decode("c6c101c3c2c102").unwrap(),
+            TypeEncoding::Eip712,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(
+            method.referenced_types,
+            "Apple(Zoo[] zs)Arguments(Zoo z,Apple a)Zoo(uint8 x)"
+        );
+        assert_eq!(method.signature, "f(Zoo,Apple)");
+        // keccak256("f((uint8),((uint8)[]))")
+        assert_eq!(hex::encode(&method.input[..4]), "7889dbe6");
+
+        // Structs which contain themselves cannot be ABI-encoded
+        assert!(super::encode_meta_call_method(
+            "f(Node n)Node(Node[] children)",
+            &hex::decode("c2c1c0").unwrap(),
+            TypeEncoding::Eip712,
+        )
+        .is_err());
+        // Arguments is the name of the struct of the method arguments
+        assert!(super::encode_meta_call_method(
+            "f(Arguments a)Arguments(uint8 x)",
+            &hex::decode("c2c101").unwrap(),
+            TypeEncoding::Eip712,
+        )
+        .is_err());
+    }
+
+    // The first version of meta-calls keeps the encoding it was signed with
+    #[test]
+    fn test_encode_meta_call_method_legacy() {
+        let u256 = |value: u64| u256_to_arr(&U256::from(value));
+        let hash = |parts: &[&[u8]]| keccak(&parts.concat());
+
+        let method = super::encode_meta_call_method(
+            "f(Zoo z,Apple a)Zoo(uint8 x)Apple(Zoo[] zs)",
+            &hex::decode("c6c101c3c2c102").unwrap(),
+            TypeEncoding::Legacy,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(
+            method.referenced_types,
+            "Arguments(Zoo z,Apple a)Zoo(uint8 x)Apple(Zoo[] zs)"
+        );
+        assert_eq!(method.input[..4], keccak(b"f(Zoo,Apple)").as_bytes()[..4]);
+        let zoo = |x: u64| hash(&[keccak(b"Zoo(uint8 x)").as_bytes(), &u256(x)]);
+        let apple = hash(&[
+            keccak(b"Apple(Zoo[] zs)").as_bytes(),
+            keccak(zoo(2).as_bytes()).as_bytes(),
+        ]);
+        let arguments = hash(&[
+            keccak(method.referenced_types.as_bytes()).as_bytes(),
+            zoo(1).as_bytes(),
+            apple.as_bytes(),
+        ]);
+        assert_eq!(method.arguments_hash, arguments.0);
+
+        // bytesN are not padded and intN are not sign extended
+        let method = super::encode_meta_call_method(
+            "g(bytes4 b,int8 i)",
+            &hex::decode("c784deadbeef81ff").unwrap(),
+            TypeEncoding::Legacy,
+        )
+        .ok()
+        .unwrap();
+        let arguments = hash(&[
+            keccak(b"Arguments(bytes4 b,int8 i)").as_bytes(),
+            &[0xde, 0xad, 0xbe, 0xef],
+            &u256(0xff),
+        ]);
+        assert_eq!(method.arguments_hash, arguments.0);
+    }
+
+    fn bytes(value: &[u8]) -> RlpValue {
+        RlpValue::Bytes(value.to_vec())
+    }
+
     fn create_array_type_string(inner_type: &str, size: Option<u8>) -> String {
         format!(
             "{}[{}]",
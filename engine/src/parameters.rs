@@ -1,12 +1,13 @@
 use crate::admin_controlled::PausedMask;
 use crate::fungible_token::FungibleTokenMetadata;
-use crate::json::{JsonError, JsonValue};
+use crate::json::{JsonError, JsonOutOfRangeError, JsonValue};
 use crate::prelude::account_id::AccountId;
 use crate::prelude::{
     format, Balance, BorshDeserialize, BorshSerialize, EthAddress, RawAddress, RawH256, RawU256,
-    String, ToString, TryFrom, Vec, WeiU256,
+    String, ToString, TryFrom, Vec, Wei, WeiU256, U256,
 };
 use crate::proof::Proof;
+use aurora_engine_types::json::escape_json;
 use aurora_engine_types::types::Fee;
 use evm::backend::Log;
 
@@ -39,6 +40,26 @@ pub struct MetaCallArgs {
     pub args: Vec<u8>,
 }
 
+/// Borsh-encoded parameters for the `meta_call_v2` function.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct MetaCallArgsV2 {
+    /// Address the meta-call is made from. If it is a contract, the signature is checked
+    /// with EIP-1271 `isValidSignature`. Without it, the sender is the ECDSA signer.
+    pub sender: Option<RawAddress>,
+    /// ECDSA signature as `r || s || v`, or any signature accepted by a contract sender
+    pub signature: Vec<u8>,
+    pub nonce: RawU256,
+    /// NEAR block timestamp in seconds after which the meta-call is rejected
+    pub deadline: u64,
+    pub fee_amount: RawU256,
+    pub fee_address: RawAddress,
+    pub contract_address: RawAddress,
+    pub value: RawU256,
+    pub gas_limit: u64,
+    pub method_def: String,
+    pub args: Vec<u8>,
+}
+
 /// Borsh-encoded log for use in a `SubmitResult`.
 #[derive(Debug, BorshSerialize, BorshDeserialize)]
 pub struct ResultLog {
@@ -191,6 +212,140 @@ pub struct DeployErc20TokenArgs {
 /// Borsh-encoded parameters for `get_erc20_from_nep141` function.
 pub type GetErc20FromNep141CallArgs = DeployErc20TokenArgs;
 
+/// Borsh-encoded parameters for `set_wnear_address` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct SetWnearAddressArgs {
+    /// The wNEAR ERC-20, or `None` to stop wrapping NEAR attached to `call`.
+    pub address: Option<RawAddress>,
+}
+
+/// Borsh-encoded parameters for `call_with_wrapped_near` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct WrappedNearCallArgs {
+    /// The wNEAR ERC-20 the attached NEAR is wrapped into.
+    pub wnear: RawAddress,
+    /// The account credited with the wrapped NEAR, which makes the call.
+    pub origin: RawAddress,
+    /// The account refunded with the attached NEAR if it could not be wrapped.
+    pub payer: AccountId,
+    pub amount: u128,
+    pub call: CallArgs,
+}
+
+/// Borsh-encoded parameters for `set_evm_log_events` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct SetEvmLogEventsArgs {
+    /// Whether EVM logs are also written to the NEAR logs as NEP-297 events.
+    pub enabled: bool,
+}
+
+/// Borsh-encoded parameters for `set_unprotected_transaction_allowed` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct SetUnprotectedTransactionAllowedArgs {
+    /// Hash of the signed transaction, as submitted.
+    pub transaction_hash: RawH256,
+    /// Whether the transaction is accepted when unprotected transactions are rejected.
+    pub allowed: bool,
+}
+
+/// Borsh-encoded parameters for `deploy_erc721_token` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DeployErc721TokenArgs {
+    pub nep171: AccountId,
+}
+
+/// Borsh-encoded parameters for `get_erc721_from_nep171` function.
+pub type GetErc721FromNep171CallArgs = DeployErc721TokenArgs;
+
+/// JSON-encoded parameters of the NEP-171 `nft_on_transfer` function.
+#[derive(Debug, Clone)]
+pub struct NEP171NftOnTransferArgs {
+    pub sender_id: AccountId,
+    pub previous_owner_id: AccountId,
+    pub token_id: String,
+    pub msg: String,
+}
+
+impl TryFrom<JsonValue> for NEP171NftOnTransferArgs {
+    type Error = JsonError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sender_id: AccountId::try_from(value.string("sender_id")?)
+                .map_err(|_| JsonError::InvalidString)?,
+            previous_owner_id: AccountId::try_from(value.string("previous_owner_id")?)
+                .map_err(|_| JsonError::InvalidString)?,
+            token_id: value.string("token_id")?,
+            msg: value.string("msg")?,
+        })
+    }
+}
+
+/// Borsh-encoded parameters for the `nft_on_transfer_callback` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct NftOnTransferCallbackArgs {
+    pub nep171: AccountId,
+    pub token_id: String,
+    pub recipient: RawAddress,
+}
+
+/// Token returned by the NEP-171 `nft_token` function. Only the fields bridged with the
+/// token are kept.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct NftToken {
+    pub token_id: String,
+    /// The NEP-177 `reference`, which points to the JSON metadata of the token like an
+    /// ERC-721 token URI does, otherwise the `media` URL. Empty without metadata.
+    pub token_uri: String,
+}
+
+impl TryFrom<JsonValue> for NftToken {
+    type Error = JsonError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        let token_id = value.string("token_id")?;
+        let token_uri = match &value {
+            JsonValue::Object(o) => match o.get("metadata") {
+                Some(metadata @ JsonValue::Object(_)) => metadata
+                    .string("reference")
+                    .or_else(|_| metadata.string("media"))
+                    .unwrap_or_default(),
+                _ => String::new(),
+            },
+            _ => return Err(JsonError::NotJsonType),
+        };
+        Ok(Self {
+            token_id,
+            token_uri,
+        })
+    }
+}
+
+/// Borsh-encoded parameters for the `sync_erc20_metadata` function and its callback.
+pub type SyncErc20MetadataArgs = DeployErc20TokenArgs;
+
+/// Metadata returned by the `ft_metadata` function of a NEP-141 token. Only the fields
+/// copied to the bridged ERC-20 are kept.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Erc20Metadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TryFrom<JsonValue> for Erc20Metadata {
+    type Error = JsonError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: value.string("name")?,
+            symbol: value.string("symbol")?,
+            decimals: u8::try_from(value.u64("decimals")?)
+                .map_err(|_| JsonError::OutOfRange(JsonOutOfRangeError::OutOfRangeU8))?,
+        })
+    }
+}
+
 /// Borsh-encoded parameters for the `get_storage_at` function.
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct GetStorageAtArgs {
@@ -261,11 +416,155 @@ impl From<NEP141FtOnTransferArgs> for String {
             value.sender_id,
             value.amount,
             // Escape message to avoid json injection attacks
-            value.msg.replace("\\", "\\\\").replace("\"", "\\\"")
+            escape_json(&value.msg)
         )
     }
 }
 
+/// Version of the JSON `msg` format of `ft_on_transfer` for bridged NEP-141 tokens.
+pub const FT_ON_TRANSFER_MESSAGE_VERSION: u64 = 1;
+
+/// Recipient, fee and optional call carried by the `msg` of `ft_on_transfer` for
+/// bridged NEP-141 tokens. Two formats are accepted:
+///
+/// * the legacy one: the recipient address in hex (40 characters), optionally followed
+///   by the fee in ETH (64 characters, big endian hex);
+/// * a JSON object `{"version": 1, "recipient": "<hex>", "fee": "<decimal>",
+///   "contract": "<hex>", "input": "<hex>"}`, where all fields but `version` and
+///   `recipient` are optional and the fee is in bridged tokens. When `contract` is set the
+///   recipient calls it with `input` right after the tokens are minted, so the recipient
+///   must be the address of the NEAR account sending the tokens.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct FtOnTransferMessage {
+    pub recipient: RawAddress,
+    pub fee: RelayerFee,
+    pub call: Option<DepositCall>,
+}
+
+/// Fee paid to the relayer of a deposit of bridged tokens.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RelayerFee {
+    /// ETH transferred from the recipient to the relayer, as in the legacy format.
+    Eth(Wei),
+    /// Bridged tokens minted to the relayer out of the deposited amount.
+    Tokens(U256),
+}
+
+/// Call made after the tokens of a deposit are minted.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DepositCall {
+    pub contract: RawAddress,
+    pub input: Vec<u8>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ParseFtOnTransferMessageError {
+    InvalidJson,
+    UnsupportedVersion,
+    InvalidRecipient,
+    InvalidFee,
+    InvalidCall,
+}
+
+impl AsRef<[u8]> for ParseFtOnTransferMessageError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::InvalidJson => b"ERR_INVALID_MSG_JSON",
+            Self::UnsupportedVersion => b"ERR_UNSUPPORTED_MSG_VERSION",
+            Self::InvalidRecipient => b"ERR_INVALID_RECIPIENT",
+            Self::InvalidFee => b"ERR_INVALID_FEE",
+            Self::InvalidCall => b"ERR_INVALID_DEPOSIT_CALL",
+        }
+    }
+}
+
+impl FtOnTransferMessage {
+    pub fn parse(msg: &str) -> Result<Self, ParseFtOnTransferMessageError> {
+        if msg.starts_with('{') {
+            Self::parse_json(msg)
+        } else {
+            Self::parse_legacy(msg.as_bytes())
+        }
+    }
+
+    fn parse_legacy(msg: &[u8]) -> Result<Self, ParseFtOnTransferMessageError> {
+        if msg.len() < 40 {
+            return Err(ParseFtOnTransferMessageError::InvalidRecipient);
+        }
+        let recipient =
+            parse_hex_address(&msg[..40]).ok_or(ParseFtOnTransferMessageError::InvalidRecipient)?;
+        let fee = match &msg[40..] {
+            [] => U256::zero(),
+            fee if fee.len() == 64 => hex::decode(fee)
+                .map(|fee| U256::from_big_endian(&fee))
+                .map_err(|_| ParseFtOnTransferMessageError::InvalidFee)?,
+            _ => return Err(ParseFtOnTransferMessageError::InvalidFee),
+        };
+
+        Ok(Self {
+            recipient,
+            fee: RelayerFee::Eth(Wei::new(fee)),
+            call: None,
+        })
+    }
+
+    fn parse_json(msg: &str) -> Result<Self, ParseFtOnTransferMessageError> {
+        let json = crate::json::parse_json(msg.as_bytes())
+            .ok_or(ParseFtOnTransferMessageError::InvalidJson)?;
+        match json.u64("version") {
+            Ok(FT_ON_TRANSFER_MESSAGE_VERSION) => (),
+            _ => return Err(ParseFtOnTransferMessageError::UnsupportedVersion),
+        }
+
+        let recipient = json
+            .string("recipient")
+            .ok()
+            .and_then(|recipient| parse_hex_address(recipient.as_bytes()))
+            .ok_or(ParseFtOnTransferMessageError::InvalidRecipient)?;
+        let fee = match json.string("fee") {
+            Ok(fee) => {
+                U256::from_dec_str(&fee).map_err(|_| ParseFtOnTransferMessageError::InvalidFee)?
+            }
+            Err(JsonError::MissingValue) => U256::zero(),
+            Err(_) => return Err(ParseFtOnTransferMessageError::InvalidFee),
+        };
+        let input = match json.string("input") {
+            Ok(input) => Some(
+                hex::decode(input.strip_prefix("0x").unwrap_or(&input))
+                    .map_err(|_| ParseFtOnTransferMessageError::InvalidCall)?,
+            ),
+            Err(JsonError::MissingValue) => None,
+            Err(_) => return Err(ParseFtOnTransferMessageError::InvalidCall),
+        };
+        let call = match (json.string("contract"), input) {
+            (Ok(contract), input) => Some(DepositCall {
+                contract: parse_hex_address(contract.as_bytes())
+                    .ok_or(ParseFtOnTransferMessageError::InvalidCall)?,
+                input: input.unwrap_or_default(),
+            }),
+            (Err(JsonError::MissingValue), None) => None,
+            _ => return Err(ParseFtOnTransferMessageError::InvalidCall),
+        };
+
+        Ok(Self {
+            recipient,
+            fee: RelayerFee::Tokens(fee),
+            call,
+        })
+    }
+}
+
+/// Parses an address given in hex, with or without the `0x` prefix.
+fn parse_hex_address(hex_address: &[u8]) -> Option<RawAddress> {
+    let hex_address = hex_address.strip_prefix(b"0x").unwrap_or(hex_address);
+    if hex_address.len() != 40 {
+        return None;
+    }
+    let mut address = [0u8; 20];
+    hex::decode_to_slice(hex_address, &mut address).ok()?;
+    Some(address)
+}
+
 /// Eth-connector deposit arguments
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct DepositCallArgs {
@@ -275,6 +574,175 @@ pub struct DepositCallArgs {
     pub relayer_eth_account: Option<EthAddress>,
 }
 
+/// Eth-connector batch deposit arguments
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DepositBatchCallArgs {
+    /// Proofs of the deposits
+    pub proofs: Vec<Proof>,
+}
+
+/// A deposit of a batch, as passed to `finish_deposit_batch`
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum BatchedDeposit {
+    /// The proof is being verified, and the deposit is finished if it is valid
+    Verifying(FinishDepositCallArgs),
+    /// The proof was rejected before verification, with the error
+    Rejected(String),
+}
+
+/// Finish batch deposit NEAR eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FinishDepositBatchCallArgs {
+    /// Deposits in the order of the proofs
+    pub deposits: Vec<BatchedDeposit>,
+}
+
+/// Outcome of a deposit of a batch, returned in the order of the proofs
+#[derive(BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq, Clone)]
+pub enum DepositBatchStatus {
+    Finished,
+    Failed(String),
+}
+
+/// How a bridge fee is computed from the bridged amount
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FeeRate {
+    /// The same fee whatever the amount
+    Flat(Fee),
+    /// A share of the amount, in basis points (1/10000)
+    BasisPoints(u16),
+}
+
+/// Fee schedule of a bridge operation
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FeeSchedule {
+    pub rate: FeeRate,
+    /// Lower bound of the fee
+    pub min: Fee,
+    /// Upper bound of the fee
+    pub max: Fee,
+}
+
+impl FeeSchedule {
+    /// Maximum rate in basis points, charging the whole amount
+    pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+    pub fn is_valid(&self) -> bool {
+        let rate_is_valid = match self.rate {
+            FeeRate::Flat(_) => true,
+            FeeRate::BasisPoints(basis_points) => basis_points <= Self::MAX_BASIS_POINTS,
+        };
+        rate_is_valid && self.min <= self.max
+    }
+
+    /// Returns the fee charged on the amount, which never exceeds the amount.
+    pub fn fee_for(&self, amount: Balance) -> Balance {
+        let fee = match self.rate {
+            FeeRate::Flat(fee) => fee.into_u128(),
+            FeeRate::BasisPoints(basis_points) => {
+                // Split the amount to not overflow
+                let basis_points = u128::from(basis_points);
+                let max_basis_points = u128::from(Self::MAX_BASIS_POINTS);
+                (amount / max_basis_points) * basis_points
+                    + (amount % max_basis_points) * basis_points / max_basis_points
+            }
+        };
+        fee.max(self.min.into_u128())
+            .min(self.max.into_u128())
+            .min(amount)
+    }
+}
+
+/// Fees of the eth-connector, charged in nETH. Fees are only charged while
+/// a fee collector is set.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct BridgeFees {
+    pub deposit: Option<FeeSchedule>,
+    pub withdraw: Option<FeeSchedule>,
+    pub ft_transfer: Option<FeeSchedule>,
+    /// Account which receives the fees
+    pub fee_collector: Option<AccountId>,
+}
+
+impl BridgeFees {
+    pub fn is_valid(&self) -> bool {
+        [self.deposit, self.withdraw, self.ft_transfer]
+            .iter()
+            .flatten()
+            .all(FeeSchedule::is_valid)
+    }
+
+    pub fn deposit_fee(&self, amount: Balance) -> Balance {
+        self.fee(self.deposit, amount)
+    }
+
+    pub fn withdraw_fee(&self, amount: Balance) -> Balance {
+        self.fee(self.withdraw, amount)
+    }
+
+    pub fn ft_transfer_fee(&self, amount: Balance) -> Balance {
+        self.fee(self.ft_transfer, amount)
+    }
+
+    fn fee(&self, schedule: Option<FeeSchedule>, amount: Balance) -> Balance {
+        match (schedule, &self.fee_collector) {
+            (Some(schedule), Some(_)) => schedule.fee_for(amount),
+            _ => 0,
+        }
+    }
+}
+
+/// set_bridge_fees eth-connector call args
+pub type SetBridgeFeesCallArgs = BridgeFees;
+
+/// finish_deposit_erc20 eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FinishDepositErc20CallArgs {
+    /// Aurora ERC-20 bridged from the locked Ethereum token
+    pub erc20: RawAddress,
+    pub recipient: RawAddress,
+    pub amount: RawU256,
+    pub proof_key: String,
+}
+
+/// register_bridged_erc20 eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RegisterBridgedErc20CallArgs {
+    /// ERC-20 on Ethereum locked in the ERC-20 locker
+    pub eth_token: EthAddress,
+    /// ERC-20 on Aurora minted for the locked tokens, with the engine as admin
+    pub erc20: RawAddress,
+}
+
+/// set_erc20_locker eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetErc20LockerCallArgs {
+    pub locker_address: EthAddress,
+}
+
+/// migrate_used_proofs eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MigrateUsedProofsCallArgs {
+    /// Keys of used proofs recorded before the compact registry
+    pub proof_keys: Vec<String>,
+}
+
+/// set_proof_watermark eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetProofWatermarkCallArgs {
+    /// Proofs of blocks below it are rejected, no watermark if `None`
+    pub min_block_number: Option<u64>,
+}
+
+/// Storage occupied by the registry of used proofs
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct UsedProofsStorage {
+    /// Number of used proofs in the compact registry
+    pub entries: u64,
+    /// Bytes of storage used by these entries, including the per-record overhead
+    pub bytes: u64,
+}
+
 /// Eth-connector isUsedProof arguments
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct IsUsedProofCallArgs {
@@ -357,6 +825,9 @@ pub struct InitCallArgs {
 /// Eth-connector Set contract data call args
 pub type SetContractDataCallArgs = InitCallArgs;
 
+/// set_eth_connector_metadata eth-connector call args
+pub type SetMetadataCallArgs = FungibleTokenMetadata;
+
 /// transfer eth-connector call args
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct TransferCallCallArgs {
@@ -476,11 +947,35 @@ pub struct RegisterRelayerCallArgs {
     pub address: EthAddress,
 }
 
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct PauseEthConnectorCallArgs {
     pub paused_mask: PausedMask,
 }
 
+/// Roles of the eth-connector, granted by the owner
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ConnectorRole {
+    /// Can pause flows with `set_paused_flags`
+    PauseManager,
+    /// Can unpause flows with `set_paused_flags`
+    UnpauseManager,
+    /// Can set the bridge fees
+    FeeManager,
+    /// Can set the nETH metadata with `set_eth_connector_metadata`. Changing the prover
+    /// account or the custodian address requires the `ContractDataManager` role.
+    MetadataManager,
+    /// Can set the prover account, the custodian address and the nETH metadata with
+    /// `set_eth_connector_contract_data`
+    ContractDataManager,
+}
+
+/// grant_role and revoke_role eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ConnectorRoleCallArgs {
+    pub role: ConnectorRole,
+    pub account_id: AccountId,
+}
+
 impl TryFrom<JsonValue> for ResolveTransferCallArgs {
     type Error = error::ParseTypeFromJsonError;
 
@@ -527,6 +1022,116 @@ pub mod error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prelude::u256_to_arr;
+
+    #[test]
+    fn test_ft_on_transfer_message() {
+        let recipient = [0x11; 20];
+        let legacy = hex::encode(recipient);
+        assert_eq!(
+            FtOnTransferMessage::parse(&legacy),
+            Ok(FtOnTransferMessage {
+                recipient,
+                fee: RelayerFee::Eth(Wei::zero()),
+                call: None,
+            })
+        );
+
+        let fee = U256::from(u64::MAX) + 1;
+        let legacy_with_fee = format!("{}{}", legacy, hex::encode(u256_to_arr(&fee)));
+        assert_eq!(
+            FtOnTransferMessage::parse(&legacy_with_fee).unwrap().fee,
+            RelayerFee::Eth(Wei::new(fee))
+        );
+
+        let json = format!(
+            r#"{{"version": 1, "recipient": "0x{}", "fee": "{}", "contract": "{}", "input": "0xdeadbeef"}}"#,
+            legacy,
+            fee,
+            hex::encode([0x22; 20]),
+        );
+        assert_eq!(
+            FtOnTransferMessage::parse(&json),
+            Ok(FtOnTransferMessage {
+                recipient,
+                fee: RelayerFee::Tokens(fee),
+                call: Some(DepositCall {
+                    contract: [0x22; 20],
+                    input: vec![0xde, 0xad, 0xbe, 0xef],
+                }),
+            })
+        );
+
+        let json = format!(r#"{{"version": 1, "recipient": "{}"}}"#, legacy);
+        assert_eq!(FtOnTransferMessage::parse(&json).unwrap().call, None);
+
+        let json = format!(r#"{{"version": 2, "recipient": "{}"}}"#, legacy);
+        assert_eq!(
+            FtOnTransferMessage::parse(&json),
+            Err(ParseFtOnTransferMessageError::UnsupportedVersion)
+        );
+        let json = format!(
+            r#"{{"version": 1, "recipient": "{}", "input": "00"}}"#,
+            legacy
+        );
+        assert_eq!(
+            FtOnTransferMessage::parse(&json),
+            Err(ParseFtOnTransferMessageError::InvalidCall)
+        );
+        assert_eq!(
+            FtOnTransferMessage::parse(&legacy[..39]),
+            Err(ParseFtOnTransferMessageError::InvalidRecipient)
+        );
+        assert_eq!(
+            FtOnTransferMessage::parse(&format!("{}00", legacy)),
+            Err(ParseFtOnTransferMessageError::InvalidFee)
+        );
+    }
+
+    #[test]
+    fn test_bridge_fees() {
+        let basis_points = FeeSchedule {
+            rate: FeeRate::BasisPoints(30),
+            min: Fee::new(10),
+            max: Fee::new(1_000),
+        };
+        assert_eq!(basis_points.fee_for(100_000), 300);
+        assert_eq!(basis_points.fee_for(1_000), 10);
+        assert_eq!(basis_points.fee_for(5), 5);
+        assert_eq!(basis_points.fee_for(u128::MAX), 1_000);
+        let flat = FeeSchedule {
+            rate: FeeRate::Flat(Fee::new(50)),
+            min: Fee::new(0),
+            max: Fee::new(u128::MAX),
+        };
+        assert_eq!(flat.fee_for(1_000), 50);
+
+        let mut fees = BridgeFees {
+            deposit: Some(basis_points),
+            withdraw: Some(flat),
+            ft_transfer: None,
+            fee_collector: None,
+        };
+        assert!(fees.is_valid());
+        // Nothing is charged without a fee collector
+        assert_eq!(fees.deposit_fee(100_000), 0);
+        fees.fee_collector = Some("fees.near".parse().unwrap());
+        assert_eq!(fees.deposit_fee(100_000), 300);
+        assert_eq!(fees.withdraw_fee(100_000), 50);
+        assert_eq!(fees.ft_transfer_fee(100_000), 0);
+
+        fees.ft_transfer = Some(FeeSchedule {
+            rate: FeeRate::BasisPoints(FeeSchedule::MAX_BASIS_POINTS + 1),
+            ..flat
+        });
+        assert!(!fees.is_valid());
+        fees.ft_transfer = Some(FeeSchedule {
+            min: Fee::new(2),
+            max: Fee::new(1),
+            ..flat
+        });
+        assert!(!fees.is_valid());
+    }
 
     #[test]
     fn test_view_call_fail() {
@@ -589,4 +1194,73 @@ mod tests {
         let parsed_data = CallArgs::deserialize(&input_bytes);
         assert_eq!(parsed_data, None);
     }
+
+    #[test]
+    fn test_erc20_metadata_from_json() {
+        let json = crate::json::parse_json(
+            br#"{"spec": "ft-1.0.0", "name": "Wrapped NEAR", "symbol": "wNEAR", "icon": null, "reference": null, "reference_hash": null, "decimals": 24}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Erc20Metadata::try_from(json).ok(),
+            Some(Erc20Metadata {
+                name: "Wrapped NEAR".to_string(),
+                symbol: "wNEAR".to_string(),
+                decimals: 24,
+            })
+        );
+
+        let json =
+            crate::json::parse_json(br#"{"name": "A", "symbol": "A", "decimals": 256}"#).unwrap();
+        assert!(Erc20Metadata::try_from(json).is_err());
+
+        let json = crate::json::parse_json(br#"{"name": "A", "decimals": 18}"#).unwrap();
+        assert!(Erc20Metadata::try_from(json).is_err());
+    }
+
+    #[test]
+    fn test_ft_on_transfer_args_to_json() {
+        let args = NEP141FtOnTransferArgs {
+            sender_id: "alice.near".parse().unwrap(),
+            amount: 10,
+            msg: "say \"hi\"\n\u{1}".to_string(),
+        };
+        assert_eq!(
+            String::from(args),
+            r#"{"sender_id": "alice.near", "amount": "10", "msg": "say \"hi\"\n\u0001"}"#
+        );
+    }
+
+    #[test]
+    fn test_nft_token_from_json() {
+        let token =
+            |json: &str| NftToken::try_from(crate::json::parse_json(json.as_bytes()).unwrap()).ok();
+
+        assert_eq!(
+            token(
+                r#"{"token_id": "1", "owner_id": "aurora", "metadata": {"title": null, "media": "https://example.com/1.png", "reference": "https://example.com/1.json"}}"#
+            ),
+            Some(NftToken {
+                token_id: "1".to_string(),
+                token_uri: "https://example.com/1.json".to_string(),
+            })
+        );
+        assert_eq!(
+            token(
+                r#"{"token_id": "1", "owner_id": "aurora", "metadata": {"media": "https://example.com/1.png", "reference": null}}"#
+            ),
+            Some(NftToken {
+                token_id: "1".to_string(),
+                token_uri: "https://example.com/1.png".to_string(),
+            })
+        );
+        assert_eq!(
+            token(r#"{"token_id": "1", "owner_id": "aurora", "metadata": null}"#),
+            Some(NftToken {
+                token_id: "1".to_string(),
+                token_uri: String::new(),
+            })
+        );
+        assert_eq!(token(r#"{"owner_id": "aurora"}"#), None);
+    }
 }
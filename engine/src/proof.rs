@@ -22,4 +22,32 @@ impl Proof {
             .map(|n| n.to_string())
             .collect()
     }
+
+    /// Number of the Ethereum block of the proof, read from the block header.
+    pub fn block_number(&self) -> Option<u64> {
+        rlp::Rlp::new(&self.header_data).val_at(8).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_number() {
+        let mut header = rlp::RlpStream::new_list(15);
+        for _ in 0..8 {
+            header.append_empty_data();
+        }
+        header.append(&0x00d1_5f4bu64);
+        for _ in 9..15 {
+            header.append_empty_data();
+        }
+        let proof = Proof {
+            header_data: header.out().to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(proof.block_number(), Some(0x00d1_5f4b));
+        assert_eq!(Proof::default().block_number(), None);
+    }
 }
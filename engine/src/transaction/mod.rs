@@ -4,6 +4,8 @@ use rlp::{Decodable, DecoderError, Rlp};
 pub mod eip_1559;
 pub mod eip_2930;
 pub mod legacy;
+#[cfg(feature = "std")]
+pub mod signing;
 
 use aurora_engine_types::types::Wei;
 use eip_2930::AccessTuple;
@@ -61,6 +63,7 @@ impl From<EthTransactionKind> for Vec<u8> {
 
 /// A normalized Ethereum transaction which can be created from older
 /// transactions.
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct NormalizedEthTransaction {
     pub address: Option<Address>,
     pub chain_id: Option<u64>,
@@ -0,0 +1,397 @@
+//! Building, signing and encoding of Ethereum transactions, so relayers and tooling can
+//! create the transactions the engine accepts.
+//!
+//! E.g. the bytes to `submit` for an EIP-1559 transaction:
+//! `Vec::<u8>::from(UnsignedTransaction::Eip1559(transaction).sign(&secret_key))`.
+
+use super::eip_1559::{self, SignedTransaction1559, Transaction1559};
+use super::eip_2930::{self, SignedTransaction2930, Transaction2930};
+use super::legacy::{LegacyEthSignedTransaction, TransactionLegacy};
+use super::{EthTransactionKind, NormalizedEthTransaction};
+use crate::prelude::{sdk, Address, H256, U256};
+use rlp::RlpStream;
+
+pub use secp256k1::SecretKey;
+
+/// A transaction of any kind, ready to be signed
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum UnsignedTransaction {
+    /// Legacy transaction, replay protected (EIP-155) if it has a chain ID
+    Legacy {
+        transaction: TransactionLegacy,
+        chain_id: Option<u64>,
+    },
+    Eip2930(Transaction2930),
+    Eip1559(Transaction1559),
+}
+
+/// Type of an Ethereum transaction (see https://eips.ethereum.org/EIPS/eip-2718), which a
+/// normalized transaction doesn't keep
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TransactionType {
+    Legacy,
+    Eip2930,
+    Eip1559,
+}
+
+impl From<&EthTransactionKind> for TransactionType {
+    fn from(tx: &EthTransactionKind) -> Self {
+        match tx {
+            EthTransactionKind::Legacy(_) => Self::Legacy,
+            EthTransactionKind::Eip2930(_) => Self::Eip2930,
+            EthTransactionKind::Eip1559(_) => Self::Eip1559,
+        }
+    }
+}
+
+impl UnsignedTransaction {
+    /// Makes a transaction of the given type from the fields of the normalized transaction,
+    /// or fails if the type can't hold them. The `address` is left out, as the sender is
+    /// given by the signature.
+    pub fn from_normalized(
+        tx: NormalizedEthTransaction,
+        transaction_type: TransactionType,
+    ) -> Result<Self, UnsignedTransactionError> {
+        let single_gas_price = tx.max_priority_fee_per_gas == tx.max_fee_per_gas;
+        if transaction_type != TransactionType::Eip1559 && !single_gas_price {
+            return Err(UnsignedTransactionError::PriorityFeeNotSupported);
+        }
+
+        match transaction_type {
+            TransactionType::Legacy if !tx.access_list.is_empty() => {
+                Err(UnsignedTransactionError::AccessListNotSupported)
+            }
+            TransactionType::Legacy => Ok(Self::Legacy {
+                chain_id: tx.chain_id,
+                transaction: legacy_transaction(tx),
+            }),
+            TransactionType::Eip2930 => Ok(Self::Eip2930(Transaction2930 {
+                chain_id: tx
+                    .chain_id
+                    .ok_or(UnsignedTransactionError::MissingChainId)?,
+                nonce: tx.nonce,
+                gas_price: tx.max_fee_per_gas,
+                gas_limit: tx.gas_limit,
+                to: tx.to,
+                value: tx.value,
+                data: tx.data,
+                access_list: tx.access_list,
+            })),
+            TransactionType::Eip1559 => Ok(Self::Eip1559(Transaction1559 {
+                chain_id: tx
+                    .chain_id
+                    .ok_or(UnsignedTransactionError::MissingChainId)?,
+                nonce: tx.nonce,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                gas_limit: tx.gas_limit,
+                to: tx.to,
+                value: tx.value,
+                data: tx.data,
+                access_list: tx.access_list,
+            })),
+        }
+    }
+
+    /// Hash of the transaction which the sender signs
+    pub fn signing_hash(&self) -> H256 {
+        let mut rlp_stream = RlpStream::new();
+        match self {
+            Self::Legacy {
+                transaction,
+                chain_id,
+            } => transaction.rlp_append_unsigned(&mut rlp_stream, *chain_id),
+            Self::Eip2930(transaction) => {
+                rlp_stream.append(&eip_2930::TYPE_BYTE);
+                transaction.rlp_append_unsigned(&mut rlp_stream);
+            }
+            Self::Eip1559(transaction) => {
+                rlp_stream.append(&eip_1559::TYPE_BYTE);
+                transaction.rlp_append_unsigned(&mut rlp_stream);
+            }
+        }
+        sdk::keccak(rlp_stream.as_raw())
+    }
+
+    /// Signs the transaction. The typed transaction envelope of the result is given by
+    /// `Vec::<u8>::from`.
+    pub fn sign(self, secret_key: &SecretKey) -> EthTransactionKind {
+        let message = secp256k1::Message::parse(&self.signing_hash().0);
+        let (signature, recovery_id) = secp256k1::sign(&message, secret_key);
+        let r = U256::from_big_endian(&signature.r.b32());
+        let s = U256::from_big_endian(&signature.s.b32());
+        let parity = recovery_id.serialize();
+
+        match self {
+            Self::Legacy {
+                transaction,
+                chain_id,
+            } => {
+                // See https://eips.ethereum.org/EIPS/eip-155#specification
+                let v = match chain_id {
+                    Some(chain_id) => u64::from(parity) + 2 * chain_id + 35,
+                    None => u64::from(parity) + 27,
+                };
+                EthTransactionKind::Legacy(LegacyEthSignedTransaction {
+                    transaction,
+                    v,
+                    r,
+                    s,
+                })
+            }
+            Self::Eip2930(transaction) => EthTransactionKind::Eip2930(SignedTransaction2930 {
+                transaction,
+                parity,
+                r,
+                s,
+            }),
+            Self::Eip1559(transaction) => EthTransactionKind::Eip1559(SignedTransaction1559 {
+                transaction,
+                parity,
+                r,
+                s,
+            }),
+        }
+    }
+}
+
+fn legacy_transaction(tx: NormalizedEthTransaction) -> TransactionLegacy {
+    TransactionLegacy {
+        nonce: tx.nonce,
+        gas_price: tx.max_fee_per_gas,
+        gas_limit: tx.gas_limit,
+        to: tx.to,
+        value: tx.value,
+        data: tx.data,
+    }
+}
+
+impl NormalizedEthTransaction {
+    /// Signs the normalized transaction as a transaction of the given type, see
+    /// `UnsignedTransaction::from_normalized`.
+    pub fn sign(
+        self,
+        transaction_type: TransactionType,
+        secret_key: &SecretKey,
+    ) -> Result<EthTransactionKind, UnsignedTransactionError> {
+        UnsignedTransaction::from_normalized(self, transaction_type).map(|tx| tx.sign(secret_key))
+    }
+}
+
+/// Address of the account of the given secret key
+pub fn address_from_secret_key(secret_key: &SecretKey) -> Address {
+    let public_key = secp256k1::PublicKey::from_secret_key(secret_key);
+    let hash = sdk::keccak(&public_key.serialize()[1..]);
+    Address::from_slice(&hash[12..])
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UnsignedTransactionError {
+    /// Typed transactions always have a chain ID.
+    MissingChainId,
+    /// Only EIP-2930 and EIP-1559 transactions have an access list.
+    AccessListNotSupported,
+    /// Only EIP-1559 transactions have a priority fee below the max fee.
+    PriorityFeeNotSupported,
+}
+
+impl AsRef<[u8]> for UnsignedTransactionError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::MissingChainId => b"ERR_MISSING_CHAIN_ID",
+            Self::AccessListNotSupported => b"ERR_ACCESS_LIST_NOT_SUPPORTED",
+            Self::PriorityFeeNotSupported => b"ERR_PRIORITY_FEE_NOT_SUPPORTED",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{TryFrom, Vec, Wei};
+    use crate::transaction::eip_2930::AccessTuple;
+    use rand::Rng;
+
+    const ITERATIONS: usize = 64;
+
+    #[test]
+    fn test_sign_round_trip() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..ITERATIONS {
+            let secret_key = SecretKey::random(&mut rng);
+            let unsigned = random_unsigned_transaction(&mut rng);
+            let signed = unsigned.clone().sign(&secret_key);
+
+            let bytes: Vec<u8> = signed.clone().into();
+            let decoded = EthTransactionKind::try_from(bytes.as_slice()).unwrap();
+            assert_eq!(decoded, signed);
+
+            let normalized = NormalizedEthTransaction::from(decoded);
+            assert_eq!(
+                normalized.address,
+                Some(address_from_secret_key(&secret_key))
+            );
+            if let UnsignedTransaction::Legacy { chain_id, .. } = unsigned {
+                assert_eq!(normalized.chain_id, chain_id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalized_round_trip() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..ITERATIONS {
+            let secret_key = SecretKey::random(&mut rng);
+            let signed = random_unsigned_transaction(&mut rng).sign(&secret_key);
+            let transaction_type = TransactionType::from(&signed);
+            let normalized = NormalizedEthTransaction::from(signed.clone());
+
+            // Signatures are deterministic, so the same transaction is signed again
+            assert_eq!(
+                normalized.sign(transaction_type, &secret_key).unwrap(),
+                signed
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalized_incompatible_type() {
+        let tx = NormalizedEthTransaction {
+            address: None,
+            chain_id: None,
+            nonce: U256::one(),
+            gas_limit: U256::from(21_000),
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(2),
+            to: Some(Address::repeat_byte(1)),
+            value: Wei::new_u64(3),
+            data: Vec::new(),
+            access_list: Vec::new(),
+        };
+        let convert = |tx: &NormalizedEthTransaction, transaction_type| {
+            UnsignedTransaction::from_normalized(tx.clone(), transaction_type)
+        };
+
+        assert!(matches!(
+            convert(&tx, TransactionType::Legacy),
+            Ok(UnsignedTransaction::Legacy { chain_id: None, .. })
+        ));
+        assert_eq!(
+            convert(&tx, TransactionType::Eip2930).unwrap_err(),
+            UnsignedTransactionError::MissingChainId
+        );
+        assert_eq!(
+            convert(&tx, TransactionType::Eip1559).unwrap_err(),
+            UnsignedTransactionError::MissingChainId
+        );
+
+        let tx = NormalizedEthTransaction {
+            chain_id: Some(1313161554),
+            access_list: vec![AccessTuple {
+                address: Address::repeat_byte(2),
+                storage_keys: vec![H256::repeat_byte(3)],
+            }],
+            ..tx
+        };
+        assert_eq!(
+            convert(&tx, TransactionType::Legacy).unwrap_err(),
+            UnsignedTransactionError::AccessListNotSupported
+        );
+        assert!(matches!(
+            convert(&tx, TransactionType::Eip2930),
+            Ok(UnsignedTransaction::Eip2930(_))
+        ));
+        assert!(matches!(
+            convert(&tx, TransactionType::Eip1559),
+            Ok(UnsignedTransaction::Eip1559(_))
+        ));
+
+        let tx = NormalizedEthTransaction {
+            max_priority_fee_per_gas: U256::one(),
+            access_list: Vec::new(),
+            ..tx
+        };
+        assert_eq!(
+            convert(&tx, TransactionType::Legacy).unwrap_err(),
+            UnsignedTransactionError::PriorityFeeNotSupported
+        );
+        assert_eq!(
+            convert(&tx, TransactionType::Eip2930).unwrap_err(),
+            UnsignedTransactionError::PriorityFeeNotSupported
+        );
+        assert!(matches!(
+            convert(&tx, TransactionType::Eip1559),
+            Ok(UnsignedTransaction::Eip1559(_))
+        ));
+    }
+
+    fn random_unsigned_transaction<R: Rng>(rng: &mut R) -> UnsignedTransaction {
+        let chain_id = rng.gen_range(1, u64::MAX / 4);
+        let nonce = random_u256(rng);
+        let gas_price = random_u256(rng);
+        let gas_limit = random_u256(rng);
+        let to = if rng.gen() {
+            Some(Address::from(rng.gen::<[u8; 20]>()))
+        } else {
+            None
+        };
+        let value = Wei::new(random_u256(rng));
+        let data: Vec<u8> = (0..rng.gen_range(0, 256)).map(|_| rng.gen()).collect();
+        let access_list = (0..rng.gen_range(0, 4))
+            .map(|_| AccessTuple {
+                address: Address::from(rng.gen::<[u8; 20]>()),
+                storage_keys: (0..rng.gen_range(0, 4))
+                    .map(|_| H256::from(rng.gen::<[u8; 32]>()))
+                    .collect(),
+            })
+            .collect();
+
+        match rng.gen_range(0, 3) {
+            0 => UnsignedTransaction::Legacy {
+                transaction: TransactionLegacy {
+                    nonce,
+                    gas_price,
+                    gas_limit,
+                    to,
+                    value,
+                    data,
+                },
+                chain_id: if rng.gen() { Some(chain_id) } else { None },
+            },
+            1 => UnsignedTransaction::Eip2930(Transaction2930 {
+                chain_id,
+                nonce,
+                gas_price,
+                gas_limit,
+                to,
+                value,
+                data,
+                access_list,
+            }),
+            _ => UnsignedTransaction::Eip1559(Transaction1559 {
+                chain_id,
+                nonce,
+                // The priority fee is at most the max fee, and equal to it for a fixed gas
+                // price
+                max_priority_fee_per_gas: if rng.gen() || gas_price.is_zero() {
+                    gas_price
+                } else {
+                    random_u256(rng) % gas_price
+                },
+                max_fee_per_gas: gas_price,
+                gas_limit,
+                to,
+                value,
+                data,
+                access_list,
+            }),
+        }
+    }
+
+    fn random_u256<R: Rng>(rng: &mut R) -> U256 {
+        // Mostly small values, as RLP strips leading zeros
+        let len = rng.gen_range(0, 33);
+        let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        U256::from_big_endian(&bytes)
+    }
+}
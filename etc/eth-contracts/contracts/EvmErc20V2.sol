@@ -48,12 +48,16 @@ contract EvmErc20V2 is ERC20, AdminControlled, IExit {
         _mint(account, amount);
     }
 
+    function burn(address account, uint256 amount) public onlyAdmin {
+        _burn(account, amount);
+    }
+
     function withdrawToNear(bytes memory recipient, uint256 amount) external override {
         address sender = _msgSender();
         _burn(sender, amount);
 
         bytes32 amount_b = bytes32(amount);
-        bytes memory input = abi.encodePacked("\x01", sender, amount_b, recipient);
+        bytes memory input = abi.encodePacked("\x02", sender, amount_b, recipient);
         uint input_size = 1 + 20 + 32 + recipient.length;
 
         assembly {
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: CC0-1.0
+pragma solidity ^0.8.0;
+
+import "@openzeppelin/contracts/token/ERC721/extensions/ERC721URIStorage.sol";
+import "./AdminControlled.sol";
+
+
+/**
+ * @title EvmErc721
+ * @dev ERC-721 token mapped from a native NEP-171 token. Tokens are minted by the admin
+ * when NFTs are deposited to Aurora and burned when they are withdrawn back to NEAR.
+ * The ERC-721 token id is the hash of the NEP-171 token id, which is kept to withdraw the token.
+ */
+contract EvmErc721 is ERC721URIStorage, AdminControlled {
+    string private _name;
+    string private _symbol;
+    mapping(uint256 => string) private _nearTokenIds;
+
+    constructor (string memory metadata_name, string memory metadata_symbol, address admin)
+        ERC721(metadata_name, metadata_symbol)
+        AdminControlled(admin, 0)
+    {
+        _name = metadata_name;
+        _symbol = metadata_symbol;
+    }
+
+    function name() public view override returns (string memory) {
+        return _name;
+    }
+
+    function symbol() public view override returns (string memory) {
+        return _symbol;
+    }
+
+    function setMetadata(string memory metadata_name, string memory metadata_symbol) external onlyAdmin {
+        _name = metadata_name;
+        _symbol = metadata_symbol;
+    }
+
+    function tokenIdOf(string memory near_token_id) public pure returns (uint256) {
+        return uint256(keccak256(bytes(near_token_id)));
+    }
+
+    function nearTokenIdOf(uint256 tokenId) public view returns (string memory) {
+        require(_exists(tokenId), "ERC721: nonexistent token");
+        return _nearTokenIds[tokenId];
+    }
+
+    function mint(address account, string memory near_token_id, string memory token_uri) public onlyAdmin {
+        uint256 tokenId = tokenIdOf(near_token_id);
+        _mint(account, tokenId);
+        _setTokenURI(tokenId, token_uri);
+        _nearTokenIds[tokenId] = near_token_id;
+    }
+
+    function withdrawToNear(bytes memory recipient, uint256 tokenId) external {
+        require(_isApprovedOrOwner(_msgSender(), tokenId), "ERC721: caller is not owner nor approved");
+        string memory near_token_id = _nearTokenIds[tokenId];
+        // The token is minted again to its owner if the transfer on NEAR fails
+        address owner = ownerOf(tokenId);
+        string memory token_uri = tokenURI(tokenId);
+        _burn(tokenId);
+        delete _nearTokenIds[tokenId];
+
+        bytes memory input = abi.encode(near_token_id, recipient, owner, token_uri);
+        uint input_size = input.length;
+
+        assembly {
+            let res := call(gas(), 0x4ef4bcbe1bc5c00c19ec753e362ac0756c01f43b, 0, add(input, 32), input_size, 0, 0)
+            if iszero(res) {
+                revert(0, 0)
+            }
+        }
+    }
+}
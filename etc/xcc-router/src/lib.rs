@@ -0,0 +1,110 @@
+//! Router of the cross-contract call precompile of the Aurora engine.
+//!
+//! The engine deploys a router to `<hex EVM address>.<engine account>` for each EVM contract
+//! which calls NEAR contracts. Promises are executed from the router account, so they never
+//! have the engine as the predecessor, and NEAR attached to them is paid by the router.
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::{
+    env, ext_contract, is_promise_success, near_bindgen, AccountId, Balance, Gas, PanicOnDefault,
+    Promise,
+};
+
+/// NEAR gas attached to `near_withdraw` of the wNEAR contract.
+const NEAR_WITHDRAW_GAS: Gas = 10_000_000_000_000;
+/// NEAR gas used by `forward`, on top of the gas attached to the promise.
+const FORWARD_GAS: Gas = 10_000_000_000_000;
+/// NEAR gas attached to `storage_deposit` of the wNEAR contract.
+const STORAGE_DEPOSIT_GAS: Gas = 10_000_000_000_000;
+/// Storage deposit of the router on the wNEAR contract.
+const WNEAR_STORAGE_DEPOSIT: Balance = 1_250_000_000_000_000_000_000;
+
+/// Borsh layout of `aurora_engine_types::parameters::PromiseCreateArgs`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PromiseCreateArgs {
+    pub target_account_id: AccountId,
+    pub method: String,
+    pub args: Vec<u8>,
+    pub attached_balance: Balance,
+    pub attached_gas: Gas,
+}
+
+#[ext_contract(ext_wnear)]
+trait WNear {
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
+    fn near_withdraw(&mut self, amount: U128);
+}
+
+#[ext_contract(ext_self)]
+trait SelfCallbacks {
+    fn forward(&mut self, #[serializer(borsh)] promise: PromiseCreateArgs) -> Promise;
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Router {
+    /// The engine, the only account which may schedule promises.
+    parent: AccountId,
+    /// The wNEAR contract unwrapped to pay the NEAR attached to promises, if any.
+    wnear: Option<AccountId>,
+}
+
+#[near_bindgen]
+impl Router {
+    /// Called by the engine when it creates the router. Registers the router with the
+    /// wNEAR contract so the EVM contract can transfer wNEAR to it.
+    #[init]
+    pub fn initialize(wnear_account_id: Option<ValidAccountId>) -> Self {
+        let parent = env::predecessor_account_id();
+        assert!(
+            env::current_account_id().ends_with(&format!(".{}", parent)),
+            "ERR_NOT_PARENT"
+        );
+        let wnear: Option<AccountId> = wnear_account_id.map(Into::into);
+        if let Some(wnear) = &wnear {
+            ext_wnear::storage_deposit(
+                Some(env::current_account_id()),
+                Some(true),
+                wnear,
+                WNEAR_STORAGE_DEPOSIT,
+                STORAGE_DEPOSIT_GAS,
+            );
+        }
+        Self { parent, wnear }
+    }
+
+    /// Executes a promise of the cross-contract call precompile and returns its result.
+    /// Attached NEAR is first unwrapped from the wNEAR held by the router.
+    pub fn execute(&mut self, #[serializer(borsh)] promise: PromiseCreateArgs) -> Promise {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.parent,
+            "ERR_NOT_ALLOWED"
+        );
+        if promise.attached_balance == 0 {
+            return Self::create(promise);
+        }
+        let wnear = self.wnear.as_ref().expect("ERR_NO_WNEAR");
+        let forward_gas = promise.attached_gas + FORWARD_GAS;
+        ext_wnear::near_withdraw(U128(promise.attached_balance), wnear, 1, NEAR_WITHDRAW_GAS).then(
+            ext_self::forward(promise, &env::current_account_id(), 0, forward_gas),
+        )
+    }
+
+    #[private]
+    pub fn forward(&mut self, #[serializer(borsh)] promise: PromiseCreateArgs) -> Promise {
+        assert!(is_promise_success(), "ERR_NEAR_WITHDRAW");
+        Self::create(promise)
+    }
+}
+
+impl Router {
+    fn create(promise: PromiseCreateArgs) -> Promise {
+        Promise::new(promise.target_account_id).function_call(
+            promise.method.into_bytes(),
+            promise.args,
+            promise.attached_balance,
+            promise.attached_gas,
+        )
+    }
+}